@@ -0,0 +1,32 @@
+#ifndef ANALYSIS_YOMIKAE_H
+#define ANALYSIS_YOMIKAE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/*
+ * 読み替え規定文を解析して、結果をJSON文字列で返す
+ * 成功した場合は{"Ok":[...]}、失敗した場合は{"Err":{...}}という形になる
+ * 解析中にpanicが起きた場合もC側には巻き戻さず、{"Err":{"InternalError":[...]}}を返す
+ * textがNULLか不正なUTF-8の場合はNULLを返す
+ * numは法律番号で、NULLでもよい
+ */
+char *yomikae_parse(const char *text, const char *num);
+
+/*
+ * 改め文を解析して、結果をJSON文字列で返す
+ * 引数と返り値の形はyomikae_parseと同じ
+ */
+char *yomikae_parse_kaiseibun(const char *text, const char *num);
+
+/*
+ * yomikae_parseなどが返した文字列を解放する
+ */
+void yomikae_free_string(char *ptr);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* ANALYSIS_YOMIKAE_H */
@@ -0,0 +1,106 @@
+//! [`proptest`]で使うための[`Arbitrary`]の実装
+//!
+//! `proptest` featureを有効にすると使えるようになる
+
+use crate::{LawFileInfo, LawInfo, YomikaeData, YomikaeError, YomikaeInfo};
+use jplaw_text::{Article, LawContents, LawText};
+use proptest::prelude::*;
+
+/// 鉤括弧を含まない、読み替え前後の単語らしい文字列
+fn word() -> impl Strategy<Value = String> {
+  "[一-龠ぁ-んァ-ヶ、。（）]{0,20}"
+}
+
+fn law_num() -> impl Strategy<Value = String> {
+  "(明治|大正|昭和|平成|令和)[一二三四五六七八九十]{1,3}年(法律|政令|省令)第[一二三四五六七八九十百]{1,4}号"
+}
+
+fn article() -> impl Strategy<Value = Article> {
+  (
+    "第[一二三四五六七八九十百]{1,3}条(の[二三四五])?",
+    proptest::option::of("[1-9]"),
+    proptest::option::of("[1-9]"),
+    proptest::option::of(Just("附則".to_string())),
+  )
+    .prop_map(
+      |(article, paragraph, item, suppl_provision_title)| Article {
+        article,
+        paragraph,
+        item,
+        sub_item: None,
+        suppl_provision_title,
+      },
+    )
+}
+
+fn law_info() -> impl Strategy<Value = LawInfo> {
+  (law_num(), article(), word()).prop_map(|(num, article, text)| {
+    let law_text = LawText {
+      article_info: article.clone(),
+      contents: LawContents::Text(text),
+    };
+    LawInfo::new(num, article, law_text)
+  })
+}
+
+fn law_file_info() -> impl Strategy<Value = LawFileInfo> {
+  (law_num(), "[0-9A-Z]{15}\\.xml").prop_map(|(num, file)| LawFileInfo { num, file })
+}
+
+impl Arbitrary for YomikaeInfo {
+  type Parameters = ();
+  type Strategy = BoxedStrategy<Self>;
+  fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+    (proptest::collection::vec(word(), 1..4), word())
+      .prop_map(|(before_words, after_word)| YomikaeInfo::new(before_words, after_word))
+      .boxed()
+  }
+}
+
+impl Arbitrary for YomikaeData {
+  type Parameters = ();
+  type Strategy = BoxedStrategy<Self>;
+  fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+    (
+      law_num(),
+      article(),
+      proptest::collection::vec(any::<YomikaeInfo>(), 0..5),
+    )
+      .prop_map(|(num, article, data)| YomikaeData::new(num, article, data))
+      .boxed()
+  }
+}
+
+impl Arbitrary for YomikaeError {
+  type Parameters = ();
+  type Strategy = BoxedStrategy<Self>;
+  fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+    prop_oneof![
+      law_info().prop_map(YomikaeError::ContentsOfTable),
+      law_info().prop_map(YomikaeError::UnmatchedParen),
+      law_info().prop_map(YomikaeError::UnexpectedParallelWords),
+      law_info().prop_map(YomikaeError::UnmatchedSorezoreWords),
+      law_info().prop_map(YomikaeError::NotFoundYomikae),
+      (law_file_info(), word()).prop_map(|(info, msg)| YomikaeError::ReadLawFile(info, msg)),
+      (law_file_info(), word()).prop_map(|(info, msg)| YomikaeError::ParseLawXml(info, msg)),
+      (law_info(), word()).prop_map(|(info, msg)| YomikaeError::InternalError(info, msg)),
+      (law_file_info(), word()).prop_map(|(info, msg)| YomikaeError::AnalyzeLaw(info, msg)),
+    ]
+    .boxed()
+  }
+}
+
+#[cfg(test)]
+proptest! {
+  #[test]
+  fn check_yomikae_data_json_roundtrip(yomikae_data in any::<YomikaeData>()) {
+    let json = serde_json::to_string(&yomikae_data).unwrap();
+    prop_assert_eq!(yomikae_data, serde_json::from_str::<YomikaeData>(&json).unwrap());
+  }
+
+  #[test]
+  fn check_yomikae_error_json_roundtrip(err in any::<YomikaeError>()) {
+    let json = serde_json::to_string(&err).unwrap();
+    prop_assert_eq!(err, serde_json::from_str::<YomikaeError>(&json).unwrap());
+  }
+}
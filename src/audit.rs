@@ -0,0 +1,214 @@
+//! 「と読み替える」を含む全ての文が、最終的にどう扱われたのかの記録
+//!
+//! 表で読み替えると書かれているのに表が見つからなかった文のように、
+//! 解析する前に取り除かれてエラーにも解析結果にも現れない文を見つけるために使う
+//! また、組み立て途中の状態を捨てる原因になった文字列を集計し、まだ扱えていない接続詞などを探すために使う
+
+use crate::error_key::error_kind;
+use crate::segment::split_sentences;
+use crate::{detect_table_style, reset_connectives, ParserConfig, YomikaeError};
+use jplaw_text::{Article, LawContents, LawText};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 読み替え規定文の最終的な扱い
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuditDisposition {
+  /// 文を解析して読み替えの組を取り出した
+  Parsed,
+  /// 文の後にある読み替えの表を解析して読み替えの組を取り出した
+  Table,
+  /// 解析せずに取り除いた（表で読み替えると書かれているのに表が見つからなかったなど）
+  Skipped,
+  /// 解析に失敗した、または読み替えの組が取り出せなかった
+  Error,
+}
+
+/// 「と読み替える」を含む一つの文の記録
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AuditRecord {
+  /// 法律番号
+  pub num: String,
+  /// その文がある条項
+  pub article: Article,
+  /// 文
+  pub sentence: String,
+  /// 最終的な扱い
+  pub disposition: AuditDisposition,
+  /// 取り出した読み替えの組の数
+  pub pair_count: usize,
+  /// 解析に失敗した場合のエラーの種類
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+  /// 組み立て途中の状態を捨てる原因になった、閉じ鉤括弧の後の文字列（[`reset_connectives`]）
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub reset_connectives: Vec<String>,
+}
+
+/// 一つの法令の中の、「と読み替える」を含む文の記録をためるもの
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AuditLog {
+  records: Vec<AuditRecord>,
+  /// 読み替えの表を使う文かどうか
+  is_table: Vec<bool>,
+}
+
+impl AuditLog {
+  /// [`crate::select_yomikae_law_text_with_config`]に渡す前の条文のリストから、`config`で読み替え規定文の候補となる文を全て集める
+  /// 全ての文は、解析結果が記録されるまでは[`AuditDisposition::Skipped`]として扱う
+  pub fn new(num: &str, law_text_lst: &[LawText], config: &ParserConfig) -> Self {
+    let mut audit_log = AuditLog::default();
+    for law_text in law_text_lst {
+      if let LawContents::Text(s) = &law_text.contents {
+        if config.is_candidate(s) {
+          audit_log.records.push(AuditRecord {
+            num: num.to_string(),
+            article: law_text.article_info.clone(),
+            sentence: s.clone(),
+            disposition: AuditDisposition::Skipped,
+            pair_count: 0,
+            error: None,
+            reset_connectives: split_sentences(s)
+              .iter()
+              .filter(|sentence| config.is_candidate(sentence))
+              .flat_map(|sentence| reset_connectives(sentence))
+              .collect(),
+          });
+          audit_log.is_table.push(detect_table_style(s).is_some());
+        }
+      }
+    }
+    audit_log
+  }
+
+  /// [`crate::parse_yomikae`]で解析した結果を記録する
+  /// 表の場合は、同じ条項にある表で読み替えると書かれた文の結果として記録する
+  /// 読み替えの組が一つも取り出せなかった場合はエラーとして扱う
+  pub fn record(&mut self, law_text: &LawText, res: Result<usize, &YomikaeError>) {
+    let index = self
+      .records
+      .iter()
+      .zip(self.is_table.iter())
+      .position(|(record, is_table)| {
+        record.disposition == AuditDisposition::Skipped
+          && record.article == law_text.article_info
+          && match &law_text.contents {
+            LawContents::Text(s) => !is_table && &record.sentence == s,
+            LawContents::Table(_) => *is_table,
+          }
+      });
+    let record = match index {
+      Some(index) => &mut self.records[index],
+      None => return,
+    };
+    match res {
+      Ok(0) => {
+        record.disposition = AuditDisposition::Error;
+        record.error = Some("not_found_yomikae".to_string());
+      }
+      Ok(pair_count) => {
+        record.disposition = if matches!(law_text.contents, LawContents::Table(_)) {
+          AuditDisposition::Table
+        } else {
+          AuditDisposition::Parsed
+        };
+        record.pair_count = pair_count;
+      }
+      Err(err) => {
+        record.disposition = AuditDisposition::Error;
+        record.error = Some(error_kind(err).to_string());
+      }
+    }
+  }
+
+  /// 記録を取り出す
+  pub fn into_records(self) -> Vec<AuditRecord> {
+    self.records
+  }
+}
+
+/// 組み立て途中の状態を捨てる原因になった文字列ごとの件数
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ResetTally {
+  /// 文字列ごとの件数
+  pub reset_connectives: BTreeMap<String, usize>,
+}
+
+impl ResetTally {
+  /// 記録に含まれる文字列を数える
+  pub fn add_records(&mut self, records: &[AuditRecord]) {
+    for text in records.iter().flat_map(|record| &record.reset_connectives) {
+      *self.reset_connectives.entry(text.clone()).or_default() += 1;
+    }
+  }
+
+  /// 件数の多い順に並べる（件数が同じ場合は文字列の順）
+  pub fn most_common(&self) -> Vec<(&str, usize)> {
+    let mut lst = self
+      .reset_connectives
+      .iter()
+      .map(|(text, count)| (text.as_str(), *count))
+      .collect::<Vec<_>>();
+    lst.sort_by(|(text1, count1), (text2, count2)| count2.cmp(count1).then(text1.cmp(text2)));
+    lst
+  }
+}
+
+#[test]
+fn check_audit_log() {
+  use crate::{test_utils, ArticleExt};
+  let other_article = test_utils::test_article().with_paragraph("2");
+  let law_text_lst = vec![
+    test_utils::law_text("前条の規定を準用する。"),
+    test_utils::law_text(test_utils::SENTENCE_SINGLE),
+    test_utils::law_text("「前項」と読み替えるものとする。"),
+    test_utils::law_text(test_utils::SENTENCE_TABLE),
+    test_utils::LawTextBuilder::text(test_utils::SENTENCE_TABLE)
+      .article(other_article.clone())
+      .build(),
+    test_utils::LawTextBuilder::table(&[&["第二条", "前条", "第一条"]])
+      .article(other_article)
+      .build(),
+  ];
+  let mut audit_log = AuditLog::new("test", &law_text_lst, &ParserConfig::default());
+  audit_log.record(&law_text_lst[1], Ok(1));
+  audit_log.record(&law_text_lst[2], Ok(0));
+  audit_log.record(&law_text_lst[5], Ok(1));
+  let dispositions = audit_log
+    .into_records()
+    .into_iter()
+    .map(|record| (record.disposition, record.error))
+    .collect::<Vec<_>>();
+  assert_eq!(
+    vec![
+      (AuditDisposition::Parsed, None),
+      (
+        AuditDisposition::Error,
+        Some("not_found_yomikae".to_string())
+      ),
+      (AuditDisposition::Skipped, None),
+      (AuditDisposition::Table, None),
+    ],
+    dispositions
+  );
+}
+
+#[test]
+fn check_reset_tally() {
+  use crate::test_utils;
+  let law_text_lst = vec![
+    test_utils::law_text(
+      "「前項」を「次項」と、「前条」を「次条」と読み替える。「市長」と定める。",
+    ),
+    test_utils::law_text("「前項」とあり次項と読み替える。"),
+  ];
+  let records = AuditLog::new("test", &law_text_lst, &ParserConfig::default()).into_records();
+  assert_eq!(
+    vec!["を".to_string(), "を".to_string()],
+    records[0].reset_connectives
+  );
+  let mut tally = ResetTally::default();
+  tally.add_records(&records);
+  assert_eq!(vec![("を", 2), ("とあり", 1)], tally.most_common());
+}
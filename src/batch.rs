@@ -0,0 +1,559 @@
+//! 法令XMLや条文のリストをまとめて解析するための非同期の関数
+//!
+//! `async` featureを有効にすると使えるようになる
+//! 一つの文を解析するだけなら、同期的な[`parse_yomikae`]を直接使えばよい
+//!
+//! [`analyze_laws`]を使うと、法令XMLファイルの読み込みから読み替え規定文と表の取り出し、解析までを
+//! 複数の法令について並行して行い、その結果をストリームとして受け取れる
+//! [`run_analysis`]を使うと、解析結果に加えて法令ごとの解析の始まりと終わりや警告もイベントとして受け取れるので、
+//! ログを読まずに解析の進み具合を表示できる
+//!
+//! どちらも`watch::Receiver<bool>`を受け取り、`true`が送られるとまだ始めていない法令は解析せずに終わる
+//! 解析中の法令は最後まで解析するので、途中までの結果もそのまま使える
+//! Ctrl-Cで止めたい場合は[`cancel_on_ctrl_c`]の受け取り側を渡せばよい
+
+use crate::pipeline::{
+  analyze_law_text_lst, panic_message, LawAnalysis, LawAnalysisOptions, LawSource,
+};
+use crate::ruby::strip_ruby_readings;
+use crate::schema::{
+  detect_document_type, label_unnumbered_articles, law_title, normalize_law_xml, DocumentType,
+  LawXmlSchema,
+};
+use crate::{
+  egov, read_law_revision, Lang, LawFileInfo, Message, ParserConfig, YomikaeData, YomikaeError,
+};
+use jplaw_text::{xml_to_law_text, LawText};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch, Semaphore};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_stream::{wrappers::ReceiverStream, Stream, StreamExt};
+use tracing::*;
+
+/// 一つの法令をまとめて解析した結果
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LawParseResult {
+  /// 読み替えの組が一つ以上取り出せた条項の解析結果
+  pub data: Vec<YomikaeData>,
+  /// 解析に失敗した条文のエラー
+  pub errors: Vec<YomikaeError>,
+  /// 解析は続けられたが、利用者に知らせるべきこと
+  pub messages: Vec<Message>,
+  /// 法令XMLが表す文書の種類（条文のリストを解析した場合は[`DocumentType::Misc`]）
+  pub document_type: DocumentType,
+}
+
+impl From<LawAnalysis> for LawParseResult {
+  fn from(law_analysis: LawAnalysis) -> Self {
+    let mut law_parse_result = LawParseResult {
+      messages: law_analysis.select_messages,
+      ..Default::default()
+    };
+    for article in law_analysis.articles {
+      law_parse_result.data.extend(article.data);
+      law_parse_result.errors.extend(article.errors);
+      law_parse_result.messages.extend(article.messages);
+    }
+    law_parse_result
+      .messages
+      .extend(law_analysis.conflict_messages);
+    law_parse_result
+  }
+}
+
+/// 法令の条文のリストから読み替え規定文と読み替えの表を取り出して、全て解析する
+pub async fn parse_law_text_lst(num: &str, law_text_lst: Vec<LawText>) -> LawParseResult {
+  parse_law_text_lst_with_config(num, law_text_lst, &ParserConfig::default()).await
+}
+
+/// [`parse_law_text_lst`]と同じように解析する
+/// 読み替え規定文の候補とする文言は`config`のものを使う
+pub async fn parse_law_text_lst_with_config(
+  num: &str,
+  law_text_lst: Vec<LawText>,
+  config: &ParserConfig,
+) -> LawParseResult {
+  let options = LawAnalysisOptions {
+    parser_config: config.clone(),
+    ..Default::default()
+  };
+  analyze_law_text_lst(law_text_lst, &LawSource::new(num), &options).into()
+}
+
+/// 法令XMLを読み込んで解析し、解析結果とエラーに`source`の情報を付ける
+/// `config.include_title`が`true`で`source`に法令名が無い場合は、法令XMLの法令名を使う
+async fn parse_law_xml_with_info(
+  law_file_info: LawFileInfo,
+  xml: &[u8],
+  mut source: LawSource,
+  config: &AnalyzeConfig,
+) -> Result<LawParseResult, YomikaeError> {
+  let document_type = detect_document_type(xml);
+  let (law_xml_schema, xml) = normalize_law_xml(xml);
+  let (xml, ruby_count) = strip_ruby_readings(&xml);
+  if config.include_title && source.title.is_none() {
+    source.title = law_title(&xml);
+  }
+  match xml_to_law_text(&xml).await {
+    Ok(law_text_lst) => {
+      let (law_text_lst, labeled_count) = label_unnumbered_articles(law_text_lst);
+      let mut law_parse_result =
+        LawParseResult::from(analyze_law_text_lst(law_text_lst, &source, &config.options));
+      law_parse_result.document_type = document_type;
+      if labeled_count > 0 {
+        let message = Message::UnnumberedArticlesLabeled(law_file_info.clone(), labeled_count);
+        law_parse_result.messages.insert(0, message);
+      }
+      if ruby_count > 0 {
+        let message = Message::RubyReadingsRemoved(law_file_info.clone(), ruby_count);
+        law_parse_result.messages.insert(0, message);
+      }
+      if let LawXmlSchema::Unknown(root) = law_xml_schema {
+        let message = Message::UnknownLawXmlSchema(law_file_info, root);
+        law_parse_result.messages.insert(0, message);
+      }
+      Ok(law_parse_result)
+    }
+    Err(e) => Err(YomikaeError::ParseLawXml(law_file_info, e.to_string())),
+  }
+}
+
+/// 法令XMLを読み込んで、その中の読み替え規定文と読み替えの表を全て解析する
+pub async fn parse_law_xml(num: &str, xml: &[u8]) -> Result<LawParseResult, YomikaeError> {
+  let law_file_info = LawFileInfo {
+    num: num.to_string(),
+    file: String::new(),
+  };
+  parse_law_xml_with_info(
+    law_file_info,
+    xml,
+    LawSource::new(num),
+    &AnalyzeConfig::default(),
+  )
+  .await
+}
+
+/// [`analyze_laws`]の設定
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct AnalyzeConfig {
+  /// 法令XMLファイルがあるフォルダ
+  pub work_dir: PathBuf,
+  /// 解析結果にe-Gov法令検索のURLを含めるかどうか
+  pub egov_url: bool,
+  /// 警告のメッセージの言語
+  pub lang: Lang,
+  /// 解析結果に法令の版の情報を含めるかどうか
+  pub revision: bool,
+  /// 解析結果とエラーに法令名を含めるかどうか
+  pub include_title: bool,
+  /// 法律番号ごとの法令名（`include_title`が`true`の場合に、法令XMLの法令名より優先して使う）
+  pub titles: BTreeMap<String, String>,
+  /// 解析のしかたの設定（CLIと同じ[`analyze_law_text_lst`]に渡す）
+  pub options: LawAnalysisOptions,
+}
+
+/// 一つの法令XMLファイルを読み込んで解析する
+/// 読み替えの組が取り出せた条項ごとの解析結果と、解析に失敗した条文のエラーを返す
+pub async fn analyze_law(
+  law_file_info: &LawFileInfo,
+  config: &AnalyzeConfig,
+) -> Vec<Result<YomikaeData, YomikaeError>> {
+  let (res_lst, messages) = analyze_law_with_messages(law_file_info, config).await;
+  for message in messages.iter() {
+    warn!("{}", message.to_message(config.lang));
+  }
+  res_lst
+}
+
+/// [`analyze_law`]と同じように解析し、警告のメッセージをログに書かずに返す
+async fn analyze_law_with_messages(
+  law_file_info: &LawFileInfo,
+  config: &AnalyzeConfig,
+) -> (Vec<Result<YomikaeData, YomikaeError>>, Vec<Message>) {
+  let num = &law_file_info.num;
+  let file_path = config.work_dir.join(&law_file_info.file);
+  let file_info = LawFileInfo {
+    num: num.clone(),
+    file: file_path.display().to_string(),
+  };
+  let buf = match tokio::fs::read(&file_path).await {
+    Ok(buf) => buf,
+    Err(e) => {
+      let err = YomikaeError::ReadLawFile(file_info, e.to_string());
+      return (vec![Err(err)], Vec::new());
+    }
+  };
+  let source = LawSource {
+    num: num.clone(),
+    file: Some(law_file_info.file.clone()),
+    law_id: egov::law_id_from_file(&law_file_info.file).filter(|_| config.egov_url),
+    revision: if config.revision {
+      Some(read_law_revision(&law_file_info.file, &buf))
+    } else {
+      None
+    },
+    title: config
+      .titles
+      .get(num)
+      .filter(|_| config.include_title)
+      .cloned(),
+  };
+  let law_parse_result = match parse_law_xml_with_info(file_info, &buf, source, config).await {
+    Ok(law_parse_result) => law_parse_result,
+    Err(err) => return (vec![Err(err)], Vec::new()),
+  };
+  let res_lst = law_parse_result
+    .data
+    .into_iter()
+    .map(Ok)
+    .chain(law_parse_result.errors.into_iter().map(Err))
+    .collect();
+  (res_lst, law_parse_result.messages)
+}
+
+/// [`analyze_law_with_messages`]を別のタスクで動かし、解析がpanicで止まった場合はその法令の
+/// [`YomikaeError::AnalyzeLaw`]にする
+async fn analyze_law_catch_panic(
+  law_file_info: LawFileInfo,
+  config: Arc<AnalyzeConfig>,
+) -> (Vec<Result<YomikaeData, YomikaeError>>, Vec<Message>) {
+  let task_law_file_info = law_file_info.clone();
+  let res =
+    tokio::spawn(async move { analyze_law_with_messages(&task_law_file_info, &config).await })
+      .await;
+  match res {
+    Ok(analysis) => analysis,
+    Err(join_error) => {
+      let message = if join_error.is_panic() {
+        panic_message(join_error.into_panic().as_ref())
+      } else {
+        join_error.to_string()
+      };
+      let err = YomikaeError::AnalyzeLaw(law_file_info, message);
+      (vec![Err(err)], Vec::new())
+    }
+  }
+}
+
+/// Ctrl-Cが押されると`true`になる受け取り側を返す
+///
+/// [`analyze_laws`]や[`run_analysis`]に渡すと、Ctrl-Cが押されたときにまだ始めていない法令を解析せずに終わる
+/// tokioのランタイムの中で呼び出す必要がある
+pub fn cancel_on_ctrl_c() -> watch::Receiver<bool> {
+  let (cancel_tx, cancel_rx) = watch::channel(false);
+  tokio::spawn(async move {
+    if tokio::signal::ctrl_c().await.is_ok() {
+      let _ = cancel_tx.send(true);
+    }
+  });
+  cancel_rx
+}
+
+/// 複数の法令XMLファイルを最大`concurrency`個まで並行して解析し、その結果を順に流すストリームを返す
+///
+/// 一つの法令の結果はまとめて流れるが、法令の間の順番は入力の順番とは限らない
+/// `cancel`に`true`が送られるか、ストリームを受け取る側が無くなると、まだ始めていない法令は解析しない
+/// tokioのランタイムの中で呼び出す必要がある
+pub fn analyze_laws<S>(
+  laws: S,
+  config: AnalyzeConfig,
+  concurrency: usize,
+  cancel: watch::Receiver<bool>,
+) -> impl Stream<Item = Result<YomikaeData, YomikaeError>>
+where
+  S: Stream<Item = LawFileInfo> + Send + Unpin + 'static,
+{
+  let concurrency = concurrency.max(1);
+  let (tx, rx) = mpsc::channel(concurrency);
+  let config = Arc::new(config);
+  tokio::spawn(async move {
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut laws = laws;
+    while let Some(law_file_info) = laws.next().await {
+      if *cancel.borrow() {
+        break;
+      }
+      let permit = match semaphore.clone().acquire_owned().await {
+        Ok(permit) => permit,
+        Err(_) => break,
+      };
+      // 空きを待っている間に止められたか、ストリームを受け取る側が無くなったら、残りの法令は解析しない
+      if *cancel.borrow() || tx.is_closed() {
+        break;
+      }
+      let tx = tx.clone();
+      let config = config.clone();
+      tokio::spawn(async move {
+        let lang = config.lang;
+        let (res_lst, messages) = analyze_law_catch_panic(law_file_info, config).await;
+        drop(permit);
+        for message in messages.iter() {
+          warn!("{}", message.to_message(lang));
+        }
+        for res in res_lst {
+          if tx.send(res).await.is_err() {
+            break;
+          }
+        }
+      });
+    }
+  });
+  ReceiverStream::new(rx)
+}
+
+/// [`run_analysis`]で解析の進み具合を知らせるイベント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+  /// 法令の解析を始めた
+  LawStarted(LawFileInfo),
+  /// 読み替えの組が取り出せた条項の解析結果
+  Data(YomikaeData),
+  /// 解析に失敗した条文のエラー
+  Error(YomikaeError),
+  /// 解析は続けられたが、利用者に知らせるべきこと
+  Warning(Message),
+  /// 法令の解析を終えた
+  LawFinished {
+    law: LawFileInfo,
+    /// その法令から取り出した読み替えの組の数
+    pair_count: usize,
+    /// その法令のエラーの数
+    error_count: usize,
+  },
+}
+
+/// [`run_analysis`]で全ての法令を解析し終えたとき、または途中で止めたときのまとめ
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct AnalysisSummary {
+  /// 解析した法令の数
+  pub law_count: usize,
+  /// 取り出した読み替えの組の数
+  pub pair_count: usize,
+  /// エラーの数
+  pub error_count: usize,
+  /// 警告の数
+  pub warning_count: usize,
+  /// `cancel`で止めたために、解析を始めなかった法令があるかどうか
+  pub is_cancelled: bool,
+  /// 解析を終えた法令（終えた順）
+  pub completed: Vec<LawFileInfo>,
+  /// 止めたために解析を始めなかった法令（入力の順）
+  pub not_started: Vec<LawFileInfo>,
+}
+
+/// 複数の法令XMLファイルを最大`concurrency`個まで並行して解析し、解析の進み具合をイベントのストリームで知らせる
+///
+/// 全ての法令を解析し終えると、返した[`JoinHandle`]がまとめを返す
+/// 一つの法令のイベントは[`ProgressEvent::LawStarted`]から[`ProgressEvent::LawFinished`]の順に流れるが、
+/// 複数の法令のイベントは混ざって流れる
+/// ストリームを受け取る側が無くなっても解析は続けるので、止めるには`cancel`に`true`を送る
+/// 止めた場合は解析中の法令を解析し終えてから、解析を終えた法令と始めなかった法令をまとめに入れて返す
+/// 法令の解析がpanicで止まった場合は、その法令の[`YomikaeError::AnalyzeLaw`]を[`ProgressEvent::Error`]で知らせる
+/// tokioのランタイムの中で呼び出す必要がある
+pub fn run_analysis(
+  laws: Vec<LawFileInfo>,
+  config: AnalyzeConfig,
+  concurrency: usize,
+  cancel: watch::Receiver<bool>,
+) -> (
+  JoinHandle<AnalysisSummary>,
+  impl Stream<Item = ProgressEvent>,
+) {
+  let concurrency = concurrency.max(1);
+  let (tx, rx) = mpsc::channel(concurrency);
+  let config = Arc::new(config);
+  let handle = tokio::spawn(async move {
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let mut join_set = JoinSet::new();
+    let mut not_started = Vec::new();
+    let mut laws = laws.into_iter();
+    for law_file_info in laws.by_ref() {
+      if *cancel.borrow() {
+        not_started.push(law_file_info);
+        break;
+      }
+      let permit = match semaphore.clone().acquire_owned().await {
+        Ok(permit) => permit,
+        Err(_) => break,
+      };
+      if *cancel.borrow() {
+        not_started.push(law_file_info);
+        break;
+      }
+      let tx = tx.clone();
+      let config = config.clone();
+      join_set.spawn(async move {
+        let _ = tx
+          .send(ProgressEvent::LawStarted(law_file_info.clone()))
+          .await;
+        let (res_lst, messages) = analyze_law_catch_panic(law_file_info.clone(), config).await;
+        drop(permit);
+        let mut summary = AnalysisSummary {
+          law_count: 1,
+          warning_count: messages.len(),
+          ..Default::default()
+        };
+        for message in messages {
+          let _ = tx.send(ProgressEvent::Warning(message)).await;
+        }
+        for res in res_lst {
+          let event = match res {
+            Ok(yomikae_data) => {
+              summary.pair_count += yomikae_data.data.len();
+              ProgressEvent::Data(yomikae_data)
+            }
+            Err(err) => {
+              summary.error_count += 1;
+              ProgressEvent::Error(err)
+            }
+          };
+          let _ = tx.send(event).await;
+        }
+        let event = ProgressEvent::LawFinished {
+          law: law_file_info.clone(),
+          pair_count: summary.pair_count,
+          error_count: summary.error_count,
+        };
+        let _ = tx.send(event).await;
+        summary.completed.push(law_file_info);
+        summary
+      });
+    }
+    not_started.extend(laws);
+    let mut summary = AnalysisSummary {
+      is_cancelled: !not_started.is_empty(),
+      not_started,
+      ..Default::default()
+    };
+    while let Some(res) = join_set.join_next().await {
+      match res {
+        Ok(law_summary) => {
+          summary.law_count += law_summary.law_count;
+          summary.pair_count += law_summary.pair_count;
+          summary.error_count += law_summary.error_count;
+          summary.warning_count += law_summary.warning_count;
+          summary.completed.extend(law_summary.completed);
+        }
+        // 解析のpanicは法令のエラーにしてあるので、ここに来るのはイベントを送る途中で止まった場合だけ
+        Err(join_error) => {
+          error!("{join_error}");
+          summary.error_count += 1;
+        }
+      }
+    }
+    summary
+  });
+  (handle, ReceiverStream::new(rx))
+}
+
+#[tokio::test]
+async fn check_parse_law_text_lst() {
+  use crate::{test_utils, TargetRef, YomikaeInfo};
+  let law_text_lst = vec![
+    test_utils::law_text("前条の規定を準用する。"),
+    test_utils::law_text(test_utils::SENTENCE_SINGLE),
+    test_utils::law_text(test_utils::SENTENCE_TABLE),
+  ];
+  let mut law_parse_result = parse_law_text_lst("test", law_text_lst).await;
+  // CLIと同じように、元の条文のハッシュ値が付く
+  for yomikae_data in law_parse_result.data.iter_mut() {
+    assert!(yomikae_data.content_hash.take().is_some());
+  }
+  assert_eq!(
+    vec![YomikaeData::new(
+      "test",
+      test_utils::test_article(),
+      vec![YomikaeInfo::new(
+        ["被後見人を代表する"],
+        "被保佐人を代表し、又は被保佐人がこれをすることに同意する"
+      )
+      .with_target(TargetRef::provision("第八百五十一条第四号"))]
+    )],
+    law_parse_result.data
+  );
+  assert!(law_parse_result.errors.is_empty());
+}
+
+#[tokio::test]
+async fn check_analyze_laws() {
+  let laws = tokio_stream::iter(vec![LawFileInfo {
+    num: "test".to_string(),
+    file: "not_found.xml".to_string(),
+  }]);
+  let config = AnalyzeConfig {
+    work_dir: std::env::temp_dir().join("analysis_yomikae_not_found"),
+    ..Default::default()
+  };
+  let (_cancel_tx, cancel_rx) = watch::channel(false);
+  let res_lst = analyze_laws(laws, config, 2, cancel_rx)
+    .collect::<Vec<_>>()
+    .await;
+  assert_eq!(1, res_lst.len());
+  assert!(matches!(res_lst[0], Err(YomikaeError::ReadLawFile(_, _))));
+}
+
+#[tokio::test]
+async fn check_run_analysis() {
+  let law_file_info = LawFileInfo {
+    num: "test".to_string(),
+    file: "not_found.xml".to_string(),
+  };
+  let config = AnalyzeConfig {
+    work_dir: std::env::temp_dir().join("analysis_yomikae_not_found"),
+    ..Default::default()
+  };
+  let (_cancel_tx, cancel_rx) = watch::channel(false);
+  let (handle, events) = run_analysis(vec![law_file_info.clone()], config, 2, cancel_rx);
+  let events = events.collect::<Vec<_>>().await;
+  assert_eq!(3, events.len());
+  assert_eq!(ProgressEvent::LawStarted(law_file_info.clone()), events[0]);
+  assert!(matches!(
+    events[1],
+    ProgressEvent::Error(YomikaeError::ReadLawFile(_, _))
+  ));
+  assert_eq!(
+    ProgressEvent::LawFinished {
+      law: law_file_info.clone(),
+      pair_count: 0,
+      error_count: 1,
+    },
+    events[2]
+  );
+  let summary = handle.await.unwrap();
+  assert_eq!(1, summary.law_count);
+  assert_eq!(1, summary.error_count);
+  assert!(!summary.is_cancelled);
+  assert_eq!(vec![law_file_info], summary.completed);
+}
+
+#[tokio::test]
+async fn check_run_analysis_cancelled() {
+  let laws = vec![
+    LawFileInfo {
+      num: "test1".to_string(),
+      file: "not_found1.xml".to_string(),
+    },
+    LawFileInfo {
+      num: "test2".to_string(),
+      file: "not_found2.xml".to_string(),
+    },
+  ];
+  let config = AnalyzeConfig {
+    work_dir: std::env::temp_dir().join("analysis_yomikae_not_found"),
+    ..Default::default()
+  };
+  let (cancel_tx, cancel_rx) = watch::channel(false);
+  cancel_tx.send(true).unwrap();
+  let (handle, events) = run_analysis(laws.clone(), config.clone(), 2, cancel_rx.clone());
+  assert!(events.collect::<Vec<_>>().await.is_empty());
+  let summary = handle.await.unwrap();
+  assert!(summary.is_cancelled);
+  assert!(summary.completed.is_empty());
+  assert_eq!(laws, summary.not_started);
+  let res_lst = analyze_laws(tokio_stream::iter(laws), config, 2, cancel_rx)
+    .collect::<Vec<_>>()
+    .await;
+  assert!(res_lst.is_empty());
+}
@@ -0,0 +1,545 @@
+//! 解析結果とエラーをターミナル上で見て回るためのTUI
+//!
+//! `tui` featureを有効にすると使えるようになる
+//!
+//! 法令の一覧→条項の一覧→読み替えの組の一覧の順にたどり、
+//! 元の文が分かる場合は取り出した語の箇所を強調して表示する
+
+use crate::{
+  article_to_string, cmp_article, Lang, LawInfo, YomikaeData, YomikaeError, YomikaeInfo,
+};
+use crossterm::{
+  event::{self, Event, KeyCode, KeyEventKind},
+  execute,
+  terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use jplaw_text::{xml_to_law_text, Article, LawContents};
+use ratatui::{
+  backend::CrosstermBackend,
+  layout::{Constraint, Direction, Layout},
+  style::{Modifier, Style},
+  text::{Line, Span},
+  widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+  Frame, Terminal,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 一つの条項の解析結果とエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticleEntry {
+  pub article: Article,
+  /// 読み替え前後の語の組
+  pub pairs: Vec<YomikaeInfo>,
+  /// この条項で起きたエラー
+  pub errors: Vec<YomikaeError>,
+  /// 元の文
+  pub source: Option<String>,
+}
+
+/// 一つの法令の解析結果とエラー
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LawEntry {
+  /// 法律番号
+  pub num: String,
+  pub articles: Vec<ArticleEntry>,
+  /// 法令ファイルの読み込みなど、条項に結び付かないエラー
+  pub errors: Vec<YomikaeError>,
+}
+
+impl LawEntry {
+  fn new(num: &str) -> Self {
+    LawEntry {
+      num: num.to_string(),
+      articles: Vec::new(),
+      errors: Vec::new(),
+    }
+  }
+
+  fn article_entry(&mut self, article: &Article) -> &mut ArticleEntry {
+    let index = match self.articles.iter().position(|a| &a.article == article) {
+      Some(index) => index,
+      None => {
+        self.articles.push(ArticleEntry {
+          article: article.clone(),
+          pairs: Vec::new(),
+          errors: Vec::new(),
+          source: None,
+        });
+        self.articles.len() - 1
+      }
+    };
+    &mut self.articles[index]
+  }
+}
+
+fn law_info_source(law_info: &LawInfo) -> Option<String> {
+  match &law_info.contents.contents {
+    LawContents::Text(s) => Some(s.clone()),
+    LawContents::Table(_) => None,
+  }
+}
+
+/// 解析結果とエラーを法令・条項ごとにまとめる
+pub fn group_results(
+  yomikae_data_lst: Vec<YomikaeData>,
+  error_lst: Vec<YomikaeError>,
+) -> Vec<LawEntry> {
+  let mut laws: BTreeMap<String, LawEntry> = BTreeMap::new();
+  for yomikae_data in yomikae_data_lst {
+    laws
+      .entry(yomikae_data.num.to_string())
+      .or_insert_with(|| LawEntry::new(&yomikae_data.num))
+      .article_entry(&yomikae_data.article)
+      .pairs
+      .extend(yomikae_data.data);
+  }
+  for err in error_lst {
+    match &err {
+      YomikaeError::ContentsOfTable(law_info)
+      | YomikaeError::UnmatchedParen(law_info)
+      | YomikaeError::UnexpectedParallelWords(law_info)
+      | YomikaeError::UnmatchedSorezoreWords(law_info)
+      | YomikaeError::NotFoundYomikae(law_info)
+      | YomikaeError::InternalError(law_info, _) => {
+        let article_entry = laws
+          .entry(law_info.num.to_string())
+          .or_insert_with(|| LawEntry::new(&law_info.num))
+          .article_entry(&law_info.article);
+        if article_entry.source.is_none() {
+          article_entry.source = law_info_source(law_info);
+        }
+        article_entry.errors.push(err.clone());
+      }
+      YomikaeError::ReadLawFile(law_file_info, _)
+      | YomikaeError::ParseLawXml(law_file_info, _)
+      | YomikaeError::AnalyzeLaw(law_file_info, _) => {
+        laws
+          .entry(law_file_info.num.clone())
+          .or_insert_with(|| LawEntry::new(&law_file_info.num))
+          .errors
+          .push(err.clone());
+      }
+    }
+  }
+  laws
+    .into_values()
+    .map(|mut law| {
+      law
+        .articles
+        .sort_by(|a, b| cmp_article(&a.article, &b.article));
+      law
+    })
+    .collect()
+}
+
+/// 文を、語のいずれかに当たる部分とそれ以外の部分とに分ける
+/// 当たる部分は`true`になる
+pub fn highlight(text: &str, words: &[&str]) -> Vec<(String, bool)> {
+  let mut is_highlighted = vec![false; text.len()];
+  for word in words.iter().filter(|w| !w.is_empty()) {
+    for (start, _) in text.match_indices(word) {
+      for b in is_highlighted[start..start + word.len()].iter_mut() {
+        *b = true;
+      }
+    }
+  }
+  let mut segments: Vec<(String, bool)> = Vec::new();
+  for (i, c) in text.char_indices() {
+    match segments.last_mut() {
+      Some((s, h)) if *h == is_highlighted[i] => s.push(c),
+      _ => segments.push((c.to_string(), is_highlighted[i])),
+    }
+  }
+  segments
+}
+
+/// 法令ファイルから元の文を読み込むための情報
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLoader {
+  /// 法令XMLファイル群が置かれている作業ディレクトリ
+  pub work_dir: PathBuf,
+  /// 法律番号から法令XMLファイル名への対応
+  pub files: HashMap<String, String>,
+}
+
+impl SourceLoader {
+  /// 法令の読み替え規定文を読み込んで、元の文が分かっていない条項に設定する
+  async fn load(&self, law: &mut LawEntry) {
+    let file = match self.files.get(&law.num) {
+      Some(file) => file,
+      None => return,
+    };
+    let buf = match tokio::fs::read(self.work_dir.join(file)).await {
+      Ok(buf) => buf,
+      Err(_) => return,
+    };
+    let law_text_lst = match xml_to_law_text(&buf).await {
+      Ok(law_text_lst) => law_text_lst,
+      Err(_) => return,
+    };
+    for law_text in law_text_lst {
+      if let LawContents::Text(s) = law_text.contents {
+        if !s.contains("と読み替える") {
+          continue;
+        }
+        if let Some(article_entry) = law
+          .articles
+          .iter_mut()
+          .find(|a| a.article == law_text.article_info && a.source.is_none())
+        {
+          article_entry.source = Some(s);
+        }
+      }
+    }
+  }
+}
+
+/// 今見ている一覧
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+  Law,
+  Article,
+  Pair,
+}
+
+struct App {
+  laws: Vec<LawEntry>,
+  lang: Lang,
+  level: Level,
+  law_state: ListState,
+  article_state: ListState,
+  pair_state: ListState,
+  /// 元の文を読み込み済みの法令
+  loaded: Vec<bool>,
+}
+
+impl App {
+  fn law(&self) -> Option<&LawEntry> {
+    self.law_state.selected().and_then(|i| self.laws.get(i))
+  }
+
+  fn article(&self) -> Option<&ArticleEntry> {
+    self
+      .law()
+      .zip(self.article_state.selected())
+      .and_then(|(law, i)| law.articles.get(i))
+  }
+
+  fn len(&self) -> usize {
+    match self.level {
+      Level::Law => self.laws.len(),
+      Level::Article => self.law().map_or(0, |law| law.articles.len()),
+      Level::Pair => self
+        .article()
+        .map_or(0, |article| article.pairs.len() + article.errors.len()),
+    }
+  }
+
+  fn state(&mut self) -> &mut ListState {
+    match self.level {
+      Level::Law => &mut self.law_state,
+      Level::Article => &mut self.article_state,
+      Level::Pair => &mut self.pair_state,
+    }
+  }
+
+  fn move_selection(&mut self, is_down: bool) {
+    let len = self.len();
+    let state = self.state();
+    let i = state.selected().unwrap_or(0);
+    let i = if is_down {
+      (i + 1).min(len.saturating_sub(1))
+    } else {
+      i.saturating_sub(1)
+    };
+    state.select(Some(i));
+  }
+
+  /// 一つ下の一覧に移る
+  /// 法令の一覧から条項の一覧に移るときは、その法令の元の文を読み込む必要があるかどうかを返す
+  fn enter(&mut self) -> bool {
+    let next = match self.level {
+      Level::Law => Level::Article,
+      Level::Article => Level::Pair,
+      Level::Pair => return false,
+    };
+    self.level = next;
+    if self.len() == 0 {
+      self.level = match next {
+        Level::Article => Level::Law,
+        _ => Level::Article,
+      };
+      return false;
+    }
+    self.state().select(Some(0));
+    next == Level::Article && matches!(self.law_state.selected(), Some(i) if !self.loaded[i])
+  }
+
+  fn back(&mut self) {
+    self.level = match self.level {
+      Level::Law | Level::Article => Level::Law,
+      Level::Pair => Level::Article,
+    };
+  }
+}
+
+fn list_items(app: &App) -> (String, Vec<ListItem<'static>>) {
+  match app.level {
+    Level::Law => (
+      "laws".to_string(),
+      app
+        .laws
+        .iter()
+        .map(|law| {
+          let pairs = law.articles.iter().map(|a| a.pairs.len()).sum::<usize>();
+          let errors =
+            law.errors.len() + law.articles.iter().map(|a| a.errors.len()).sum::<usize>();
+          ListItem::new(format!("{} ({pairs} / {errors})", law.num))
+        })
+        .collect(),
+    ),
+    Level::Article => (
+      app.law().map(|law| law.num.clone()).unwrap_or_default(),
+      app
+        .law()
+        .map(|law| {
+          law
+            .articles
+            .iter()
+            .map(|a| {
+              ListItem::new(format!(
+                "{} ({} / {})",
+                article_to_string(&a.article),
+                a.pairs.len(),
+                a.errors.len()
+              ))
+            })
+            .collect()
+        })
+        .unwrap_or_default(),
+    ),
+    Level::Pair => (
+      app
+        .article()
+        .map(|a| article_to_string(&a.article))
+        .unwrap_or_default(),
+      app
+        .article()
+        .map(|a| {
+          let pairs = a.pairs.iter().map(|p| ListItem::new(p.to_string()));
+          let errors = a
+            .errors
+            .iter()
+            .map(|e| ListItem::new(format!("[ERROR] {}", e.to_message(app.lang))));
+          pairs.chain(errors).collect()
+        })
+        .unwrap_or_default(),
+    ),
+  }
+}
+
+fn detail_lines(app: &App) -> Vec<Line<'static>> {
+  let highlighted = Style::default().add_modifier(Modifier::REVERSED);
+  let source_line = |source: &Option<String>, words: &[&str]| match source {
+    Some(source) => Line::from(
+      highlight(source, words)
+        .into_iter()
+        .map(|(s, h)| {
+          if h {
+            Span::styled(s, highlighted)
+          } else {
+            Span::raw(s)
+          }
+        })
+        .collect::<Vec<_>>(),
+    ),
+    None => Line::from("(source sentence not available)"),
+  };
+  match app.level {
+    Level::Law => app
+      .law()
+      .map(|law| {
+        let mut lines = vec![Line::from(law.num.clone())];
+        lines.extend(
+          law
+            .errors
+            .iter()
+            .map(|e| Line::from(format!("[ERROR] {}", e.to_message(app.lang)))),
+        );
+        lines
+      })
+      .unwrap_or_default(),
+    Level::Article => app
+      .article()
+      .map(|a| {
+        let words = a
+          .pairs
+          .iter()
+          .flat_map(|p| p.before_words.iter().chain(std::iter::once(&p.after_word)))
+          .map(|s| s.as_str())
+          .collect::<Vec<_>>();
+        let mut lines = vec![source_line(&a.source, &words), Line::from("")];
+        lines.extend(a.pairs.iter().map(|p| Line::from(p.to_string())));
+        lines
+      })
+      .unwrap_or_default(),
+    Level::Pair => app
+      .article()
+      .zip(app.pair_state.selected())
+      .map(|(a, i)| match a.pairs.get(i) {
+        Some(pair) => {
+          let words = pair
+            .before_words
+            .iter()
+            .chain(std::iter::once(&pair.after_word))
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>();
+          vec![
+            source_line(&a.source, &words),
+            Line::from(""),
+            Line::from(pair.to_string()),
+            Line::from(format!("{:?}", pair.operation)),
+          ]
+        }
+        None => {
+          let message = a
+            .errors
+            .get(i - a.pairs.len())
+            .map(|e| e.to_message(app.lang))
+            .unwrap_or_default();
+          vec![
+            source_line(&a.source, &[]),
+            Line::from(""),
+            Line::from(message),
+          ]
+        }
+      })
+      .unwrap_or_default(),
+  }
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+  let chunks = Layout::default()
+    .direction(Direction::Horizontal)
+    .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+    .split(frame.size());
+  let (title, items) = list_items(app);
+  let list = List::new(items)
+    .block(Block::default().borders(Borders::ALL).title(title))
+    .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+  let detail = Paragraph::new(detail_lines(app))
+    .wrap(Wrap { trim: false })
+    .block(
+      Block::default()
+        .borders(Borders::ALL)
+        .title("q: quit, Enter: open, Esc: back"),
+    );
+  frame.render_stateful_widget(list, chunks[0], app.state());
+  frame.render_widget(detail, chunks[1]);
+}
+
+/// TUIを起動し、`q`が押されるまで解析結果とエラーを表示する
+pub async fn run(laws: Vec<LawEntry>, loader: Option<SourceLoader>, lang: Lang) -> io::Result<()> {
+  let mut app = App {
+    loaded: vec![false; laws.len()],
+    laws,
+    lang,
+    level: Level::Law,
+    law_state: ListState::default().with_selected(Some(0)),
+    article_state: ListState::default(),
+    pair_state: ListState::default(),
+  };
+  enable_raw_mode()?;
+  execute!(io::stdout(), EnterAlternateScreen)?;
+  let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+  let res = async {
+    loop {
+      terminal.draw(|frame| draw(frame, &mut app))?;
+      if !event::poll(Duration::from_millis(200))? {
+        continue;
+      }
+      let key = match event::read()? {
+        Event::Key(key) if key.kind == KeyEventKind::Press => key,
+        _ => continue,
+      };
+      match key.code {
+        KeyCode::Char('q') => return Ok::<(), io::Error>(()),
+        KeyCode::Down | KeyCode::Char('j') => app.move_selection(true),
+        KeyCode::Up | KeyCode::Char('k') => app.move_selection(false),
+        KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+          if app.enter() {
+            if let (Some(loader), Some(i)) = (&loader, app.law_state.selected()) {
+              loader.load(&mut app.laws[i]).await;
+              app.loaded[i] = true;
+            }
+          }
+        }
+        KeyCode::Esc | KeyCode::Left | KeyCode::Char('h') => app.back(),
+        _ => (),
+      }
+    }
+  }
+  .await;
+  disable_raw_mode()?;
+  execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+  res
+}
+
+#[test]
+fn check_group_results() {
+  use crate::ArticleExt;
+  let law_info = LawInfo::new(
+    "a",
+    Article::article("2"),
+    crate::test_utils::law_text("「前条」とある"),
+  );
+  let laws = group_results(
+    vec![
+      YomikaeData::new(
+        "a",
+        Article::article("3"),
+        vec![YomikaeInfo::new(["前項"], "第二項")],
+      ),
+      YomikaeData::new(
+        "a",
+        Article::article("1"),
+        vec![YomikaeInfo::new(["前条"], "第一条")],
+      ),
+    ],
+    vec![YomikaeError::UnmatchedParen(law_info.clone())],
+  );
+  assert_eq!(1, laws.len());
+  assert_eq!(
+    vec!["1", "2", "3"],
+    laws[0]
+      .articles
+      .iter()
+      .map(|a| a.article.article.as_str())
+      .collect::<Vec<_>>()
+  );
+  assert_eq!(
+    Some("「前条」とある".to_string()),
+    laws[0].articles[1].source
+  );
+  assert_eq!(
+    vec![YomikaeError::UnmatchedParen(law_info)],
+    laws[0].articles[1].errors
+  );
+}
+
+#[test]
+fn check_highlight() {
+  assert_eq!(
+    vec![
+      ("「".to_string(), false),
+      ("前条".to_string(), true),
+      ("」とあるのは「".to_string(), false),
+      ("第一条".to_string(), true),
+      ("」".to_string(), false),
+    ],
+    highlight("「前条」とあるのは「第一条」", &["前条", "第一条"])
+  );
+}
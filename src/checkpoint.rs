@@ -0,0 +1,59 @@
+//! 長時間の解析を途中から再開するためのチェックポイント
+//!
+//! `async` featureを有効にすると使えるようになる
+//!
+//! 一定の数の法令を解析し終えるごとに、インデックス情報の中での位置と出力ファイルに書き出したバイト数を記録する
+//! 再開するときは出力ファイルをそのバイト数まで切り詰めてから、次の法令から解析を続ける
+
+use crate::sink::SinkOffsets;
+use serde::{Deserialize, Serialize};
+
+/// 解析を再開するための情報
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+  /// 次に解析する法令の、インデックス情報の中での位置（0始まり）
+  pub next_index: usize,
+  /// 最後に解析し終えた法令の法律番号
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub last_num: Option<String>,
+  /// それまでに出力ファイルに書き出したバイト数
+  pub offsets: SinkOffsets,
+}
+
+impl Checkpoint {
+  /// インデックス情報の`law_index`番目の法令を解析し終えたときに、チェックポイントを書くかどうか
+  /// `interval`が0の場合は書かない
+  pub fn is_due(law_index: usize, interval: usize) -> bool {
+    interval != 0 && (law_index + 1) % interval == 0
+  }
+
+  /// インデックス情報の法律番号の並びが、チェックポイントを書いたときと同じと見なせるかどうか
+  /// 再開する位置の一つ前の法令の法律番号が一致するかで判断する
+  pub fn matches_index(&self, nums: &[String]) -> bool {
+    match self.next_index.checked_sub(1) {
+      None => true,
+      Some(i) => nums.get(i) == self.last_num.as_ref(),
+    }
+  }
+}
+
+#[test]
+fn check_checkpoint() {
+  assert!(!Checkpoint::is_due(0, 2));
+  assert!(Checkpoint::is_due(1, 2));
+  assert!(!Checkpoint::is_due(1, 0));
+  let nums = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+  let checkpoint = Checkpoint {
+    next_index: 2,
+    last_num: Some("b".to_string()),
+    offsets: SinkOffsets::default(),
+  };
+  assert!(checkpoint.matches_index(&nums));
+  assert!(!checkpoint.matches_index(&nums[..1]));
+  let checkpoint = Checkpoint {
+    last_num: Some("c".to_string()),
+    ..checkpoint
+  };
+  assert!(!checkpoint.matches_index(&nums));
+  assert!(Checkpoint::default().matches_index(&[]));
+}
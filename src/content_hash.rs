@@ -0,0 +1,72 @@
+//! 元の条文の内容から、実行やスキーマのバージョンによらず変わらないハッシュ値を作る
+//!
+//! 空白の有無のような違いを無視するため、正規化してからXXH3の64bitのハッシュ値を取る
+
+use crate::{get_table_text, YomikaeData};
+use jplaw_text::{LawContents, LawText};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// 空白を全て取り除いて、ハッシュ値を取るための形にする
+pub fn normalize_sentence(s: &str) -> String {
+  s.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+/// 文字列のハッシュ値を16桁の16進数で表す
+fn hash_hex(s: &str) -> String {
+  format!("{:016x}", xxh3_64(s.as_bytes()))
+}
+
+/// 文を正規化したもののハッシュ値
+pub fn sentence_hash(s: &str) -> String {
+  hash_hex(&normalize_sentence(s))
+}
+
+/// 条文の内容のハッシュ値
+/// 表の場合は、正規化した各欄を「\t」で、各行を「\n」でつないだもののハッシュ値にする
+pub fn law_text_hash(law_text: &LawText) -> String {
+  match &law_text.contents {
+    LawContents::Text(s) => sentence_hash(s),
+    LawContents::Table(table) => {
+      let text = table
+        .iter()
+        .map(|row| {
+          row
+            .row
+            .iter()
+            .map(|column| normalize_sentence(&get_table_text(column)))
+            .collect::<Vec<_>>()
+            .join("\t")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+      hash_hex(&text)
+    }
+  }
+}
+
+impl YomikaeData {
+  /// 元の条文の内容のハッシュ値を記録する
+  pub fn with_content_hash(self, law_text: &LawText) -> Self {
+    YomikaeData {
+      content_hash: Some(law_text_hash(law_text)),
+      ..self
+    }
+  }
+}
+
+#[test]
+fn check_content_hash() {
+  use crate::test_utils;
+  assert_eq!(
+    sentence_hash("前条中「前項」とあるのは「第二項」と読み替える。"),
+    sentence_hash(" 前条中「前項」とあるのは\n「第二項」と読み替える。　")
+  );
+  assert_ne!(
+    sentence_hash("前条中「前項」とあるのは「第二項」と読み替える。"),
+    sentence_hash("前条中「前項」とあるのは「第三項」と読み替える。")
+  );
+  assert_eq!(16, sentence_hash("").len());
+  let table = test_utils::LawTextBuilder::table(&[&["第二条", "前条", "第一条"]]).build();
+  let other_table = test_utils::LawTextBuilder::table(&[&["第二条", "前条第一", "条"]]).build();
+  assert_ne!(law_text_hash(&table), law_text_hash(&other_table));
+}
@@ -0,0 +1,98 @@
+//! 「と読み替える」などの文言を含む法令のうち、解析結果が一つもない法令を調べる
+//!
+//! 解析器が取りこぼしている法令を見つけ、どこから直すべきかを決めるために使う
+
+use crate::{LawFileInfo, ParserConfig, YomikaeData};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// 法令XMLが`config`の読み替え規定文の候補とする文言（`trigger_phrases`）のどれかを含むかどうか
+/// XMLを解析せずにバイト列をそのまま探すので、ルビの読みは先に取り除いておく
+pub fn law_xml_has_yomikae_phrase(xml: &[u8], config: &ParserConfig) -> bool {
+  config.trigger_phrases.iter().any(|phrase| {
+    let phrase = phrase.as_bytes();
+    !phrase.is_empty() && xml.windows(phrase.len()).any(|w| w == phrase)
+  })
+}
+
+/// 文言を含む法令と、解析結果がある法令との比較
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct CoverageReport {
+  /// 調べた法令の数
+  pub total_laws: usize,
+  /// 読み替え規定であることを示す文言を含む法令の数
+  pub laws_with_phrase: usize,
+  /// そのうち解析結果が一つ以上ある法令の数
+  pub laws_with_output: usize,
+  /// 文言を含むのに解析結果が一つもない法令
+  pub missing: Vec<LawFileInfo>,
+}
+
+/// 法令ごとに文言を含むかどうかを調べた結果と解析結果を比べる
+pub fn coverage_report(
+  laws: &[(LawFileInfo, bool)],
+  yomikae_data_lst: &[YomikaeData],
+) -> CoverageReport {
+  let output_nums = yomikae_data_lst
+    .iter()
+    .filter(|yomikae_data| !yomikae_data.data.is_empty())
+    .map(|yomikae_data| yomikae_data.num.as_str())
+    .collect::<HashSet<_>>();
+  let mut report = CoverageReport {
+    total_laws: laws.len(),
+    ..Default::default()
+  };
+  for (law_file_info, has_phrase) in laws {
+    if !has_phrase {
+      continue;
+    }
+    report.laws_with_phrase += 1;
+    if output_nums.contains(law_file_info.num.as_str()) {
+      report.laws_with_output += 1;
+    } else {
+      report.missing.push(law_file_info.clone());
+    }
+  }
+  report
+}
+
+#[test]
+fn check_coverage_report() {
+  use crate::{test_utils, YomikaeInfo};
+  let config = ParserConfig::default();
+  assert!(law_xml_has_yomikae_phrase(
+    "<Sentence>「前項」とあるのは「次項」と読み替えるものとする。</Sentence>".as_bytes(),
+    &config
+  ));
+  assert!(!law_xml_has_yomikae_phrase(
+    "<Sentence>前条の規定を準用する。</Sentence>".as_bytes(),
+    &config
+  ));
+  assert!(law_xml_has_yomikae_phrase(
+    "<Sentence>前条の規定を準用する。</Sentence>".as_bytes(),
+    &ParserConfig::with_trigger_phrases(["を準用する"])
+  ));
+  let law_file_info = |num: &str| LawFileInfo {
+    num: num.to_string(),
+    file: format!("{num}.xml"),
+  };
+  let laws = vec![
+    (law_file_info("a"), true),
+    (law_file_info("b"), true),
+    (law_file_info("c"), false),
+  ];
+  let yomikae_data_lst = vec![YomikaeData::new(
+    "a",
+    test_utils::test_article(),
+    vec![YomikaeInfo::new(["前項"], "次項")],
+  )];
+  assert_eq!(
+    CoverageReport {
+      total_laws: 3,
+      laws_with_phrase: 2,
+      laws_with_output: 1,
+      missing: vec![law_file_info("b")],
+    },
+    coverage_report(&laws, &yomikae_data_lst)
+  );
+}
@@ -0,0 +1,130 @@
+//! 解析結果の集まりから、ある規定を読み替えている規定を逆に引く
+
+use crate::{LawNum, TargetRef, YomikaeData, YomikaeInfo};
+use jplaw_text::Article;
+use serde::{Deserialize, Serialize};
+
+/// 解析結果の集まり
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct YomikaeDataSet {
+  pub data: Vec<YomikaeData>,
+}
+
+/// ある規定を読み替えている規定と、その読み替えの組
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ReaderEntry {
+  /// 読み替えている法令の法律番号
+  pub num: LawNum,
+  /// 読み替えている条項
+  pub article: Article,
+  /// その規定を対象にしている読み替えの組
+  pub pairs: Vec<YomikaeInfo>,
+}
+
+/// 対象の規定が、指定した法令と条項に当たるかどうか
+///
+/// 対象に法令の名前が書かれている場合はその名前と、書かれていない場合は読み替えている法令の法律番号と`law`を比べる
+/// 条項は、対象の規定が`article`と同じか、`article`の後に「第二項」のような下位の規定が続くものを当たるとする
+/// 「同条」「前条」のような他の規定を指す書き方は解決できないので、当たらないものとする
+fn is_target_of(target: &TargetRef, num: &str, law: &str, article: &str) -> bool {
+  let is_same_law = match &target.law {
+    Some(target_law) => target_law == law,
+    None => num == law,
+  };
+  let is_same_article = match target.provision.strip_prefix(article) {
+    Some(rest) => rest.is_empty() || rest.starts_with('第'),
+    None => false,
+  };
+  is_same_law && is_same_article
+}
+
+impl YomikaeDataSet {
+  pub fn new(data: Vec<YomikaeData>) -> Self {
+    YomikaeDataSet { data }
+  }
+
+  /// 法令`law`の条項`article`（「第八百五十一条」など）を読み替えている規定を、解析結果の順に返す
+  ///
+  /// `law`は、読み替えの対象に書かれた法令の名前（「同法」「徴収法施行規則」など）か、
+  /// 対象に法令の名前が書かれていない場合の、読み替えている法令の法律番号
+  /// 読み替え前の語ごとに対象が書かれている場合は、その語の対象も見る
+  pub fn readers_of(&self, law: &str, article: &str) -> Vec<ReaderEntry> {
+    self
+      .data
+      .iter()
+      .filter_map(|yomikae_data| {
+        let is_target = |target: &Option<TargetRef>| {
+          target.as_ref().map_or(false, |target| {
+            is_target_of(target, &yomikae_data.num, law, article)
+          })
+        };
+        let pairs = yomikae_data
+          .data
+          .iter()
+          .filter(|yomikae_info| {
+            is_target(&yomikae_info.target)
+              || yomikae_info
+                .scoped_before_words
+                .iter()
+                .any(|scoped_word| is_target(&scoped_word.scope))
+          })
+          .cloned()
+          .collect::<Vec<_>>();
+        if pairs.is_empty() {
+          None
+        } else {
+          Some(ReaderEntry {
+            num: yomikae_data.num.clone(),
+            article: yomikae_data.article.clone(),
+            pairs,
+          })
+        }
+      })
+      .collect()
+  }
+}
+
+impl From<Vec<YomikaeData>> for YomikaeDataSet {
+  fn from(data: Vec<YomikaeData>) -> Self {
+    YomikaeDataSet::new(data)
+  }
+}
+
+#[test]
+fn check_readers_of() {
+  use crate::ArticleExt;
+  let dataset = YomikaeDataSet::new(vec![
+    YomikaeData::new(
+      "test",
+      Article::article("2"),
+      vec![
+        YomikaeInfo::new(["前項"], "次項")
+          .with_target(TargetRef::provision("第八百五十一条第四号")),
+        YomikaeInfo::new(["知事"], "市長").with_target(TargetRef::provision("第八百五十条")),
+      ],
+    ),
+    YomikaeData::new(
+      "other",
+      Article::article("3"),
+      vec![YomikaeInfo::new(["前項"], "次項")
+        .with_target(TargetRef::provision("第八百五十一条").with_law("民法"))],
+    ),
+    YomikaeData::new(
+      "test",
+      Article::article("4"),
+      vec![
+        YomikaeInfo::new(["前項"], "次項").with_target(TargetRef::provision("第八百五十一条の二"))
+      ],
+    ),
+  ]);
+  let readers = dataset.readers_of("test", "第八百五十一条");
+  assert_eq!(1, readers.len());
+  assert_eq!(Article::article("2"), readers[0].article);
+  assert_eq!(vec!["前項"], readers[0].pairs[0].before_words);
+  assert_eq!(1, readers[0].pairs.len());
+  let readers = dataset.readers_of("民法", "第八百五十一条");
+  assert_eq!(1, readers.len());
+  assert_eq!("other", readers[0].num);
+  assert!(dataset.readers_of("test", "第八百五十二条").is_empty());
+}
@@ -0,0 +1,133 @@
+//! 同じ法令の同じ版の中で全く同じ読み替えが繰り返されている場合にまとめる
+
+use crate::{LawNum, LawRevision, YomikaeData, YomikaeInfo};
+use jplaw_text::Article;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// まとめられた読み替えの情報
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct DuplicateEntry {
+  /// 法律番号
+  pub num: LawNum,
+  /// 法令の版の情報
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub revision: Option<LawRevision>,
+  /// 読み替え前後の語のリスト
+  pub data: Vec<YomikaeInfo>,
+  /// 同じ読み替えがあった条項のリスト（最初に出てきたものを含む）
+  pub articles: Vec<Article>,
+}
+
+/// 重複していた読み替えの一覧
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct DuplicateReport {
+  pub duplicates: Vec<DuplicateEntry>,
+}
+
+/// 同じ法令の同じ版の中で読み替え前後の語のリストが全く同じものを、最初に出てきたものだけを残してまとめる
+/// まとめられたものは、それらが書かれていた条項とともに[`DuplicateReport`]に記録される
+pub fn dedupe_yomikae(yomikae_data_lst: Vec<YomikaeData>) -> (Vec<YomikaeData>, DuplicateReport) {
+  let mut deduped_lst: Vec<YomikaeData> = Vec::new();
+  // (法律番号, 版, 読み替えのリスト) => (deduped_lstでの位置, 出てきた条項のリスト)
+  #[allow(clippy::type_complexity)]
+  let mut seen: HashMap<(LawNum, Option<LawRevision>, Vec<YomikaeInfo>), (usize, Vec<Article>)> =
+    HashMap::new();
+  for yomikae_data in yomikae_data_lst {
+    let key = (
+      yomikae_data.num.clone(),
+      yomikae_data.revision.clone(),
+      yomikae_data.data.clone(),
+    );
+    match seen.get_mut(&key) {
+      Some((_, articles)) => articles.push(yomikae_data.article),
+      None => {
+        seen.insert(key, (deduped_lst.len(), vec![yomikae_data.article.clone()]));
+        deduped_lst.push(yomikae_data);
+      }
+    }
+  }
+  let mut duplicates = seen
+    .into_iter()
+    .filter(|(_, (_, articles))| articles.len() >= 2)
+    .map(|((num, revision, data), (i, articles))| {
+      (
+        i,
+        DuplicateEntry {
+          num,
+          revision,
+          data,
+          articles,
+        },
+      )
+    })
+    .collect::<Vec<_>>();
+  duplicates.sort_by_key(|(i, _)| *i);
+  let report = DuplicateReport {
+    duplicates: duplicates.into_iter().map(|(_, entry)| entry).collect(),
+  };
+  (deduped_lst, report)
+}
+
+#[test]
+fn check_dedupe_yomikae() {
+  let article = |s: &str| Article {
+    article: s.to_string(),
+    paragraph: None,
+    item: None,
+    sub_item: None,
+    suppl_provision_title: Some("附則".to_string()),
+  };
+  let data = vec![YomikaeInfo {
+    before_words: vec!["前条".to_string()],
+    after_word: "第三条".to_string(),
+    operation: crate::YomikaeOperation::Replace,
+    confidence: crate::YomikaeConfidence::High,
+    qualifiers: Vec::new(),
+    law_refs: Vec::new(),
+    target: None,
+    scoped_before_words: Vec::new(),
+    table_cell: None,
+  }];
+  let yomikae_data = |s: &str, data: Vec<YomikaeInfo>| YomikaeData {
+    num: LawNum::new("test"),
+    article: article(s),
+    data,
+    url: None,
+    source_file: None,
+    xml_path: None,
+    content_hash: None,
+    revision: None,
+    title: None,
+  };
+  let (deduped_lst, report) = dedupe_yomikae(vec![
+    yomikae_data("2", data.clone()),
+    yomikae_data("3", vec![]),
+    yomikae_data("5", data.clone()),
+  ]);
+  assert_eq!(
+    vec![yomikae_data("2", data.clone()), yomikae_data("3", vec![])],
+    deduped_lst
+  );
+  assert_eq!(
+    DuplicateReport {
+      duplicates: vec![DuplicateEntry {
+        num: LawNum::new("test"),
+        revision: None,
+        data: data.clone(),
+        articles: vec![article("2"), article("5")]
+      }]
+    },
+    report
+  );
+  let revision = LawRevision {
+    enforcement_date: Some("20230401".to_string()),
+    ..Default::default()
+  };
+  let (deduped_lst, report) = dedupe_yomikae(vec![
+    yomikae_data("2", data.clone()),
+    yomikae_data("5", data).with_revision(revision),
+  ]);
+  assert_eq!(2, deduped_lst.len());
+  assert!(report.duplicates.is_empty());
+}
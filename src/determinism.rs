@@ -0,0 +1,129 @@
+//! 同じ入力を二回解析した結果を比べて、解析が決定的かどうかを確かめる
+//!
+//! 法令を並行して解析すると、出力の順番の揺れやデータ競合で結果が変わることがあるので、
+//! 解析結果とエラーを順番に依らない形にそろえてから比べる
+
+use crate::{YomikaeData, YomikaeError};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// 解析結果とエラーを一件ずつJSONの文字列にして並べ替えた、順番に依らない形
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct CanonicalOutput {
+  /// 解析結果
+  pub data: Vec<String>,
+  /// エラー
+  pub errors: Vec<String>,
+}
+
+impl CanonicalOutput {
+  /// 解析の結果を順番に依らない形にそろえる
+  pub fn new<'a, I>(res_lst: I) -> Self
+  where
+    I: IntoIterator<Item = &'a Result<YomikaeData, YomikaeError>>,
+  {
+    let mut output = CanonicalOutput::default();
+    for res in res_lst {
+      match res {
+        Ok(yomikae_data) => output.data.extend(serde_json::to_string(yomikae_data).ok()),
+        Err(err) => output.errors.extend(serde_json::to_string(err).ok()),
+      }
+    }
+    output.data.sort();
+    output.errors.sort();
+    output
+  }
+}
+
+/// 二回の解析の結果の違い
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct DeterminismReport {
+  /// 一回目の解析の件数（解析結果とエラーの合計）
+  pub first_count: usize,
+  /// 二回目の解析の件数（解析結果とエラーの合計）
+  pub second_count: usize,
+  /// 一回目にだけ現れた解析結果とエラー
+  pub only_first: Vec<String>,
+  /// 二回目にだけ現れた解析結果とエラー
+  pub only_second: Vec<String>,
+}
+
+impl DeterminismReport {
+  /// 二回の解析の結果が一致したかどうか
+  pub fn is_deterministic(&self) -> bool {
+    self.only_first.is_empty() && self.only_second.is_empty()
+  }
+}
+
+/// 並べ替えた二つのリストを比べ、片方にだけある要素を、同じものが複数ある場合はその数の差だけ取り出す
+fn sorted_difference(first: &[String], second: &[String]) -> (Vec<String>, Vec<String>) {
+  let mut only_first = Vec::new();
+  let mut only_second = Vec::new();
+  let (mut i, mut j) = (0, 0);
+  while i < first.len() && j < second.len() {
+    match first[i].cmp(&second[j]) {
+      Ordering::Less => {
+        only_first.push(first[i].clone());
+        i += 1;
+      }
+      Ordering::Greater => {
+        only_second.push(second[j].clone());
+        j += 1;
+      }
+      Ordering::Equal => {
+        i += 1;
+        j += 1;
+      }
+    }
+  }
+  only_first.extend_from_slice(&first[i..]);
+  only_second.extend_from_slice(&second[j..]);
+  (only_first, only_second)
+}
+
+/// 二回の解析の結果を比べる
+pub fn compare_outputs(first: &CanonicalOutput, second: &CanonicalOutput) -> DeterminismReport {
+  let (mut only_first, mut only_second) = sorted_difference(&first.data, &second.data);
+  let (errors_only_first, errors_only_second) = sorted_difference(&first.errors, &second.errors);
+  only_first.extend(errors_only_first);
+  only_second.extend(errors_only_second);
+  DeterminismReport {
+    first_count: first.data.len() + first.errors.len(),
+    second_count: second.data.len() + second.errors.len(),
+    only_first,
+    only_second,
+  }
+}
+
+#[test]
+fn check_compare_outputs() {
+  use crate::{test_utils, ArticleExt, LawInfo, YomikaeInfo};
+  use jplaw_text::Article;
+  let yomikae_data = |num: &str| {
+    YomikaeData::new(
+      num,
+      Article::article("1"),
+      vec![YomikaeInfo::new(["前項"], "次項")],
+    )
+  };
+  let err = YomikaeError::UnmatchedParen(LawInfo::new(
+    "c",
+    test_utils::test_article(),
+    test_utils::law_text("「前項」とあるのは「次項と読み替える。"),
+  ));
+  let first = CanonicalOutput::new(&[
+    Ok(yomikae_data("a")),
+    Err(err.clone()),
+    Ok(yomikae_data("b")),
+  ]);
+  let second = CanonicalOutput::new(&[Ok(yomikae_data("b")), Ok(yomikae_data("a")), Err(err)]);
+  let report = compare_outputs(&first, &second);
+  assert!(report.is_deterministic());
+  assert_eq!(3, report.first_count);
+  let third = CanonicalOutput::new(&[Ok(yomikae_data("a")), Ok(yomikae_data("a"))]);
+  let report = compare_outputs(&first, &third);
+  assert!(!report.is_deterministic());
+  assert_eq!(2, report.only_first.len());
+  assert_eq!(1, report.only_second.len());
+  assert!(report.only_second[0].contains("\"a\""));
+}
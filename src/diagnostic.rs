@@ -0,0 +1,210 @@
+//! エラーを元の文と該当箇所を指し示した形で表示するための[`miette::Diagnostic`]
+//!
+//! `miette` featureを有効にすると使えるようになる
+
+use crate::{LawInfo, YomikaeError};
+use jplaw_text::LawContents;
+use miette::{Diagnostic, LabeledSpan, SourceCode};
+use std::fmt::Display;
+use thiserror::Error;
+
+/// 元の文と、その中の問題のある箇所を持ったエラー
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct YomikaeDiagnostic {
+  code: &'static str,
+  message: String,
+  source_code: Option<String>,
+  labels: Vec<LabeledSpan>,
+  help: Option<String>,
+}
+
+impl Diagnostic for YomikaeDiagnostic {
+  fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+    Some(Box::new(self.code))
+  }
+  fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+    self
+      .help
+      .as_ref()
+      .map(|help| Box::new(help) as Box<dyn Display + 'a>)
+  }
+  fn source_code(&self) -> Option<&dyn SourceCode> {
+    self.source_code.as_ref().map(|s| s as &dyn SourceCode)
+  }
+  fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+    if self.labels.is_empty() {
+      None
+    } else {
+      Some(Box::new(self.labels.iter().cloned()))
+    }
+  }
+}
+
+fn law_text_source(law_info: &LawInfo) -> Option<String> {
+  match &law_info.contents.contents {
+    LawContents::Text(s) => Some(s.clone()),
+    LawContents::Table(_) => None,
+  }
+}
+
+/// 対応の取れていない鉤括弧の位置（バイト単位）を探す
+fn unmatched_paren_spans(text: &str) -> Vec<LabeledSpan> {
+  let mut open_lst = Vec::new();
+  for (i, c) in text.char_indices() {
+    match c {
+      '「' => open_lst.push(i),
+      '」' => {
+        if open_lst.pop().is_none() {
+          return vec![LabeledSpan::at(
+            i..i + c.len_utf8(),
+            "no matching 「 for this 」",
+          )];
+        }
+      }
+      _ => (),
+    }
+  }
+  open_lst
+    .into_iter()
+    .map(|i| LabeledSpan::at(i..i + '「'.len_utf8(), "this 「 is never closed"))
+    .collect()
+}
+
+/// 「とある」の後に出てきた「とあり」の位置を探す
+fn unexpected_parallel_spans(text: &str) -> Vec<LabeledSpan> {
+  text
+    .find("」とある")
+    .and_then(|aru| {
+      text[aru..].find("」とあり").map(|i| {
+        let start = aru + i + "」".len();
+        LabeledSpan::at(start..start + "とあり".len(), "「とあり」 after 「とある」")
+      })
+    })
+    .into_iter()
+    .collect()
+}
+
+/// 読み替え規定であると判断した根拠の文言の位置を探す
+fn trigger_spans(text: &str) -> Vec<LabeledSpan> {
+  text
+    .find("と読み替える")
+    .map(|i| {
+      LabeledSpan::at(
+        i..i + "と読み替える".len(),
+        "yomikae sentence detected here",
+      )
+    })
+    .into_iter()
+    .collect()
+}
+
+/// エラーから、元の文と問題のある箇所を持った[`YomikaeDiagnostic`]を作る
+pub fn to_diagnostic(err: &YomikaeError) -> YomikaeDiagnostic {
+  let (code, message, law_info, help) = match err {
+    YomikaeError::ContentsOfTable(law_info) => (
+      "analysis_yomikae::contents_of_table",
+      "table has an unexpected number of columns",
+      Some(law_info),
+      Some("yomikae tables must have two or three columns"),
+    ),
+    YomikaeError::UnmatchedParen(law_info) => (
+      "analysis_yomikae::unmatched_paren",
+      "unmatched parentheses",
+      Some(law_info),
+      Some("check that every 「 has a matching 」"),
+    ),
+    YomikaeError::UnexpectedParallelWords(law_info) => (
+      "analysis_yomikae::unexpected_parallel_words",
+      "unexpected parallel words",
+      Some(law_info),
+      Some("「とあり」 can only appear before the 「とある」 that ends the list of before words"),
+    ),
+    YomikaeError::UnmatchedSorezoreWords(law_info) => (
+      "analysis_yomikae::unmatched_sorezore_words",
+      "unmatched number of words for 「それぞれ」",
+      Some(law_info),
+      Some(
+        "with 「それぞれ」, the before words and the after words must be listed in the same number",
+      ),
+    ),
+    YomikaeError::NotFoundYomikae(law_info) => (
+      "analysis_yomikae::not_found_yomikae",
+      "no yomikae pair was extracted",
+      Some(law_info),
+      Some(
+        "the sentence looks like a yomikae sentence, but no 「A」とあるのは「B」 pair was found",
+      ),
+    ),
+    YomikaeError::ReadLawFile(_, _) => (
+      "analysis_yomikae::read_law_file",
+      "failed to read law file",
+      None,
+      None,
+    ),
+    YomikaeError::ParseLawXml(_, _) => (
+      "analysis_yomikae::parse_law_xml",
+      "failed to parse law XML",
+      None,
+      None,
+    ),
+    YomikaeError::InternalError(law_info, _) => (
+      "analysis_yomikae::internal_error",
+      "internal error",
+      Some(law_info),
+      None,
+    ),
+    YomikaeError::AnalyzeLaw(_, _) => (
+      "analysis_yomikae::analyze_law",
+      "failed to analyze law file",
+      None,
+      None,
+    ),
+  };
+  let source_code = law_info.and_then(law_text_source);
+  let labels = match (err, &source_code) {
+    (YomikaeError::UnmatchedParen(_), Some(text)) => unmatched_paren_spans(text),
+    (YomikaeError::UnexpectedParallelWords(_), Some(text)) => unexpected_parallel_spans(text),
+    (YomikaeError::NotFoundYomikae(_), Some(text)) => trigger_spans(text),
+    _ => Vec::new(),
+  };
+  let message = match err {
+    YomikaeError::ReadLawFile(info, msg)
+    | YomikaeError::ParseLawXml(info, msg)
+    | YomikaeError::AnalyzeLaw(info, msg) => {
+      format!("{message}: {} ({msg})", info.file)
+    }
+    YomikaeError::InternalError(_, msg) => format!("{message}: {msg}"),
+    _ => message.to_string(),
+  };
+  YomikaeDiagnostic {
+    code,
+    message,
+    source_code,
+    labels,
+    help: help.map(|s| s.to_string()),
+  }
+}
+
+#[test]
+fn check_unmatched_paren_spans() {
+  let spans = unmatched_paren_spans("「前条」」とある");
+  assert_eq!(1, spans.len());
+  assert_eq!("「前条」".len(), spans[0].offset());
+  let spans = unmatched_paren_spans("「前条とある");
+  assert_eq!(1, spans.len());
+  assert_eq!(0, spans[0].offset());
+}
+
+#[test]
+fn check_to_diagnostic() {
+  let law_info = LawInfo::new(
+    "test",
+    crate::test_utils::test_article(),
+    crate::test_utils::law_text("「A」とあるのは「B」と「C」とあり「D」と読み替える"),
+  );
+  let diagnostic = to_diagnostic(&YomikaeError::UnexpectedParallelWords(law_info));
+  let labels = diagnostic.labels().unwrap().collect::<Vec<_>>();
+  assert_eq!(1, labels.len());
+  assert_eq!("「A」とあるのは「B」と「C」".len(), labels[0].offset());
+}
@@ -0,0 +1,115 @@
+//! 解析結果を人が読みやすい形で表示する
+
+use crate::{YomikaeData, YomikaeInfo};
+use jplaw_text::Article;
+use std::fmt;
+
+/// 「第三条」「本則」のように番号から始まっていない場合はそのまま、
+/// 「3_2」のような番号の場合は「第3条の2」のように、前後に文字を付けて表示する
+fn number_to_string(s: &str, suffix: &str) -> String {
+  if !s.starts_with(|c: char| c.is_ascii_digit()) {
+    s.to_string()
+  } else {
+    let mut parts = s.split('_');
+    let head = parts.next().unwrap_or_default();
+    let mut text = format!("第{head}{suffix}");
+    for part in parts {
+      text.push('の');
+      text.push_str(part);
+    }
+    text
+  }
+}
+
+/// 条項を「附則第3条第1項第2号」のような文字列にする
+pub fn article_to_string(article: &Article) -> String {
+  let mut text = String::new();
+  if let Some(title) = &article.suppl_provision_title {
+    text.push_str(title);
+  }
+  text.push_str(&number_to_string(&article.article, "条"));
+  if let Some(paragraph) = &article.paragraph {
+    text.push_str(&number_to_string(paragraph, "項"));
+  }
+  if let Some(item) = &article.item {
+    text.push_str(&number_to_string(item, "号"));
+  }
+  for sub_item in article.sub_item.iter().flatten() {
+    text.push_str(sub_item);
+  }
+  text
+}
+
+impl fmt::Display for YomikaeInfo {
+  /// 「A」、「B」→「C」の形で表示する
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let before = self
+      .before_words
+      .iter()
+      .map(|s| format!("「{s}」"))
+      .collect::<Vec<_>>()
+      .join("、");
+    write!(f, "{before}→「{}」", self.after_word)
+  }
+}
+
+impl fmt::Display for YomikaeData {
+  /// 一行目に法律番号を、それ以降に一行ずつ「A」→「B」（対象: 第X条）の形で表示する
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.num)?;
+    let article = article_to_string(&self.article);
+    for yomikae_info in self.data.iter() {
+      write!(f, "\n  {yomikae_info}（対象: {article}）")?;
+    }
+    Ok(())
+  }
+}
+
+#[test]
+fn check_display() {
+  use crate::LawNum;
+  let yomikae_data = YomikaeData {
+    num: LawNum::new("平成二十四年法律第六十五号"),
+    article: Article {
+      article: "3_2".to_string(),
+      paragraph: Some("1".to_string()),
+      item: None,
+      sub_item: None,
+      suppl_provision_title: None,
+    },
+    data: vec![
+      YomikaeInfo {
+        before_words: vec!["同項各号に定める者".to_string(), "その者".to_string()],
+        after_word: "都道府県の教育委員会".to_string(),
+        operation: crate::YomikaeOperation::Replace,
+        confidence: crate::YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: None,
+        scoped_before_words: Vec::new(),
+        table_cell: None,
+      },
+      YomikaeInfo {
+        before_words: vec!["、第三項".to_string()],
+        after_word: String::new(),
+        operation: crate::YomikaeOperation::Delete,
+        confidence: crate::YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: None,
+        scoped_before_words: Vec::new(),
+        table_cell: None,
+      },
+    ],
+    url: None,
+    source_file: None,
+    xml_path: None,
+    content_hash: None,
+    revision: None,
+    title: None,
+  };
+  assert_eq!(
+    "平成二十四年法律第六十五号\n  「同項各号に定める者」、「その者」→「都道府県の教育委員会」（対象: 第3条の2第1項）\n  「、第三項」→「」（対象: 第3条の2第1項）",
+    yomikae_data.to_string()
+  );
+}
@@ -0,0 +1,103 @@
+//! e-Gov法令検索の法令や条項へのURLを作る
+//!
+//! 法令は法令ID（法令XMLファイル名の先頭の部分）で、条項は「Mp-At_3_2-Pr_1-It_2」のようなアンカーで指定する
+
+use crate::YomikaeData;
+use jplaw_text::Article;
+use std::fmt::Write;
+
+/// e-Gov法令検索で法令を表すURLの前半
+pub const EGOV_LAW_BASE: &str = "https://laws.e-gov.go.jp/law/";
+
+/// 法令XMLファイル名から法令IDを取り出す
+/// 「405AC0000000088_20230401_504AC0000000068.xml」のようなファイル名の先頭の部分を法令IDとする
+pub fn law_id_from_file(file: &str) -> Option<String> {
+  let name = file.rsplit(['/', '\\']).next().unwrap_or(file);
+  let id = name.split(['_', '.']).next().unwrap_or_default();
+  if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()) {
+    Some(id.to_string())
+  } else {
+    None
+  }
+}
+
+/// URLに使えない文字をパーセントエンコードする
+pub(crate) fn percent_encode(s: &str) -> String {
+  let mut encoded = String::new();
+  for b in s.bytes() {
+    if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+      encoded.push(b as char);
+    } else {
+      let _ = write!(encoded, "%{b:02X}");
+    }
+  }
+  encoded
+}
+
+/// 条項のアンカー
+/// 本則は「Mp」、附則は「Sp」から始まり、条・項・号・号の細分の番号を続ける
+pub fn article_anchor(article: &Article) -> String {
+  let mut anchor = match &article.suppl_provision_title {
+    Some(_) => "Sp".to_string(),
+    None => "Mp".to_string(),
+  };
+  let _ = write!(anchor, "-At_{}", percent_encode(&article.article));
+  if let Some(paragraph) = &article.paragraph {
+    let _ = write!(anchor, "-Pr_{}", percent_encode(paragraph));
+  }
+  if let Some(item) = &article.item {
+    let _ = write!(anchor, "-It_{}", percent_encode(item));
+  }
+  for sub_item in article.sub_item.iter().flatten() {
+    let _ = write!(anchor, "-Si_{}", percent_encode(sub_item));
+  }
+  anchor
+}
+
+/// 法令のURL
+pub fn egov_law_url(law_id: &str) -> String {
+  format!("{EGOV_LAW_BASE}{law_id}")
+}
+
+/// 条項のURL
+pub fn egov_url(law_id: &str, article: &Article) -> String {
+  format!("{}#{}", egov_law_url(law_id), article_anchor(article))
+}
+
+impl YomikaeData {
+  /// 読み替え規定がある条項のe-Gov法令検索のURLを設定する
+  pub fn with_egov_url(self, law_id: &str) -> Self {
+    YomikaeData {
+      url: Some(egov_url(law_id, &self.article)),
+      ..self
+    }
+  }
+}
+
+#[test]
+fn check_law_id_from_file() {
+  assert_eq!(
+    Some("405AC0000000088".to_string()),
+    law_id_from_file("law_xml/405AC0000000088_20230401_504AC0000000068.xml")
+  );
+  assert_eq!(None, law_id_from_file("法令.xml"));
+}
+
+#[test]
+fn check_egov_url() {
+  use crate::ArticleExt;
+  assert_eq!(
+    "https://laws.e-gov.go.jp/law/405AC0000000088#Mp-At_3_2-Pr_1-It_2",
+    egov_url(
+      "405AC0000000088",
+      &Article::article("3_2").with_paragraph("1").with_item("2")
+    )
+  );
+  assert_eq!(
+    "https://laws.e-gov.go.jp/law/405AC0000000088#Sp-At_1",
+    egov_url(
+      "405AC0000000088",
+      &Article::article("1").in_suppl_provision("附則")
+    )
+  );
+}
@@ -0,0 +1,82 @@
+//! 法令のインデックス情報に、法令ごとの読み替え規定の解析結果の集計を書き加える
+//!
+//! 他のツールが読み替え規定の有無などで法令を絞り込めるようにするためのもの
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 一つの法令の解析結果の集計
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct LawStats {
+  /// 読み替えの組が一つ以上取り出せたかどうか
+  pub has_yomikae: bool,
+  /// 取り出した読み替えの組の数
+  pub pair_count: usize,
+  /// エラーの数
+  pub error_count: usize,
+}
+
+impl LawStats {
+  /// 取り出した読み替えの組の数を足す
+  pub fn add_pairs(&mut self, pair_count: usize) {
+    self.pair_count += pair_count;
+    self.has_yomikae = self.pair_count > 0;
+  }
+
+  /// エラーの数を一つ増やす
+  pub fn add_error(&mut self) {
+    self.error_count += 1;
+  }
+}
+
+/// インデックス情報のJSONの中で`num`を持つオブジェクトを探し、
+/// その法律番号の集計があれば`has_yomikae`・`pair_count`・`error_count`を書き加える
+/// それ以外の部分は変えない
+pub fn enrich_index(index: &mut Value, stats: &HashMap<String, LawStats>) {
+  match index {
+    Value::Array(lst) => {
+      for v in lst.iter_mut() {
+        enrich_index(v, stats);
+      }
+    }
+    Value::Object(map) => {
+      let law_stats = map
+        .get("num")
+        .and_then(|num| num.as_str())
+        .and_then(|num| stats.get(num));
+      if let Some(law_stats) = law_stats {
+        map.insert("has_yomikae".to_string(), law_stats.has_yomikae.into());
+        map.insert("pair_count".to_string(), law_stats.pair_count.into());
+        map.insert("error_count".to_string(), law_stats.error_count.into());
+      } else {
+        for v in map.values_mut() {
+          enrich_index(v, stats);
+        }
+      }
+    }
+    _ => (),
+  }
+}
+
+#[test]
+fn check_enrich_index() {
+  use serde_json::json;
+  let mut index = json!([
+    {"num": "a", "file": "a.xml", "name": "A法"},
+    {"num": "b", "file": "b.xml", "name": "B法"},
+  ]);
+  let mut a_stats = LawStats::default();
+  a_stats.add_pairs(3);
+  a_stats.add_error();
+  let mut stats = HashMap::new();
+  stats.insert("a".to_string(), a_stats);
+  enrich_index(&mut index, &stats);
+  assert_eq!(
+    json!([
+      {"num": "a", "file": "a.xml", "name": "A法", "has_yomikae": true, "pair_count": 3, "error_count": 1},
+      {"num": "b", "file": "b.xml", "name": "B法"},
+    ]),
+    index
+  );
+}
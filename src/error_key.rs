@@ -0,0 +1,104 @@
+//! エラーを見分けるための軽い識別子
+//!
+//! エラーに含まれる条文全体を比べずに、法令・条項・エラーの種類でエラーの重複を取り除いたり、
+//! エラーを引いたりするために使う
+
+use crate::YomikaeError;
+use jplaw_text::Article;
+use serde::{Deserialize, Serialize};
+
+/// エラーの識別子
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ErrorKey {
+  /// 法律番号
+  pub law: String,
+  /// エラーが起きた条項（法令ファイルについてのエラーの場合は`None`）
+  pub article: Option<Article>,
+  /// 条文の中でエラーが起きた文の番号
+  pub sentence_index: Option<usize>,
+  /// エラーの種類（`unmatched_paren`など）
+  pub code: String,
+}
+
+/// エラーの種類の名前
+pub(crate) fn error_kind(err: &YomikaeError) -> &'static str {
+  match err {
+    YomikaeError::ContentsOfTable(_) => "contents_of_table",
+    YomikaeError::UnmatchedParen(_) => "unmatched_paren",
+    YomikaeError::UnexpectedParallelWords(_) => "unexpected_parallel_words",
+    YomikaeError::UnmatchedSorezoreWords(_) => "unmatched_sorezore_words",
+    YomikaeError::NotFoundYomikae(_) => "not_found_yomikae",
+    YomikaeError::ReadLawFile(_, _) => "read_law_file",
+    YomikaeError::ParseLawXml(_, _) => "parse_law_xml",
+    YomikaeError::InternalError(_, _) => "internal_error",
+    YomikaeError::AnalyzeLaw(_, _) => "analyze_law",
+  }
+}
+
+impl YomikaeError {
+  /// エラーの識別子を作る
+  pub fn key(&self) -> ErrorKey {
+    let code = error_kind(self).to_string();
+    match self {
+      YomikaeError::ContentsOfTable(law_info)
+      | YomikaeError::UnmatchedParen(law_info)
+      | YomikaeError::UnexpectedParallelWords(law_info)
+      | YomikaeError::UnmatchedSorezoreWords(law_info)
+      | YomikaeError::NotFoundYomikae(law_info)
+      | YomikaeError::InternalError(law_info, _) => ErrorKey {
+        law: law_info.num.to_string(),
+        article: Some(law_info.article.clone()),
+        sentence_index: law_info.sentence_index,
+        code,
+      },
+      YomikaeError::ReadLawFile(law_file_info, _)
+      | YomikaeError::ParseLawXml(law_file_info, _)
+      | YomikaeError::AnalyzeLaw(law_file_info, _) => ErrorKey {
+        law: law_file_info.num.clone(),
+        article: None,
+        sentence_index: None,
+        code,
+      },
+    }
+  }
+}
+
+#[test]
+fn check_error_key() {
+  use crate::{test_utils, LawFileInfo, LawInfo};
+  let law_info = LawInfo::new(
+    "test",
+    test_utils::test_article(),
+    test_utils::law_text("「前項」」とあるのは「次項」と読み替える。"),
+  );
+  let other_law_info = LawInfo::new(
+    "test",
+    test_utils::test_article(),
+    test_utils::law_text("「前項」」とあるのは 「次項」と読み替える。"),
+  );
+  assert_eq!(
+    YomikaeError::UnmatchedParen(law_info.clone()).key(),
+    YomikaeError::UnmatchedParen(other_law_info).key()
+  );
+  assert_ne!(
+    YomikaeError::UnmatchedParen(law_info.clone()).key(),
+    YomikaeError::NotFoundYomikae(law_info).key()
+  );
+  let law_file_info = LawFileInfo {
+    num: "test".to_string(),
+    file: "test.xml".to_string(),
+  };
+  assert_eq!(
+    ErrorKey {
+      law: "test".to_string(),
+      article: None,
+      sentence_index: None,
+      code: "read_law_file".to_string(),
+    },
+    YomikaeError::ReadLawFile(law_file_info.clone(), "not found".to_string()).key()
+  );
+  assert_ne!(
+    YomikaeError::ReadLawFile(law_file_info.clone(), "panic".to_string()).key(),
+    YomikaeError::AnalyzeLaw(law_file_info, "panic".to_string()).key()
+  );
+}
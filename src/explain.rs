@@ -0,0 +1,161 @@
+//! 一つの読み替え規定文がどのように解析されるのかを、途中の過程と合わせて説明する
+//!
+//! `repl`サブコマンドで、解析がうまくいかない文を調べるために使う
+
+use crate::error_key::error_kind;
+use crate::segment::{split_segments, Segment};
+use crate::{closing_transition, parse_yomikae_sentences, ArticleExt, Transition, YomikaeInfo};
+use jplaw_text::{Article, LawContents, LawText};
+use std::fmt;
+
+/// 閉じ鉤括弧一つごとの、状態の変わり方
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExplainStep {
+  /// 鉤括弧で括られた語
+  pub word: String,
+  /// 閉じ鉤括弧の後に続く、鉤括弧の外側の文字列
+  pub following_text: String,
+  /// 状態の変わり方
+  pub transition: Transition,
+}
+
+/// 一つの文の解析の説明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentenceExplanation {
+  /// 解析した文
+  pub sentence: String,
+  /// 文を一番外側の鉤括弧で分けたもの（鉤括弧の対応が取れていない場合は`None`）
+  /// 鉤括弧で括られた部分は`true`にする
+  pub tokens: Option<Vec<(bool, String)>>,
+  /// 閉じ鉤括弧ごとの状態の変わり方
+  pub steps: Vec<ExplainStep>,
+  /// 文ごとの解析結果（エラーの場合はエラーの種類）
+  pub results: Vec<Result<Vec<YomikaeInfo>, &'static str>>,
+}
+
+/// 一つの文を解析し、その過程を説明する
+/// 文の区切り方や語の組み立て方はそれぞれ一通りしかないため、他の候補は挙げない
+pub fn explain_sentence(sentence: &str) -> SentenceExplanation {
+  let segments = split_segments(sentence);
+  let tokens = segments.as_ref().map(|segments| {
+    segments
+      .iter()
+      .map(|segment| match segment {
+        Segment::Quoted(s) => (true, s.clone()),
+        Segment::Text(s) => (false, s.clone()),
+      })
+      .collect()
+  });
+  let segments = segments.as_deref().unwrap_or_default();
+  let mut steps = Vec::new();
+  for (i, segment) in segments.iter().enumerate() {
+    if let Segment::Quoted(word) = segment {
+      let mut following_text = match segments.get(i + 1) {
+        Some(Segment::Text(text)) => text.clone(),
+        _ => String::new(),
+      };
+      let next_index = if following_text.is_empty() {
+        i + 1
+      } else {
+        i + 2
+      };
+      if let Some(Segment::Quoted(_)) = segments.get(next_index) {
+        following_text.push('「');
+      }
+      let (transition, _) = closing_transition(&following_text);
+      steps.push(ExplainStep {
+        word: word.clone(),
+        following_text,
+        transition,
+      });
+    }
+  }
+  let law_text = LawText {
+    article_info: Article::article("1"),
+    contents: LawContents::Text(sentence.to_string()),
+  };
+  let results = parse_yomikae_sentences(&law_text, "repl")
+    .into_iter()
+    .map(|res| res.map_err(|err| error_kind(&err)))
+    .collect();
+  SentenceExplanation {
+    sentence: sentence.to_string(),
+    tokens,
+    steps,
+    results,
+  }
+}
+
+impl fmt::Display for SentenceExplanation {
+  /// 鉤括弧による分割・状態の変わり方・解析結果の順に一行ずつ表示する
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.tokens {
+      Some(tokens) => {
+        writeln!(f, "tokens:")?;
+        for (is_quoted, s) in tokens.iter() {
+          if *is_quoted {
+            writeln!(f, "  quoted 「{s}」")?;
+          } else {
+            writeln!(f, "  text   {s}")?;
+          }
+        }
+      }
+      None => writeln!(f, "tokens: unbalanced brackets")?,
+    }
+    writeln!(f, "transitions:")?;
+    for step in self.steps.iter() {
+      writeln!(
+        f,
+        "  「{}」 {:?} -> {:?}",
+        step.word, step.following_text, step.transition
+      )?;
+    }
+    if self.results.is_empty() {
+      write!(f, "pairs: no sentence containing yomikae phrase")?;
+    }
+    for (i, res) in self.results.iter().enumerate() {
+      match res {
+        Ok(yomikae_info_lst) => {
+          write!(f, "pairs (sentence {i}):")?;
+          for yomikae_info in yomikae_info_lst.iter() {
+            write!(f, "\n  {yomikae_info}")?;
+          }
+        }
+        Err(kind) => write!(f, "pairs (sentence {i}): error: {kind}")?,
+      }
+      if i + 1 < self.results.len() {
+        writeln!(f)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[test]
+fn check_explain_sentence() {
+  let explanation =
+    explain_sentence("前条中「前項」とあり、及び「同項」とあるのは「第二項」と読み替える。");
+  assert_eq!(
+    vec![
+      Transition::BeforeWord,
+      Transition::LastBeforeWord,
+      Transition::AfterWord
+    ],
+    explanation
+      .steps
+      .iter()
+      .map(|step| step.transition)
+      .collect::<Vec<_>>()
+  );
+  assert_eq!(
+    Some(7),
+    explanation.tokens.as_ref().map(|tokens| tokens.len())
+  );
+  assert_eq!(1, explanation.results.len());
+  let yomikae_info_lst = explanation.results[0].as_ref().unwrap();
+  assert_eq!(vec!["前項", "同項"], yomikae_info_lst[0].before_words);
+  assert!(explanation
+    .to_string()
+    .contains("「前項」、「同項」→「第二項」"));
+  assert_eq!(None, explain_sentence("「前項」とあるのは「第二項").tokens);
+}
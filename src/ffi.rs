@@ -0,0 +1,132 @@
+//! C言語などRust以外の言語から解析を呼び出すためのAPI
+//!
+//! `ffi` featureを有効にすると使えるようになる
+//! ヘッダファイルは`include/analysis_yomikae.h`にある
+//!
+//! 結果はUTF-8のJSON文字列で返す
+//! 解析に成功した場合は`{"Ok":[...]}`、失敗した場合は`{"Err":{...}}`という形になる
+//! 解析中にpanicが起きた場合もC側に巻き戻さずに、`{"Err":{"InternalError":...}}`を返す
+//! 返された文字列は必ず[`yomikae_free_string`]で解放すること
+
+use crate::pipeline::panic_message;
+use crate::{kaiseibun::parse_kaiseibun, parse_yomikae, ArticleExt, LawInfo, YomikaeError};
+use jplaw_text::{Article, LawContents, LawText};
+use serde::Serialize;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// NULLでない場合はNUL終端されたUTF-8の文字列として読む
+unsafe fn read_str<'a>(ptr: *const c_char) -> Option<&'a str> {
+  if ptr.is_null() {
+    None
+  } else {
+    CStr::from_ptr(ptr).to_str().ok()
+  }
+}
+
+/// 解析してその結果をJSON文字列にする
+/// 引数が不正な場合や、結果を返せない場合はNULLを返す
+/// `extern "C"`の関数の外にpanicを巻き戻さないように、解析中のpanicは[`YomikaeError::InternalError`]にする
+unsafe fn parse_to_json<T, F>(text: *const c_char, num: *const c_char, parse: F) -> *mut c_char
+where
+  T: Serialize,
+  F: FnOnce(&LawText, &str, &Article) -> Result<T, YomikaeError>,
+{
+  let text = match read_str(text) {
+    Some(text) => text,
+    None => return std::ptr::null_mut(),
+  };
+  let num = match read_str(num) {
+    Some(num) => num,
+    None if num.is_null() => "",
+    None => return std::ptr::null_mut(),
+  };
+  let law_text = LawText {
+    article_info: Article::article(""),
+    contents: LawContents::Text(text.to_string()),
+  };
+  let result = catch_unwind(AssertUnwindSafe(|| {
+    parse(&law_text, num, &law_text.article_info)
+  }))
+  .unwrap_or_else(|payload| {
+    let law_info = LawInfo::new(num, law_text.article_info.clone(), law_text.clone());
+    Err(YomikaeError::InternalError(
+      law_info,
+      panic_message(payload.as_ref()),
+    ))
+  });
+  serde_json::to_string(&result)
+    .ok()
+    .and_then(|json| CString::new(json).ok())
+    .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// 読み替え規定文を解析して、結果をJSON文字列で返す
+///
+/// # Safety
+///
+/// `text`はNUL終端されたUTF-8の文字列へのポインタでなければならない
+/// `num`は法律番号を表すNUL終端されたUTF-8の文字列へのポインタか、NULLでなければならない
+#[no_mangle]
+pub unsafe extern "C" fn yomikae_parse(text: *const c_char, num: *const c_char) -> *mut c_char {
+  parse_to_json(text, num, parse_yomikae)
+}
+
+/// 改め文を解析して、結果をJSON文字列で返す
+///
+/// # Safety
+///
+/// 引数の条件は[`yomikae_parse`]と同じ
+#[no_mangle]
+pub unsafe extern "C" fn yomikae_parse_kaiseibun(
+  text: *const c_char,
+  num: *const c_char,
+) -> *mut c_char {
+  parse_to_json(text, num, parse_kaiseibun)
+}
+
+/// このライブラリが返した文字列を解放する
+///
+/// # Safety
+///
+/// `ptr`は[`yomikae_parse`]などが返したポインタかNULLでなければならず、
+/// 同じポインタを二度解放してはならない
+#[no_mangle]
+pub unsafe extern "C" fn yomikae_free_string(ptr: *mut c_char) {
+  if !ptr.is_null() {
+    drop(CString::from_raw(ptr));
+  }
+}
+
+#[test]
+fn check_yomikae_parse() {
+  let text = CString::new(crate::test_utils::SENTENCE_SINGLE).unwrap();
+  let num = CString::new("test").unwrap();
+  unsafe {
+    let ptr = yomikae_parse(text.as_ptr(), num.as_ptr());
+    assert!(!ptr.is_null());
+    let json = CStr::from_ptr(ptr).to_str().unwrap().to_string();
+    yomikae_free_string(ptr);
+    assert!(json.starts_with("{\"Ok\":[{\"before_words\":[\"被後見人を代表する\"]"));
+    assert!(yomikae_parse(std::ptr::null(), num.as_ptr()).is_null());
+  }
+}
+
+#[test]
+fn check_parse_to_json_catch_panic() {
+  let text = CString::new("「前項」とあるのは「次項」と読み替える。").unwrap();
+  unsafe {
+    let ptr = parse_to_json(
+      text.as_ptr(),
+      std::ptr::null(),
+      |_: &LawText, _: &str, _: &Article| -> Result<Vec<String>, YomikaeError> {
+        panic!("test panic")
+      },
+    );
+    assert!(!ptr.is_null());
+    let json = CStr::from_ptr(ptr).to_str().unwrap().to_string();
+    yomikae_free_string(ptr);
+    assert!(json.starts_with("{\"Err\":{\"InternalError\":"));
+    assert!(json.ends_with(",\"test panic\"]}}"));
+  }
+}
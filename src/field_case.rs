@@ -0,0 +1,364 @@
+//! 構造体のフィールド名だけをcamelCaseにしてJSONの値にする
+//!
+//! フィールド名はserdeが渡す構造体の情報から変えるので、マップのキー（法律番号などのデータ）はそのままになる
+//! jplaw_textの型（[`FOREIGN_STRUCTS`]）は、そのクレートが決めた名前のまま書き出す
+
+use serde::ser::{self, Serialize};
+use serde_json::{Map, Value};
+
+/// フィールド名を変えずに書き出す構造体の名前
+const FOREIGN_STRUCTS: &[&str] = &["Article", "LawText"];
+
+/// snake_caseの名前をcamelCaseにする
+pub(crate) fn to_camel_case(name: &str) -> String {
+  let mut converted = String::new();
+  let mut is_upper = false;
+  for c in name.chars() {
+    if c == '_' && !converted.is_empty() {
+      is_upper = true;
+    } else if is_upper {
+      converted.extend(c.to_uppercase());
+      is_upper = false;
+    } else {
+      converted.push(c);
+    }
+  }
+  converted
+}
+
+/// 構造体のフィールド名をcamelCaseにしたJSONの値にする
+pub(crate) fn to_camel_case_value<T: Serialize + ?Sized>(
+  value: &T,
+) -> Result<Value, serde_json::Error> {
+  value.serialize(CamelCaseSerializer)
+}
+
+fn variant_value(variant: &str, value: Value) -> Value {
+  let mut map = Map::new();
+  map.insert(variant.to_string(), value);
+  Value::Object(map)
+}
+
+struct CamelCaseSerializer;
+
+impl ser::Serializer for CamelCaseSerializer {
+  type Ok = Value;
+  type Error = serde_json::Error;
+  type SerializeSeq = SerializeVec;
+  type SerializeTuple = SerializeVec;
+  type SerializeTupleStruct = SerializeVec;
+  type SerializeTupleVariant = SerializeTupleVariant;
+  type SerializeMap = SerializeMap;
+  type SerializeStruct = SerializeStruct;
+  type SerializeStructVariant = SerializeStructVariant;
+
+  fn serialize_bool(self, v: bool) -> Result<Value, Self::Error> {
+    Ok(Value::Bool(v))
+  }
+  fn serialize_i8(self, v: i8) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_i16(self, v: i16) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_i32(self, v: i32) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_i64(self, v: i64) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_u8(self, v: u8) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_u16(self, v: u16) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_u32(self, v: u32) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_u64(self, v: u64) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_f32(self, v: f32) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_f64(self, v: f64) -> Result<Value, Self::Error> {
+    Ok(Value::from(v))
+  }
+  fn serialize_char(self, v: char) -> Result<Value, Self::Error> {
+    Ok(Value::String(v.to_string()))
+  }
+  fn serialize_str(self, v: &str) -> Result<Value, Self::Error> {
+    Ok(Value::String(v.to_string()))
+  }
+  fn serialize_bytes(self, v: &[u8]) -> Result<Value, Self::Error> {
+    Ok(Value::Array(v.iter().map(|&b| Value::from(b)).collect()))
+  }
+  fn serialize_none(self) -> Result<Value, Self::Error> {
+    Ok(Value::Null)
+  }
+  fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Self::Error> {
+    value.serialize(self)
+  }
+  fn serialize_unit(self) -> Result<Value, Self::Error> {
+    Ok(Value::Null)
+  }
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Self::Error> {
+    Ok(Value::Null)
+  }
+  fn serialize_unit_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+  ) -> Result<Value, Self::Error> {
+    Ok(Value::String(variant.to_string()))
+  }
+  fn serialize_newtype_struct<T: Serialize + ?Sized>(
+    self,
+    _name: &'static str,
+    value: &T,
+  ) -> Result<Value, Self::Error> {
+    value.serialize(self)
+  }
+  fn serialize_newtype_variant<T: Serialize + ?Sized>(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    value: &T,
+  ) -> Result<Value, Self::Error> {
+    Ok(variant_value(variant, value.serialize(self)?))
+  }
+  fn serialize_seq(self, len: Option<usize>) -> Result<SerializeVec, Self::Error> {
+    Ok(SerializeVec {
+      vec: Vec::with_capacity(len.unwrap_or(0)),
+    })
+  }
+  fn serialize_tuple(self, len: usize) -> Result<SerializeVec, Self::Error> {
+    self.serialize_seq(Some(len))
+  }
+  fn serialize_tuple_struct(
+    self,
+    _name: &'static str,
+    len: usize,
+  ) -> Result<SerializeVec, Self::Error> {
+    self.serialize_seq(Some(len))
+  }
+  fn serialize_tuple_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    len: usize,
+  ) -> Result<SerializeTupleVariant, Self::Error> {
+    Ok(SerializeTupleVariant {
+      variant,
+      vec: Vec::with_capacity(len),
+    })
+  }
+  fn serialize_map(self, _len: Option<usize>) -> Result<SerializeMap, Self::Error> {
+    Ok(SerializeMap {
+      map: Map::new(),
+      next_key: None,
+    })
+  }
+  fn serialize_struct(
+    self,
+    name: &'static str,
+    _len: usize,
+  ) -> Result<SerializeStruct, Self::Error> {
+    Ok(SerializeStruct {
+      map: Map::new(),
+      is_foreign: FOREIGN_STRUCTS.contains(&name),
+    })
+  }
+  fn serialize_struct_variant(
+    self,
+    _name: &'static str,
+    _variant_index: u32,
+    variant: &'static str,
+    _len: usize,
+  ) -> Result<SerializeStructVariant, Self::Error> {
+    Ok(SerializeStructVariant {
+      variant,
+      map: Map::new(),
+    })
+  }
+}
+
+struct SerializeVec {
+  vec: Vec<Value>,
+}
+
+impl ser::SerializeSeq for SerializeVec {
+  type Ok = Value;
+  type Error = serde_json::Error;
+  fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+    self.vec.push(to_camel_case_value(value)?);
+    Ok(())
+  }
+  fn end(self) -> Result<Value, Self::Error> {
+    Ok(Value::Array(self.vec))
+  }
+}
+
+impl ser::SerializeTuple for SerializeVec {
+  type Ok = Value;
+  type Error = serde_json::Error;
+  fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+    ser::SerializeSeq::serialize_element(self, value)
+  }
+  fn end(self) -> Result<Value, Self::Error> {
+    ser::SerializeSeq::end(self)
+  }
+}
+
+impl ser::SerializeTupleStruct for SerializeVec {
+  type Ok = Value;
+  type Error = serde_json::Error;
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+    ser::SerializeSeq::serialize_element(self, value)
+  }
+  fn end(self) -> Result<Value, Self::Error> {
+    ser::SerializeSeq::end(self)
+  }
+}
+
+struct SerializeTupleVariant {
+  variant: &'static str,
+  vec: Vec<Value>,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+  type Ok = Value;
+  type Error = serde_json::Error;
+  fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+    self.vec.push(to_camel_case_value(value)?);
+    Ok(())
+  }
+  fn end(self) -> Result<Value, Self::Error> {
+    Ok(variant_value(self.variant, Value::Array(self.vec)))
+  }
+}
+
+/// マップのキーはデータなので、名前を変えずに文字列にする
+struct SerializeMap {
+  map: Map<String, Value>,
+  next_key: Option<String>,
+}
+
+impl ser::SerializeMap for SerializeMap {
+  type Ok = Value;
+  type Error = serde_json::Error;
+  fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+    let key = match serde_json::to_value(key)? {
+      Value::String(s) => s,
+      Value::Number(n) => n.to_string(),
+      Value::Bool(b) => b.to_string(),
+      _ => {
+        return Err(<serde_json::Error as ser::Error>::custom(
+          "key must be a string",
+        ))
+      }
+    };
+    self.next_key = Some(key);
+    Ok(())
+  }
+  fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+    let key = self.next_key.take().ok_or_else(|| {
+      <serde_json::Error as ser::Error>::custom("serialize_value called before serialize_key")
+    })?;
+    self.map.insert(key, to_camel_case_value(value)?);
+    Ok(())
+  }
+  fn end(self) -> Result<Value, Self::Error> {
+    Ok(Value::Object(self.map))
+  }
+}
+
+struct SerializeStruct {
+  map: Map<String, Value>,
+  /// jplaw_textの型の場合は、中身も含めて名前を変えない
+  is_foreign: bool,
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+  type Ok = Value;
+  type Error = serde_json::Error;
+  fn serialize_field<T: Serialize + ?Sized>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<(), Self::Error> {
+    if self.is_foreign {
+      self
+        .map
+        .insert(key.to_string(), serde_json::to_value(value)?);
+    } else {
+      self
+        .map
+        .insert(to_camel_case(key), to_camel_case_value(value)?);
+    }
+    Ok(())
+  }
+  fn end(self) -> Result<Value, Self::Error> {
+    Ok(Value::Object(self.map))
+  }
+}
+
+struct SerializeStructVariant {
+  variant: &'static str,
+  map: Map<String, Value>,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+  type Ok = Value;
+  type Error = serde_json::Error;
+  fn serialize_field<T: Serialize + ?Sized>(
+    &mut self,
+    key: &'static str,
+    value: &T,
+  ) -> Result<(), Self::Error> {
+    self
+      .map
+      .insert(to_camel_case(key), to_camel_case_value(value)?);
+    Ok(())
+  }
+  fn end(self) -> Result<Value, Self::Error> {
+    Ok(variant_value(self.variant, Value::Object(self.map)))
+  }
+}
+
+#[test]
+fn check_to_camel_case() {
+  assert_eq!("beforeWords", to_camel_case("before_words"));
+  assert_eq!("num", to_camel_case("num"));
+}
+
+#[test]
+fn check_to_camel_case_value() {
+  use std::collections::BTreeMap;
+  #[derive(Serialize)]
+  struct Inner {
+    law_num: String,
+  }
+  #[derive(Serialize)]
+  struct Outer {
+    inner_lst: Vec<Inner>,
+    count_by_law: BTreeMap<String, usize>,
+  }
+  let value = Outer {
+    inner_lst: vec![Inner {
+      law_num: "test".to_string(),
+    }],
+    count_by_law: BTreeMap::from([("law_num".to_string(), 1)]),
+  };
+  assert_eq!(
+    serde_json::json!({
+      "innerLst": [{ "lawNum": "test" }],
+      "countByLaw": { "law_num": 1 }
+    }),
+    to_camel_case_value(&value).unwrap()
+  );
+}
@@ -0,0 +1,118 @@
+//! 条項ごとの解析結果を、読み替え前の語一つごとの平坦な形にする
+
+use crate::{
+  LawNum, LawRef, QualifierRef, TableCellRef, TargetRef, YomikaeConfidence, YomikaeData,
+  YomikaeOperation,
+};
+use jplaw_text::Article;
+use serde::{Deserialize, Serialize};
+
+/// 法令・条項・読み替え前の語・読み替え後の語の組ごとの解析結果
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FlatYomikaeRecord {
+  /// 法律番号
+  pub num: LawNum,
+  /// その読み替え規定がある条項
+  pub article: Article,
+  /// 読み替えられる前の単語
+  #[serde(alias = "beforeWord")]
+  pub before_word: String,
+  /// 読み替えられた後の単語
+  #[serde(alias = "afterWord")]
+  pub after_word: String,
+  /// 読み替えの種類
+  #[serde(default)]
+  pub operation: YomikaeOperation,
+  /// 取り出した結果の確からしさ
+  #[serde(default, skip_serializing_if = "YomikaeConfidence::is_high")]
+  pub confidence: YomikaeConfidence,
+  /// 読み替え後の語の中で、経過措置などの規定を参照している箇所
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub qualifiers: Vec<QualifierRef>,
+  /// 読み替え後の語の中で、法律番号を付けて引用している他の法令
+  #[serde(default, alias = "lawRefs", skip_serializing_if = "Vec::is_empty")]
+  pub law_refs: Vec<LawRef>,
+  /// 読み替えの対象となる規定
+  /// 読み替え前の語ごとに対象が違う場合は、その語の対象
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub target: Option<TargetRef>,
+  /// 表から取り出した場合の、元の表の中での行と列の位置
+  #[serde(default, alias = "tableCell", skip_serializing_if = "Option::is_none")]
+  pub table_cell: Option<TableCellRef>,
+  /// その読み替え規定がある条項のe-Gov法令検索のURL
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub url: Option<String>,
+  /// 元の法令XMLファイル
+  #[serde(default, alias = "sourceFile", skip_serializing_if = "Option::is_none")]
+  pub source_file: Option<String>,
+  /// 法令XMLの中での条項の要素のパス
+  #[serde(default, alias = "xmlPath", skip_serializing_if = "Option::is_none")]
+  pub xml_path: Option<String>,
+  /// 元の条文の内容のハッシュ値
+  #[serde(
+    default,
+    alias = "contentHash",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub content_hash: Option<String>,
+}
+
+/// 条項ごとの解析結果を、読み替え前の語一つごとの平坦な形にする
+/// 読み替え前の語が無い組（[`YomikaeOperation::Insert`]）は、読み替え前の語を空文字列にした一件にする
+pub fn flatten_yomikae_data(yomikae_data: &YomikaeData) -> Vec<FlatYomikaeRecord> {
+  let mut records = Vec::new();
+  for yomikae_info in yomikae_data.data.iter() {
+    let empty = [String::new()];
+    let before_words = if yomikae_info.before_words.is_empty() {
+      &empty[..]
+    } else {
+      &yomikae_info.before_words[..]
+    };
+    for (i, before_word) in before_words.iter().enumerate() {
+      let target = match yomikae_info.scoped_before_words.get(i) {
+        Some(scoped_word) => scoped_word.scope.clone(),
+        None => yomikae_info.target.clone(),
+      };
+      records.push(FlatYomikaeRecord {
+        num: yomikae_data.num.clone(),
+        article: yomikae_data.article.clone(),
+        before_word: before_word.clone(),
+        after_word: yomikae_info.after_word.clone(),
+        operation: yomikae_info.operation,
+        confidence: yomikae_info.confidence,
+        qualifiers: yomikae_info.qualifiers.clone(),
+        law_refs: yomikae_info.law_refs.clone(),
+        target,
+        table_cell: yomikae_info.table_cell,
+        url: yomikae_data.url.clone(),
+        source_file: yomikae_data.source_file.clone(),
+        xml_path: yomikae_data.xml_path.clone(),
+        content_hash: yomikae_data.content_hash.clone(),
+      });
+    }
+  }
+  records
+}
+
+#[test]
+fn check_flatten_yomikae_data() {
+  use crate::{ArticleExt, YomikaeInfo};
+  let yomikae_data = YomikaeData::new(
+    "test",
+    Article::article("1"),
+    vec![
+      YomikaeInfo::new(["都道府県知事", "知事"], "市長"),
+      YomikaeInfo::new(Vec::<String>::new(), "第二項"),
+    ],
+  );
+  let records = flatten_yomikae_data(&yomikae_data);
+  assert_eq!(
+    vec![("都道府県知事", "市長"), ("知事", "市長"), ("", "第二項")],
+    records
+      .iter()
+      .map(|record| (record.before_word.as_str(), record.after_word.as_str()))
+      .collect::<Vec<_>>()
+  );
+  assert!(records.iter().all(|record| record.num == "test"));
+  assert_eq!(YomikaeOperation::Insert, records[2].operation);
+}
@@ -0,0 +1,235 @@
+//! 同じ法令の中で、文と表の両方に書かれている読み替えを比べる
+//!
+//! 文で読み替えを書いた上で、同じ読み替えを表にも書いている法令がある
+//! 両方から取り出した読み替えの組のうち、同じ語を同じように読み替えているものは重複として、
+//! 違うように読み替えているものは食い違いとして報告する
+
+use crate::{flatten_yomikae_data, FlatYomikaeRecord, Message, TargetRef, YomikaeData};
+use jplaw_text::Article;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// 文と表の両方に書かれていた、同じ読み替え
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FormDuplicate {
+  /// 法律番号
+  pub num: String,
+  /// 読み替え前の語
+  pub before_word: String,
+  /// 読み替え後の語
+  pub after_word: String,
+  /// 文で書かれていた条項
+  pub sentence_article: Article,
+  /// 表で書かれていた条項
+  pub table_article: Article,
+}
+
+/// 文と表とで、同じ語が違うように読み替えられていたもの
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct FormConflict {
+  /// 法律番号
+  pub num: String,
+  /// 読み替え前の語
+  pub before_word: String,
+  /// 文で書かれていた条項
+  pub sentence_article: Article,
+  /// 文での読み替え後の語
+  pub sentence_after_word: String,
+  /// 表で書かれていた条項
+  pub table_article: Article,
+  /// 表での読み替え後の語
+  pub table_after_word: String,
+}
+
+/// 一つの法令についての、文と表の比較の結果
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct FormComparison {
+  /// 法律番号
+  pub num: String,
+  /// 文と表の両方に書かれていた、同じ読み替え
+  pub duplicates: Vec<FormDuplicate>,
+  /// 文と表とで食い違っていた読み替え
+  pub conflicts: Vec<FormConflict>,
+}
+
+/// 両方に対象の規定が書かれていて、それが違う場合は別の規定についての読み替えとみなす
+fn is_same_target(a: &Option<TargetRef>, b: &Option<TargetRef>) -> bool {
+  match (a, b) {
+    (Some(a), Some(b)) => a == b,
+    _ => true,
+  }
+}
+
+/// 法令ごとに、文から取り出した読み替えの組と表から取り出した読み替えの組を比べる
+/// 重複も食い違いも無かった法令は含めない
+pub fn compare_forms(yomikae_data_lst: &[YomikaeData]) -> Vec<FormComparison> {
+  // 法律番号 => (文から取り出したもの, 表から取り出したもの)
+  let mut law_records: BTreeMap<&str, (Vec<FlatYomikaeRecord>, Vec<FlatYomikaeRecord>)> =
+    BTreeMap::new();
+  for yomikae_data in yomikae_data_lst.iter() {
+    let (sentence_records, table_records) =
+      law_records.entry(yomikae_data.num.as_str()).or_default();
+    for record in flatten_yomikae_data(yomikae_data) {
+      if record.before_word.is_empty() {
+        continue;
+      }
+      if record.table_cell.is_some() {
+        table_records.push(record);
+      } else {
+        sentence_records.push(record);
+      }
+    }
+  }
+  let mut comparison_lst = Vec::new();
+  for (num, (sentence_records, table_records)) in law_records {
+    let mut table_map: HashMap<&str, Vec<&FlatYomikaeRecord>> = HashMap::new();
+    for record in table_records.iter() {
+      table_map
+        .entry(record.before_word.as_str())
+        .or_default()
+        .push(record);
+    }
+    let mut comparison = FormComparison {
+      num: num.to_string(),
+      ..Default::default()
+    };
+    for sentence_record in sentence_records.iter() {
+      let matched = table_map
+        .get(sentence_record.before_word.as_str())
+        .into_iter()
+        .flatten()
+        .filter(|table_record| is_same_target(&sentence_record.target, &table_record.target));
+      for table_record in matched {
+        if sentence_record.after_word == table_record.after_word {
+          comparison.duplicates.push(FormDuplicate {
+            num: num.to_string(),
+            before_word: sentence_record.before_word.clone(),
+            after_word: sentence_record.after_word.clone(),
+            sentence_article: sentence_record.article.clone(),
+            table_article: table_record.article.clone(),
+          });
+        } else {
+          comparison.conflicts.push(FormConflict {
+            num: num.to_string(),
+            before_word: sentence_record.before_word.clone(),
+            sentence_article: sentence_record.article.clone(),
+            sentence_after_word: sentence_record.after_word.clone(),
+            table_article: table_record.article.clone(),
+            table_after_word: table_record.after_word.clone(),
+          });
+        }
+      }
+    }
+    if !comparison.duplicates.is_empty() || !comparison.conflicts.is_empty() {
+      comparison_lst.push(comparison);
+    }
+  }
+  comparison_lst
+}
+
+/// 文と表とで食い違っていた読み替えごとの警告のメッセージ
+pub fn form_conflict_messages(yomikae_data_lst: &[YomikaeData]) -> Vec<Message> {
+  compare_forms(yomikae_data_lst)
+    .into_iter()
+    .flat_map(|comparison| comparison.conflicts)
+    .map(Message::FormConflict)
+    .collect()
+}
+
+/// 文から取り出した読み替えの組のうち、全ての読み替え前の語が表にも同じように書かれているものを取り除く
+/// 表から取り出したものは表の中での位置を持っているので、そちらを残す
+/// 読み替えの組が無くなった条項は取り除く
+pub fn reconcile_forms(
+  yomikae_data_lst: Vec<YomikaeData>,
+) -> (Vec<YomikaeData>, Vec<FormComparison>) {
+  let comparison_lst = compare_forms(&yomikae_data_lst);
+  let duplicated = comparison_lst
+    .iter()
+    .flat_map(|comparison| comparison.duplicates.iter())
+    .map(|duplicate| {
+      (
+        duplicate.num.as_str(),
+        &duplicate.sentence_article,
+        duplicate.before_word.as_str(),
+        duplicate.after_word.as_str(),
+      )
+    })
+    .collect::<HashSet<_>>();
+  let reconciled_lst = yomikae_data_lst
+    .iter()
+    .map(|yomikae_data| YomikaeData {
+      data: yomikae_data
+        .data
+        .iter()
+        .filter(|yomikae_info| {
+          yomikae_info.table_cell.is_some()
+            || yomikae_info.before_words.is_empty()
+            || !yomikae_info.before_words.iter().all(|before_word| {
+              duplicated.contains(&(
+                yomikae_data.num.as_str(),
+                &yomikae_data.article,
+                before_word.as_str(),
+                yomikae_info.after_word.as_str(),
+              ))
+            })
+        })
+        .cloned()
+        .collect(),
+      ..yomikae_data.clone()
+    })
+    .filter(|yomikae_data| !yomikae_data.data.is_empty())
+    .collect();
+  (reconciled_lst, comparison_lst)
+}
+
+#[test]
+fn check_compare_forms() {
+  use crate::{ArticleExt, TableCellRef, YomikaeInfo};
+  let table_cell = |row| TableCellRef {
+    row,
+    before_column: 1,
+    after_column: 2,
+  };
+  let yomikae_data_lst = vec![
+    YomikaeData::new(
+      "test",
+      Article::article("2"),
+      vec![
+        YomikaeInfo::new(["都道府県知事"], "市長"),
+        YomikaeInfo::new(["都道府県"], "市"),
+      ],
+    ),
+    YomikaeData::new(
+      "test",
+      Article::article("3"),
+      vec![
+        YomikaeInfo {
+          table_cell: Some(table_cell(0)),
+          ..YomikaeInfo::new(["都道府県知事"], "市長")
+        },
+        YomikaeInfo {
+          table_cell: Some(table_cell(1)),
+          ..YomikaeInfo::new(["都道府県"], "市町村")
+        },
+      ],
+    ),
+    YomikaeData::new(
+      "other",
+      Article::article("1"),
+      vec![YomikaeInfo::new(["都道府県"], "市")],
+    ),
+  ];
+  let comparison_lst = compare_forms(&yomikae_data_lst);
+  assert_eq!(1, comparison_lst.len());
+  assert_eq!(1, comparison_lst[0].duplicates.len());
+  assert_eq!("都道府県知事", comparison_lst[0].duplicates[0].before_word);
+  assert_eq!(1, comparison_lst[0].conflicts.len());
+  assert_eq!("市町村", comparison_lst[0].conflicts[0].table_after_word);
+  assert_eq!(1, form_conflict_messages(&yomikae_data_lst).len());
+  let (reconciled_lst, _) = reconcile_forms(yomikae_data_lst);
+  assert_eq!(3, reconciled_lst.len());
+  assert_eq!(
+    vec![YomikaeInfo::new(["都道府県"], "市")],
+    reconciled_lst[0].data
+  );
+}
@@ -0,0 +1,439 @@
+//! 解析結果をGraphQLで引けるようにする
+//!
+//! `graphql` featureを有効にすると使えるようになる
+//!
+//! - `POST /graphql`：GraphQLのクエリを実行する
+//! - `GET /graphql`：クエリを試すためのGraphiQLを返す
+//!
+//! 法令（`laws`・`law`）、条項（`Law.articles`）、読み替えの組（`pairs`）、エラー（`errors`）、
+//! 規定の間の読み替えの関係（`dependencies`・`readers`）を引ける
+
+use crate::{
+  article_to_string, flatten_yomikae_data, FlatYomikaeRecord, YomikaeConfidence, YomikaeData,
+  YomikaeDataSet, YomikaeError, YomikaeOperation,
+};
+use async_graphql::{
+  http::GraphiQLSource, EmptyMutation, EmptySubscription, Enum, Object, Schema, SimpleObject,
+};
+use async_graphql_axum::GraphQL;
+use axum::{response::Html, routing::get, Router};
+use std::sync::Arc;
+
+/// GraphQLのスキーマ
+pub type YomikaeSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// クエリで引く解析結果とエラー
+#[derive(Debug, Clone, Default)]
+struct Store {
+  dataset: YomikaeDataSet,
+  errors: Vec<YomikaeError>,
+}
+
+/// 読み替えの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Operation {
+  Replace,
+  Delete,
+  Insert,
+}
+
+impl From<YomikaeOperation> for Operation {
+  fn from(operation: YomikaeOperation) -> Self {
+    match operation {
+      YomikaeOperation::Replace => Operation::Replace,
+      YomikaeOperation::Delete => Operation::Delete,
+      YomikaeOperation::Insert => Operation::Insert,
+    }
+  }
+}
+
+/// 取り出した読み替えの組の確からしさ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum Confidence {
+  High,
+  Low,
+}
+
+impl From<YomikaeConfidence> for Confidence {
+  fn from(confidence: YomikaeConfidence) -> Self {
+    match confidence {
+      YomikaeConfidence::High => Confidence::High,
+      YomikaeConfidence::Low => Confidence::Low,
+    }
+  }
+}
+
+/// 読み替え前の語一つごとの読み替えの組
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+pub struct Pair {
+  /// 法律番号
+  pub num: String,
+  /// その読み替え規定がある条項
+  pub article: String,
+  /// 読み替えられる前の単語
+  pub before_word: String,
+  /// 読み替えられた後の単語
+  pub after_word: String,
+  /// 読み替えの種類
+  pub operation: Operation,
+  /// 取り出した結果の確からしさ
+  pub confidence: Confidence,
+  /// 読み替えの対象となる法令の名前（書かれていない場合は`null`）
+  pub target_law: Option<String>,
+  /// 読み替えの対象となる規定（書かれていない場合は`null`）
+  pub target_provision: Option<String>,
+}
+
+impl From<FlatYomikaeRecord> for Pair {
+  fn from(record: FlatYomikaeRecord) -> Self {
+    let (target_law, target_provision) = match record.target {
+      Some(target) => (target.law, Some(target.provision)),
+      None => (None, None),
+    };
+    Pair {
+      num: record.num.into(),
+      article: article_to_string(&record.article),
+      before_word: record.before_word,
+      after_word: record.after_word,
+      operation: record.operation.into(),
+      confidence: record.confidence.into(),
+      target_law,
+      target_provision,
+    }
+  }
+}
+
+/// 解析に失敗した条文や法令ファイルのエラー
+#[derive(Debug, Clone, PartialEq, Eq, SimpleObject)]
+pub struct ErrorEntry {
+  /// 法律番号
+  pub num: String,
+  /// エラーが起きた条項（法令ファイルについてのエラーの場合は`null`）
+  pub article: Option<String>,
+  /// 条文の中でエラーが起きた文の番号
+  pub sentence_index: Option<usize>,
+  /// エラーの種類（`unmatched_paren`など）
+  pub kind: String,
+  /// エラーの内容
+  pub message: String,
+}
+
+impl From<&YomikaeError> for ErrorEntry {
+  fn from(err: &YomikaeError) -> Self {
+    let key = err.key();
+    ErrorEntry {
+      num: key.law,
+      article: key.article.as_ref().map(article_to_string),
+      sentence_index: key.sentence_index,
+      kind: key.code,
+      message: err.to_string(),
+    }
+  }
+}
+
+/// 読み替えている規定から、読み替えの対象となる規定への関係
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SimpleObject)]
+pub struct Dependency {
+  /// 読み替えている法令の法律番号
+  pub num: String,
+  /// 読み替えている条項
+  pub article: String,
+  /// 読み替えの対象となる法令の名前（同じ法令の規定が対象の場合は`null`）
+  pub target_law: Option<String>,
+  /// 読み替えの対象となる規定
+  pub target_provision: String,
+}
+
+/// 条項ごとの解析結果
+pub struct ArticleNode {
+  yomikae_data: YomikaeData,
+}
+
+#[Object]
+impl ArticleNode {
+  /// 法律番号
+  async fn num(&self) -> &str {
+    &self.yomikae_data.num
+  }
+
+  /// 条項
+  async fn article(&self) -> String {
+    article_to_string(&self.yomikae_data.article)
+  }
+
+  /// 条項のe-Gov法令検索のURL
+  async fn url(&self) -> Option<&str> {
+    self.yomikae_data.url.as_deref()
+  }
+
+  /// 読み替えの組
+  async fn pairs(&self) -> Vec<Pair> {
+    flatten_yomikae_data(&self.yomikae_data)
+      .into_iter()
+      .map(Pair::from)
+      .collect()
+  }
+
+  /// この条項が読み替えている規定
+  async fn dependencies(&self) -> Vec<Dependency> {
+    dependencies_of(&self.yomikae_data)
+  }
+}
+
+/// 法令ごとの解析結果
+pub struct Law {
+  store: Arc<Store>,
+  num: String,
+}
+
+impl Law {
+  fn yomikae_data_lst(&self) -> impl Iterator<Item = &YomikaeData> {
+    self
+      .store
+      .dataset
+      .data
+      .iter()
+      .filter(move |yomikae_data| yomikae_data.num == self.num)
+  }
+}
+
+#[Object]
+impl Law {
+  /// 法律番号
+  async fn num(&self) -> &str {
+    &self.num
+  }
+
+  /// 読み替えの組が取り出せた条項
+  async fn articles(&self) -> Vec<ArticleNode> {
+    self
+      .yomikae_data_lst()
+      .map(|yomikae_data| ArticleNode {
+        yomikae_data: yomikae_data.clone(),
+      })
+      .collect()
+  }
+
+  /// 解析に失敗した条文や法令ファイルのエラー
+  async fn errors(&self) -> Vec<ErrorEntry> {
+    self
+      .store
+      .errors
+      .iter()
+      .map(ErrorEntry::from)
+      .filter(|entry| entry.num == self.num)
+      .collect()
+  }
+
+  /// この法令の規定が読み替えている規定
+  async fn dependencies(&self) -> Vec<Dependency> {
+    self.yomikae_data_lst().flat_map(dependencies_of).collect()
+  }
+}
+
+/// 条項が読み替えている規定を、重複を除いて出てきた順に返す
+fn dependencies_of(yomikae_data: &YomikaeData) -> Vec<Dependency> {
+  let mut dependencies: Vec<Dependency> = Vec::new();
+  for record in flatten_yomikae_data(yomikae_data) {
+    if let Some(target) = record.target {
+      let dependency = Dependency {
+        num: record.num.into(),
+        article: article_to_string(&record.article),
+        target_law: target.law,
+        target_provision: target.provision,
+      };
+      if !dependencies.contains(&dependency) {
+        dependencies.push(dependency);
+      }
+    }
+  }
+  dependencies
+}
+
+/// クエリの起点
+pub struct QueryRoot {
+  store: Arc<Store>,
+}
+
+#[Object]
+impl QueryRoot {
+  /// 解析結果かエラーのある法令（出てきた順）
+  async fn laws(&self) -> Vec<Law> {
+    let mut nums: Vec<String> = Vec::new();
+    let data_nums = self
+      .store
+      .dataset
+      .data
+      .iter()
+      .map(|data| data.num.to_string());
+    let error_nums = self.store.errors.iter().map(|err| err.key().law);
+    for num in data_nums.chain(error_nums) {
+      if !nums.contains(&num) {
+        nums.push(num);
+      }
+    }
+    nums
+      .into_iter()
+      .map(|num| Law {
+        store: self.store.clone(),
+        num,
+      })
+      .collect()
+  }
+
+  /// 法律番号で法令を引く
+  async fn law(&self, num: String) -> Option<Law> {
+    let has_data = self.store.dataset.data.iter().any(|data| data.num == num);
+    let has_errors = self.store.errors.iter().any(|err| err.key().law == num);
+    if has_data || has_errors {
+      Some(Law {
+        store: self.store.clone(),
+        num,
+      })
+    } else {
+      None
+    }
+  }
+
+  /// 法律番号が一致し、読み替え前後の語に`word`を含む読み替えの組
+  async fn pairs(&self, num: Option<String>, word: Option<String>) -> Vec<Pair> {
+    self
+      .store
+      .dataset
+      .data
+      .iter()
+      .filter(|yomikae_data| num.as_ref().map_or(true, |num| &yomikae_data.num == num))
+      .flat_map(flatten_yomikae_data)
+      .filter(|record| {
+        word.as_ref().map_or(true, |word| {
+          record.before_word.contains(word.as_str()) || record.after_word.contains(word.as_str())
+        })
+      })
+      .map(Pair::from)
+      .collect()
+  }
+
+  /// 法律番号とエラーの種類（`unmatched_paren`など）が一致するエラー
+  async fn errors(&self, num: Option<String>, kind: Option<String>) -> Vec<ErrorEntry> {
+    self
+      .store
+      .errors
+      .iter()
+      .map(ErrorEntry::from)
+      .filter(|entry| num.as_ref().map_or(true, |num| &entry.num == num))
+      .filter(|entry| kind.as_ref().map_or(true, |kind| &entry.kind == kind))
+      .collect()
+  }
+
+  /// 規定の間の読み替えの関係
+  /// `targetLaw`を指定した場合は、その名前の法令の規定を対象にしているものだけを返す
+  async fn dependencies(&self, num: Option<String>, target_law: Option<String>) -> Vec<Dependency> {
+    self
+      .store
+      .dataset
+      .data
+      .iter()
+      .filter(|yomikae_data| num.as_ref().map_or(true, |num| &yomikae_data.num == num))
+      .flat_map(dependencies_of)
+      .filter(|dependency| {
+        target_law
+          .as_ref()
+          .map_or(true, |law| dependency.target_law.as_ref() == Some(law))
+      })
+      .collect()
+  }
+
+  /// 法令`law`の条項`article`を読み替えている条項（`YomikaeDataSet::readers_of`と同じ）
+  async fn readers(&self, law: String, article: String) -> Vec<ArticleNode> {
+    self
+      .store
+      .dataset
+      .readers_of(&law, &article)
+      .into_iter()
+      .map(|reader| ArticleNode {
+        yomikae_data: YomikaeData::new(reader.num, reader.article, reader.pairs),
+      })
+      .collect()
+  }
+}
+
+/// 解析結果とエラーを引くスキーマを作る
+pub fn schema(dataset: Vec<YomikaeData>, errors: Vec<YomikaeError>) -> YomikaeSchema {
+  let store = Store {
+    dataset: YomikaeDataSet::new(dataset),
+    errors,
+  };
+  Schema::build(
+    QueryRoot {
+      store: Arc::new(store),
+    },
+    EmptyMutation,
+    EmptySubscription,
+  )
+  .finish()
+}
+
+async fn graphiql() -> Html<String> {
+  Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+/// `/graphql`のルーティングを作る
+/// [`crate::server::router`]と`merge`して使う
+pub fn router(dataset: Vec<YomikaeData>, errors: Vec<YomikaeError>) -> Router {
+  Router::new().route(
+    "/graphql",
+    get(graphiql).post_service(GraphQL::new(schema(dataset, errors))),
+  )
+}
+
+#[tokio::test]
+async fn check_schema() {
+  use crate::{test_utils, ArticleExt, LawInfo, TargetRef, YomikaeInfo};
+  use jplaw_text::Article;
+  let dataset = vec![
+    YomikaeData::new(
+      "a",
+      Article::article("1"),
+      vec![YomikaeInfo::new(["前項"], "第二項")
+        .with_target(TargetRef::provision("第八百五十一条").with_law("民法"))],
+    ),
+    YomikaeData::new(
+      "b",
+      Article::article("2"),
+      vec![YomikaeInfo::new(["前条"], "第一条")],
+    ),
+  ];
+  let law_info = LawInfo::new(
+    "c",
+    test_utils::test_article(),
+    test_utils::law_text("「前項」」とあるのは「次項」と読み替える。"),
+  );
+  let schema = schema(dataset, vec![YomikaeError::UnmatchedParen(law_info)]);
+  let res = schema
+    .execute(
+      r#"{
+        laws { num }
+        law(num: "a") { articles { pairs { beforeWord afterWord targetLaw } } }
+        errors(kind: "unmatched_paren") { num }
+        dependencies(targetLaw: "民法") { num targetProvision }
+        readers(law: "民法", article: "第八百五十一条") { num }
+      }"#,
+    )
+    .await;
+  assert!(res.errors.is_empty(), "{:?}", res.errors);
+  let json = res.data.into_json().unwrap();
+  assert_eq!(
+    serde_json::json!([{ "num": "a" }, { "num": "b" }, { "num": "c" }]),
+    json["laws"]
+  );
+  assert_eq!(
+    serde_json::json!({ "beforeWord": "前項", "afterWord": "第二項", "targetLaw": "民法" }),
+    json["law"]["articles"][0]["pairs"][0]
+  );
+  assert_eq!(serde_json::json!([{ "num": "c" }]), json["errors"]);
+  assert_eq!(
+    serde_json::json!([{ "num": "a", "targetProvision": "第八百五十一条" }]),
+    json["dependencies"]
+  );
+  assert_eq!(serde_json::json!([{ "num": "a" }]), json["readers"]);
+}
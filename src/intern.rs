@@ -0,0 +1,175 @@
+//! 解析結果をメモリにためておくときに、繰り返し現れる文字列を共有する
+//!
+//! 法令全体を解析すると、法律番号や法令XMLファイル名、読み替え前後の語は何千回も同じものが現れる
+//! 出力するときは[`YomikaeData`]に戻すので、シリアライズした形式は変わらない
+
+use crate::{
+  cmp_article, LawNum, LawRef, LawRevision, QualifierRef, ScopedWord, TableCellRef, TargetRef,
+  YomikaeConfidence, YomikaeData, YomikaeInfo, YomikaeOperation,
+};
+use jplaw_text::Article;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 同じ内容の文字列を一つの[`Arc<str>`]にまとめる
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+  strings: HashSet<Arc<str>>,
+}
+
+impl Interner {
+  /// 同じ内容の文字列が既にあればそれを、なければ新しく登録したものを返す
+  pub fn intern(&mut self, s: String) -> Arc<str> {
+    if let Some(interned) = self.strings.get(s.as_str()) {
+      return interned.clone();
+    }
+    let interned: Arc<str> = Arc::from(s);
+    self.strings.insert(interned.clone());
+    interned
+  }
+
+  /// 登録されている文字列の数
+  pub fn len(&self) -> usize {
+    self.strings.len()
+  }
+
+  /// 文字列が一つも登録されていないかどうか
+  pub fn is_empty(&self) -> bool {
+    self.strings.is_empty()
+  }
+
+  /// 読み替え前後の語を共有する形にする
+  pub fn intern_yomikae_info(&mut self, yomikae_info: YomikaeInfo) -> InternedYomikaeInfo {
+    InternedYomikaeInfo {
+      before_words: yomikae_info
+        .before_words
+        .into_iter()
+        .map(|s| self.intern(s))
+        .collect(),
+      after_word: self.intern(yomikae_info.after_word),
+      operation: yomikae_info.operation,
+      confidence: yomikae_info.confidence,
+      qualifiers: yomikae_info.qualifiers,
+      law_refs: yomikae_info.law_refs,
+      target: yomikae_info.target,
+      scoped_before_words: yomikae_info.scoped_before_words,
+      table_cell: yomikae_info.table_cell,
+    }
+  }
+
+  /// 法律番号・法令XMLファイル名・法令名・読み替え前後の語を共有する形にする
+  pub fn intern_yomikae_data(&mut self, yomikae_data: YomikaeData) -> InternedYomikaeData {
+    InternedYomikaeData {
+      num: self.intern(yomikae_data.num.into()),
+      article: yomikae_data.article,
+      data: yomikae_data
+        .data
+        .into_iter()
+        .map(|yomikae_info| self.intern_yomikae_info(yomikae_info))
+        .collect(),
+      url: yomikae_data.url,
+      source_file: yomikae_data.source_file.map(|s| self.intern(s)),
+      xml_path: yomikae_data.xml_path,
+      content_hash: yomikae_data.content_hash,
+      revision: yomikae_data.revision,
+      title: yomikae_data.title.map(|s| self.intern(s)),
+    }
+  }
+}
+
+/// 文字列を共有する形にした[`YomikaeInfo`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InternedYomikaeInfo {
+  pub before_words: Vec<Arc<str>>,
+  pub after_word: Arc<str>,
+  pub operation: YomikaeOperation,
+  pub confidence: YomikaeConfidence,
+  pub qualifiers: Vec<QualifierRef>,
+  pub law_refs: Vec<LawRef>,
+  pub target: Option<TargetRef>,
+  pub scoped_before_words: Vec<ScopedWord>,
+  pub table_cell: Option<TableCellRef>,
+}
+
+impl From<InternedYomikaeInfo> for YomikaeInfo {
+  fn from(interned: InternedYomikaeInfo) -> Self {
+    YomikaeInfo {
+      before_words: interned
+        .before_words
+        .iter()
+        .map(|s| s.to_string())
+        .collect(),
+      after_word: interned.after_word.to_string(),
+      operation: interned.operation,
+      confidence: interned.confidence,
+      qualifiers: interned.qualifiers,
+      law_refs: interned.law_refs,
+      target: interned.target,
+      scoped_before_words: interned.scoped_before_words,
+      table_cell: interned.table_cell,
+    }
+  }
+}
+
+/// 文字列を共有する形にした[`YomikaeData`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InternedYomikaeData {
+  pub num: Arc<str>,
+  pub article: Article,
+  pub data: Vec<InternedYomikaeInfo>,
+  pub url: Option<String>,
+  pub source_file: Option<Arc<str>>,
+  pub xml_path: Option<String>,
+  pub content_hash: Option<String>,
+  pub revision: Option<LawRevision>,
+  pub title: Option<Arc<str>>,
+}
+
+impl From<InternedYomikaeData> for YomikaeData {
+  fn from(interned: InternedYomikaeData) -> Self {
+    YomikaeData {
+      num: LawNum::new(interned.num),
+      article: interned.article,
+      data: interned.data.into_iter().map(YomikaeInfo::from).collect(),
+      url: interned.url,
+      source_file: interned.source_file.map(|s| s.to_string()),
+      xml_path: interned.xml_path,
+      content_hash: interned.content_hash,
+      revision: interned.revision,
+      title: interned.title.map(|s| s.to_string()),
+    }
+  }
+}
+
+/// [`crate::sort_yomikae_data`]と同じく、法律番号、版、条項の順に並べ替える
+pub fn sort_interned_yomikae_data(yomikae_data_lst: &mut [InternedYomikaeData]) {
+  yomikae_data_lst.sort_by(|a, b| {
+    a.num
+      .cmp(&b.num)
+      .then_with(|| a.revision.cmp(&b.revision))
+      .then_with(|| cmp_article(&a.article, &b.article))
+  });
+}
+
+#[test]
+fn check_intern_yomikae_data() {
+  use crate::test_utils;
+  let yomikae_data = |after_word: &str| {
+    YomikaeData::new(
+      "test",
+      test_utils::test_article(),
+      vec![YomikaeInfo::new(["都道府県知事"], after_word)],
+    )
+    .with_source("test.xml")
+  };
+  let mut interner = Interner::default();
+  let a = interner.intern_yomikae_data(yomikae_data("市町村長"));
+  let b = interner.intern_yomikae_data(yomikae_data("市長"));
+  assert!(Arc::ptr_eq(&a.num, &b.num));
+  assert!(Arc::ptr_eq(
+    &a.data[0].before_words[0],
+    &b.data[0].before_words[0]
+  ));
+  assert_eq!(5, interner.len());
+  assert_eq!(yomikae_data("市町村長"), YomikaeData::from(a));
+}
@@ -0,0 +1,197 @@
+//! 改め文を解析し、改正の対象となる文言と改正後の文言を取り出す
+
+use crate::segment::{quoted_with_following_text, split_segments};
+use crate::{LawInfo, YomikaeError};
+use jplaw_text::{Article, LawContents, LawText};
+use serde::{Deserialize, Serialize};
+use tracing::*;
+
+/// 文言を加える位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum InsertPosition {
+  /// 「〜」の下に・「〜」の次に
+  After,
+  /// 「〜」の上に・「〜」の前に
+  Before,
+}
+
+/// 改め文による一つの改正内容
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum KaiseiInfo {
+  /// 「〜」を「〜」に改める
+  Replace { before: String, after: String },
+  /// 「〜」を削る
+  Delete { target: String },
+  /// 「〜」の下に「〜」を加える
+  Insert {
+    anchor: String,
+    position: InsertPosition,
+    word: String,
+  },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct KaiseiData {
+  /// 法律番号
+  pub num: String,
+  /// その改め文がある条項
+  pub article: Article,
+  /// 改正内容のリスト
+  pub data: Vec<KaiseiInfo>,
+}
+
+/// 改め文は
+/// 「「〜」を「〜」に(、「〜」を「〜」に)*改める」
+/// 「「〜」(及び「〜」)*を削る」
+/// 「「〜」の下に「〜」を(、「〜」の下に「〜」を)*加える」
+/// のような形になっており、これらが「改め、」「削り、」「加え、」でつながっている
+pub fn parse_kaiseibun(
+  law_text: &LawText,
+  num: &str,
+  article: &Article,
+) -> Result<Vec<KaiseiInfo>, YomikaeError> {
+  let law_info = LawInfo::new(num, article.clone(), law_text.clone());
+  let input = match &law_text.contents {
+    LawContents::Text(input) => input,
+    LawContents::Table(_) => return Err(YomikaeError::ContentsOfTable(law_info)),
+  };
+  info!("[INPUT] {num} : {:?}", input);
+
+  let segments = split_segments(input).ok_or(YomikaeError::UnmatchedParen(law_info))?;
+
+  let mut kaisei_info_lst = Vec::new();
+
+  // 「〜」及び「〜」のように並べられた単語
+  let mut words: Vec<String> = Vec::new();
+  // 「〜」を「〜」に の一つ目の単語
+  let mut replace_before: Option<String> = None;
+  // 「改める」が出てくるまで保留している置き換え
+  let mut pending_replace: Vec<(String, String)> = Vec::new();
+  // 「〜」の下に「〜」を の一つ目の単語
+  let mut insert_anchor: Option<(String, InsertPosition)> = None;
+  // 「加える」が出てくるまで保留している追加
+  let mut pending_insert: Vec<(String, InsertPosition, String)> = Vec::new();
+
+  for (word, text) in quoted_with_following_text(&segments) {
+    if let Some(before) = replace_before.take() {
+      match text.strip_prefix('に') {
+        Some(rest) => {
+          pending_replace.push((before, word));
+          if rest.starts_with("改め") {
+            kaisei_info_lst.extend(
+              pending_replace
+                .drain(..)
+                .map(|(before, after)| KaiseiInfo::Replace { before, after }),
+            );
+          } else if !(rest.is_empty() || rest.starts_with('、')) {
+            pending_replace.clear();
+          }
+        }
+        None => pending_replace.clear(),
+      }
+      continue;
+    }
+    if let Some((anchor, position)) = insert_anchor.take() {
+      match text.strip_prefix('を') {
+        Some(rest) => {
+          pending_insert.push((anchor, position, word));
+          if rest.starts_with("加え") {
+            kaisei_info_lst.extend(pending_insert.drain(..).map(|(anchor, position, word)| {
+              KaiseiInfo::Insert {
+                anchor,
+                position,
+                word,
+              }
+            }));
+          } else if !(rest.is_empty() || rest.starts_with('、')) {
+            pending_insert.clear();
+          }
+        }
+        None => pending_insert.clear(),
+      }
+      continue;
+    }
+
+    words.push(word);
+    match text.as_str() {
+      "、" | "及び" | "並びに" => (),
+      "の下に" | "の次に" if words.len() == 1 => {
+        insert_anchor = words.pop().map(|anchor| (anchor, InsertPosition::After));
+      }
+      "の上に" | "の前に" if words.len() == 1 => {
+        insert_anchor = words.pop().map(|anchor| (anchor, InsertPosition::Before));
+      }
+      "を" if words.len() == 1 => {
+        replace_before = words.pop();
+      }
+      _ => {
+        if let Some(rest) = text.strip_prefix('を') {
+          if rest.starts_with('削') {
+            kaisei_info_lst.extend(words.drain(..).map(|target| KaiseiInfo::Delete { target }));
+          }
+        }
+        words.clear();
+      }
+    }
+  }
+
+  Ok(kaisei_info_lst)
+}
+
+#[test]
+fn check_replace() {
+  let lawtext = crate::test_utils::law_text(
+    "第三条第一項中「厚生大臣」を「厚生労働大臣」に、「労働省令」を「厚生労働省令」に改め、同条第二項中「若しくは」を「又は」に改める。",
+  );
+  let kaisei_info_lst =
+    parse_kaiseibun(&lawtext, "test", &crate::test_utils::test_article()).unwrap();
+  assert_eq!(
+    vec![
+      KaiseiInfo::Replace {
+        before: "厚生大臣".to_string(),
+        after: "厚生労働大臣".to_string()
+      },
+      KaiseiInfo::Replace {
+        before: "労働省令".to_string(),
+        after: "厚生労働省令".to_string()
+      },
+      KaiseiInfo::Replace {
+        before: "若しくは".to_string(),
+        after: "又は".to_string()
+      }
+    ],
+    kaisei_info_lst
+  )
+}
+
+#[test]
+fn check_delete_and_insert() {
+  let lawtext = crate::test_utils::law_text(
+    "第五条中「、第三項」及び「（第二号を除く。）」を削り、「前条」の下に「又は第七条」を加える。",
+  );
+  let kaisei_info_lst =
+    parse_kaiseibun(&lawtext, "test", &crate::test_utils::test_article()).unwrap();
+  assert_eq!(
+    vec![
+      KaiseiInfo::Delete {
+        target: "、第三項".to_string()
+      },
+      KaiseiInfo::Delete {
+        target: "（第二号を除く。）".to_string()
+      },
+      KaiseiInfo::Insert {
+        anchor: "前条".to_string(),
+        position: InsertPosition::After,
+        word: "又は第七条".to_string()
+      }
+    ],
+    kaisei_info_lst
+  )
+}
+
+#[test]
+fn check_unmatched_paren() {
+  let lawtext = crate::test_utils::law_text("第五条中「前条」」を削る。");
+  let res = parse_kaiseibun(&lawtext, "test", &crate::test_utils::test_article());
+  assert!(matches!(res, Err(YomikaeError::UnmatchedParen(_))));
+}
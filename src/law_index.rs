@@ -0,0 +1,59 @@
+//! 複数の法令のインデックス情報を一つにまとめる
+
+use crate::Message;
+use std::collections::HashMap;
+
+/// 複数のインデックス情報（インデックス情報の名前と、その中の法令の並び）を順につなげて一つにまとめる
+/// 同じ法律番号の法令が複数ある場合は先に現れたものだけを残し、取り除いたものを[`Message::DuplicateLaw`]として返す
+pub fn merge_law_index<T>(
+  index_lst: Vec<(String, Vec<T>)>,
+  num: impl Fn(&T) -> &str,
+) -> (Vec<T>, Vec<Message>) {
+  let mut merged = Vec::new();
+  let mut message_lst = Vec::new();
+  // 法律番号と、その法令を残したインデックス情報の名前
+  let mut seen: HashMap<String, String> = HashMap::new();
+  for (index_name, law_lst) in index_lst {
+    for law in law_lst {
+      let law_num = num(&law).to_string();
+      match seen.get(&law_num) {
+        Some(kept) => message_lst.push(Message::DuplicateLaw(
+          law_num,
+          kept.clone(),
+          index_name.clone(),
+        )),
+        None => {
+          seen.insert(law_num, index_name.clone());
+          merged.push(law);
+        }
+      }
+    }
+  }
+  (merged, message_lst)
+}
+
+#[test]
+fn check_merge_law_index() {
+  let (merged, message_lst) = merge_law_index(
+    vec![
+      (
+        "showa.json".to_string(),
+        vec![("a", "a.xml"), ("b", "b.xml")],
+      ),
+      (
+        "heisei.json".to_string(),
+        vec![("c", "c.xml"), ("a", "a2.xml")],
+      ),
+    ],
+    |(num, _)| num,
+  );
+  assert_eq!(vec![("a", "a.xml"), ("b", "b.xml"), ("c", "c.xml")], merged);
+  assert_eq!(
+    vec![Message::DuplicateLaw(
+      "a".to_string(),
+      "showa.json".to_string(),
+      "heisei.json".to_string()
+    )],
+    message_lst
+  );
+}
@@ -0,0 +1,331 @@
+//! 法律番号を表す型
+//!
+//! 法律番号は「平成二十四年法律第六十五号」「平成24年法律第65号」「平成二十四年　法律第六十五号」のように書き方が揺れるので、
+//! 空白を取り除き、算用数字を漢数字にした形にそろえて持つ
+//! シリアライズした形式はそろえた後の文字列で、デシリアライズするときもそろえる
+
+use crate::order::parse_kansuji;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+
+/// 元号
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Era {
+  Meiji,
+  Taisho,
+  Showa,
+  Heisei,
+  Reiwa,
+}
+
+impl Era {
+  /// 元号の名前
+  pub fn name(self) -> &'static str {
+    match self {
+      Era::Meiji => "明治",
+      Era::Taisho => "大正",
+      Era::Showa => "昭和",
+      Era::Heisei => "平成",
+      Era::Reiwa => "令和",
+    }
+  }
+
+  /// 法令IDの先頭の元号の番号（明治が1、令和が5）
+  fn code(self) -> u32 {
+    match self {
+      Era::Meiji => 1,
+      Era::Taisho => 2,
+      Era::Showa => 3,
+      Era::Heisei => 4,
+      Era::Reiwa => 5,
+    }
+  }
+
+  fn from_code(code: u32) -> Option<Self> {
+    [Era::Meiji, Era::Taisho, Era::Showa, Era::Heisei, Era::Reiwa]
+      .into_iter()
+      .find(|era| era.code() == code)
+  }
+}
+
+/// 「〇年〇〇第〇号」の形で書かれた法律番号を分けたもの
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LawNumParts {
+  pub era: Era,
+  /// 元号の年（元年は1）
+  pub year: u32,
+  /// 法令の種類（「法律」「政令」「厚生労働省令」など）
+  pub law_type: String,
+  /// 号数
+  pub number: u32,
+}
+
+impl fmt::Display for LawNumParts {
+  /// 「平成二十四年法律第六十五号」のような形で表示する
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let year = if self.year == 1 {
+      "元".to_string()
+    } else {
+      to_kansuji(self.year)
+    };
+    write!(
+      f,
+      "{}{year}年{}第{}号",
+      self.era.name(),
+      self.law_type,
+      to_kansuji(self.number)
+    )
+  }
+}
+
+/// 法律番号
+///
+/// 「〇年〇〇第〇号」の形でないもの（「昭和二十四年人事院規則一―〇」など）も、書き方をそろえた文字列として持つ
+/// エラーのメッセージなどの表示が変わらないように、`Debug`も文字列と同じ形で表示する
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub struct LawNum(String);
+
+impl LawNum {
+  /// 書き方をそろえて法律番号にする
+  pub fn new(s: impl AsRef<str>) -> Self {
+    LawNum(normalize_law_num(s.as_ref()))
+  }
+
+  /// 法令ID（「405AC0000000088」など）から法律番号を作る
+  /// 法律・政令・勅令の法令IDのみ読み、それ以外は`None`を返す
+  pub fn from_law_id(law_id: &str) -> Option<Self> {
+    if law_id.len() != 15 || !law_id.is_ascii() {
+      return None;
+    }
+    let era = Era::from_code(law_id[0..1].parse().ok()?)?;
+    let year = law_id[1..3].parse().ok()?;
+    let law_type = match &law_id[3..5] {
+      "AC" => "法律",
+      "CO" => "政令",
+      "IO" => "勅令",
+      _ => return None,
+    };
+    let number = law_id[5..].parse().ok()?;
+    Some(LawNum::from(LawNumParts {
+      era,
+      year,
+      law_type: law_type.to_string(),
+      number,
+    }))
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+
+  /// 元号・年・法令の種類・号数に分ける
+  /// 「〇年〇〇第〇号」の形でない場合は`None`を返す
+  pub fn parts(&self) -> Option<LawNumParts> {
+    let (era, rest) = [Era::Meiji, Era::Taisho, Era::Showa, Era::Heisei, Era::Reiwa]
+      .into_iter()
+      .find_map(|era| self.0.strip_prefix(era.name()).map(|rest| (era, rest)))?;
+    let (year, rest) = rest.split_once('年')?;
+    let year = match year {
+      "元" => 1,
+      _ => parse_kansuji(year)? as u32,
+    };
+    let (law_type, number) = rest.split_once('第')?;
+    let number = parse_kansuji(number.strip_suffix('号')?)? as u32;
+    if law_type.is_empty() {
+      return None;
+    }
+    Some(LawNumParts {
+      era,
+      year,
+      law_type: law_type.to_string(),
+      number,
+    })
+  }
+}
+
+impl Deref for LawNum {
+  type Target = str;
+  fn deref(&self) -> &str {
+    &self.0
+  }
+}
+
+impl AsRef<str> for LawNum {
+  fn as_ref(&self) -> &str {
+    &self.0
+  }
+}
+
+impl fmt::Display for LawNum {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl fmt::Debug for LawNum {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:?}", self.0)
+  }
+}
+
+impl From<String> for LawNum {
+  fn from(s: String) -> Self {
+    LawNum::new(s)
+  }
+}
+
+impl From<&str> for LawNum {
+  fn from(s: &str) -> Self {
+    LawNum::new(s)
+  }
+}
+
+impl From<&String> for LawNum {
+  fn from(s: &String) -> Self {
+    LawNum::new(s)
+  }
+}
+
+impl From<LawNumParts> for LawNum {
+  fn from(parts: LawNumParts) -> Self {
+    LawNum(parts.to_string())
+  }
+}
+
+impl From<LawNum> for String {
+  fn from(law_num: LawNum) -> Self {
+    law_num.0
+  }
+}
+
+impl PartialEq<str> for LawNum {
+  fn eq(&self, other: &str) -> bool {
+    self.0 == other
+  }
+}
+
+impl PartialEq<&str> for LawNum {
+  fn eq(&self, other: &&str) -> bool {
+    self.0 == *other
+  }
+}
+
+impl PartialEq<String> for LawNum {
+  fn eq(&self, other: &String) -> bool {
+    &self.0 == other
+  }
+}
+
+impl PartialEq<LawNum> for &str {
+  fn eq(&self, other: &LawNum) -> bool {
+    *self == other.0
+  }
+}
+
+impl PartialEq<LawNum> for String {
+  fn eq(&self, other: &LawNum) -> bool {
+    *self == other.0
+  }
+}
+
+/// 数値を「六十五」「二千十」のような漢数字にする
+fn to_kansuji(n: u32) -> String {
+  const DIGITS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+  fn under_man(n: u32) -> String {
+    let mut s = String::new();
+    for (unit, c) in [(1000, '千'), (100, '百'), (10, '十')] {
+      match n / unit % 10 {
+        0 => (),
+        1 => s.push(c),
+        d => {
+          s.push(DIGITS[d as usize]);
+          s.push(c);
+        }
+      }
+    }
+    match n % 10 {
+      0 => s,
+      d => {
+        s.push(DIGITS[d as usize]);
+        s
+      }
+    }
+  }
+  match n {
+    0 => "〇".to_string(),
+    n if n >= 10000 => format!("{}万{}", under_man(n / 10000), under_man(n % 10000)),
+    n => under_man(n),
+  }
+}
+
+/// 法律番号の空白を取り除き、算用数字（全角を含む）を漢数字にする
+/// 年の「1」は「元」にする
+fn normalize_law_num(s: &str) -> String {
+  let mut normalized = String::new();
+  let mut digits = String::new();
+  let mut chars = s.chars().filter(|c| !c.is_whitespace()).peekable();
+  while let Some(c) = chars.next() {
+    let digit = match c {
+      '0'..='9' => Some(c),
+      '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32),
+      _ => None,
+    };
+    match digit {
+      Some(d) => {
+        digits.push(d);
+        if chars.peek().map_or(false, |next| {
+          next.is_ascii_digit() || ('０'..='９').contains(next)
+        }) {
+          continue;
+        }
+        let n = digits.parse::<u32>().ok();
+        match (n, chars.peek()) {
+          (Some(1), Some('年')) => normalized.push('元'),
+          (Some(n), _) => normalized.push_str(&to_kansuji(n)),
+          (None, _) => normalized.push_str(&digits),
+        }
+        digits.clear();
+      }
+      None => normalized.push(c),
+    }
+  }
+  normalized
+}
+
+#[test]
+fn check_law_num() {
+  let law_num = LawNum::new("平成24年　法律第６５号");
+  assert_eq!("平成二十四年法律第六十五号", law_num);
+  assert_eq!(
+    Some(LawNumParts {
+      era: Era::Heisei,
+      year: 24,
+      law_type: "法律".to_string(),
+      number: 65,
+    }),
+    law_num.parts()
+  );
+  assert_eq!(
+    Some(law_num.clone()),
+    LawNum::from_law_id("424AC0000000065")
+  );
+  assert_eq!("平成元年政令第二百十号", LawNum::new("平成1年政令第210号"));
+  assert_eq!(
+    Some(2010),
+    LawNum::new("昭和二十二年法律第二千十号")
+      .parts()
+      .map(|parts| parts.number)
+  );
+  let rule = LawNum::new("昭和二十四年人事院規則一―〇");
+  assert_eq!("昭和二十四年人事院規則一―〇", rule);
+  assert_eq!(None, rule.parts());
+  assert_eq!(None, LawNum::from_law_id("405M60000100001"));
+  assert_eq!(
+    "\"平成二十四年法律第六十五号\"",
+    serde_json::to_string(&law_num).unwrap()
+  );
+  let law_num: LawNum = serde_json::from_str("\"平成24年法律第65号\"").unwrap();
+  assert_eq!("平成二十四年法律第六十五号", law_num);
+}
@@ -0,0 +1,109 @@
+//! 読み替え後の語に含まれる、法律番号が付いた他の法令の引用を取り出す
+//!
+//! 「地方税法（昭和二十五年法律第二百二十六号）第三百二十一条の七の二」のように、
+//! 読み替え後の語の中で他の法令を法律番号付きで引用していることがあるので、法令の間の引用関係を作るために取り出す
+
+use crate::qualifier::{law_name, ARTICLE_CHARS};
+use crate::{LawNum, YomikaeInfo};
+use serde::{Deserialize, Serialize};
+
+/// 引用されている法令
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LawRef {
+  /// 法令の名前（「地方税法」など。法令の名前が書かれていない場合は`None`）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub law: Option<String>,
+  /// 丸括弧の中に書かれた法律番号
+  pub num: LawNum,
+  /// 丸括弧の直後に書かれた条項（「第三条第二項」など。書かれていない場合は`None`）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub article: Option<String>,
+}
+
+/// 丸括弧の中身が法律番号であれば、その法律番号を返す
+/// 「昭和二十五年法律第二百二十六号。以下「法」という。」のように後ろに続く文は読まない
+fn paren_law_num(inner: &str) -> Option<LawNum> {
+  let text = inner.split(['。', '、']).next().unwrap_or_default();
+  let num = LawNum::new(text);
+  num.parts().map(|_| num)
+}
+
+/// 丸括弧の直後の「第三条第二項」のような条項を取り出す
+fn leading_article(rest: &str) -> Option<String> {
+  let end = rest
+    .char_indices()
+    .find(|(_, c)| !ARTICLE_CHARS.contains(*c))
+    .map(|(i, _)| i)
+    .unwrap_or(rest.len());
+  let article = rest[..end].trim_end_matches('の');
+  if (article.starts_with('第') || article.starts_with("附則")) && article.contains('条') {
+    Some(article.to_string())
+  } else {
+    None
+  }
+}
+
+/// 語の中の「法令の名前（法律番号）条項」の形の引用を、書かれている順に取り出す
+pub fn extract_law_refs(word: &str) -> Vec<LawRef> {
+  let mut refs = Vec::new();
+  let mut open_lst = Vec::new();
+  for (i, c) in word.char_indices() {
+    match c {
+      '（' => open_lst.push(i),
+      '）' => {
+        let start = match open_lst.pop() {
+          Some(start) => start,
+          None => continue,
+        };
+        let num = match paren_law_num(&word[start + '（'.len_utf8()..i]) {
+          Some(num) => num,
+          None => continue,
+        };
+        let law_ref = LawRef {
+          law: law_name(&word[..start]),
+          num,
+          article: leading_article(&word[i + '）'.len_utf8()..]),
+        };
+        refs.push((start, law_ref));
+      }
+      _ => (),
+    }
+  }
+  refs.sort_by_key(|(start, _)| *start);
+  refs.into_iter().map(|(_, law_ref)| law_ref).collect()
+}
+
+/// 読み替え後の語から法令の引用を取り出して[`YomikaeInfo::law_refs`]に入れる
+pub fn attach_law_refs(yomikae_info: YomikaeInfo) -> YomikaeInfo {
+  let law_refs = extract_law_refs(&yomikae_info.after_word);
+  YomikaeInfo {
+    law_refs,
+    ..yomikae_info
+  }
+}
+
+#[test]
+fn check_extract_law_refs() {
+  assert_eq!(
+    vec![LawRef {
+      law: Some("地方税法".to_string()),
+      num: LawNum::new("昭和二十五年法律第二百二十六号"),
+      article: Some("第三百二十一条の七の二".to_string()),
+    }],
+    extract_law_refs("地方税法（昭和二十五年法律第二百二十六号）第三百二十一条の七の二の規定")
+  );
+  let refs = extract_law_refs(
+    "国民年金法（昭和34年法律第141号。以下「法」という。）又は厚生年金保険法（昭和二十九年法律第百十五号）による年金",
+  );
+  assert_eq!(2, refs.len());
+  assert_eq!(Some("国民年金法".to_string()), refs[0].law);
+  assert_eq!("昭和三十四年法律第百四十一号", refs[0].num);
+  assert_eq!(None, refs[0].article);
+  assert_eq!(Some("厚生年金保険法".to_string()), refs[1].law);
+  assert!(extract_law_refs("市長（指定都市の長を除く。）").is_empty());
+  let yomikae_info = attach_law_refs(YomikaeInfo::new(
+    ["前条"],
+    "旧法（平成五年法律第八十八号）第二条",
+  ));
+  assert_eq!(1, yomikae_info.law_refs.len());
+}
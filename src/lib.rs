@@ -24,7 +24,108 @@
 //! - `-o`：解析で生成した情報を出力するJSONファイル
 //! - `-e`：解析に失敗した条文の情報を出力するJSONファイル
 //! - `-w`：法令XMLファイルがあるフォルダ
-//! - `-i`：法令のインデックス情報が書かれたJSONファイル [listup_law](https://github.com/japanese-law-analysis/listup_law)で生成するもの
+//! - `-i`：法令のインデックス情報が書かれたJSONファイル [listup_law](https://github.com/japanese-law-analysis/listup_law)で生成するもの。`-i`を複数指定したり、JSONファイルが置かれたディレクトリを指定したりすると、それらを順につなげて解析する（ディレクトリの中のファイルはファイル名の順）。同じ法律番号の法令が複数ある場合は先に現れたものだけを解析し、警告を出す
+//! - `--manifest`：解析が完了した法令の一覧を出力するJSONファイル（省略可）
+//! - `--fail-fast`：法令ファイルの読み込みに失敗した時点で解析を終了する（指定しない場合はエラー出力ファイルに記録して次の法令に進む）
+//! - `--unsorted`：解析結果を法律番号・条項の順に並べ替えず、解析が終わったものから順に出力する
+//! - `--lang`：エラーや警告のメッセージの言語（`ja`または`en`、省略した場合は`en`）
+//! - `--otlp-endpoint`：トレースを送るOTLPのエンドポイント（`otlp` featureを有効にしてビルドした場合のみ使える）
+//! - `--metrics-file`：解析した法令・条文・読み替えの組・エラーの件数と解析にかかった時間を出力するJSONファイル（省略可）
+//! - `--prometheus-listen`：Prometheus形式のメトリクスを公開するアドレス（`prometheus` featureを有効にしてビルドした場合のみ使える）
+//! - `--egov-url`：解析結果に、読み替え規定がある条項のe-Gov法令検索のURL（`url`）を含める（法令IDは法令XMLファイル名から取り出す）
+//! - `--revision`：解析結果に、法令XMLファイル名（`法令ID_施行日_改正法令ID.xml`）と`Law`要素の属性から読んだ法令の版（`revision`）を含める。同じ法令を複数の時点の法令XMLから解析した結果を、法令ID・版・内容のハッシュ値の組で見分けられるようにする。同じ法令の中の重複は版ごとにまとめ、並べ替えも法律番号・版・条項の順にする。`sqlite`形式では`yomikae`表の`law_id`・`revision`・`content_hash`の列に入れる
+//! - `--include-title`：解析結果と条文についてのエラーに法令名（`title`）を含める。インデックス情報の`name`を使い、無い場合は法令XMLの`LawTitle`要素から読む
+//! - `--enriched-index`：`-i`で指定したインデックス情報の各法令に、読み替えの組が取り出せたかどうか（`has_yomikae`）・組の数（`pair_count`）・エラーの数（`error_count`）を書き加えたJSONファイルを出力する（省略可）。インデックス情報が複数ある場合は、それぞれに書き加えたものを並べた配列にする
+//! - `--format`：解析結果とエラーを出力する形式（`json`・`ndjson`・`csv`・`sqlite`、省略した場合は`json`）。`sqlite`は`sqlite` featureを有効にしてビルドした場合のみ使え、エラーも`-o`のデータベースの`errors`表に入れる
+//! - `--key-case`：JSON・NDJSON・CSVで出力するキーや列の名前の形式（`snake`または`camel`、省略した場合は`snake`）。`camel`の場合は`before_words`が`beforeWords`のようになる（マップのキーと、条項を表す`article`の中身はそのまま）。`camel`で書き出したものも読み込める
+//! - `--audit`：「と読み替える」を含む全ての文（`--config`を指定した場合は、その`trigger_phrases`を含む全ての文）について、最終的な扱い（`parsed`・`table`・`skipped`・`error`）を一行ずつ書いたNDJSONファイルを出力する（省略可）。想定していない接続詞などが閉じ鉤括弧の後に来て組み立て途中の状態を捨てた場合は、その文字列（[`reset_connectives`]で取り出せるもの）を`reset_connectives`に書き、最後の行には全ての法令を通した文字列ごとの件数を`{"reset_connectives":{…}}`の形で書く
+//! - `--qualifiers`：読み替え後の語に「附則第十一条の規定によりなおその効力を有する」「なお従前の例による」のような修飾句があれば、参照している法令の名前と条項を`qualifiers`に含める
+//! - `--law-refs`：読み替え後の語に「地方税法（昭和二十五年法律第二百二十六号）第三条」のような法律番号を付けた他の法令の引用があれば、その法令の名前・法律番号・条項を`law_refs`に含める（法令の間の引用関係を作るためのもの）
+//! - `--bracket-repairs`：鉤括弧の対応が取れていない文のエラーに、鉤括弧を足したり消したりする直し方の候補（[`repair::suggest_bracket_repairs`]）を`bracket_repairs`として含める。候補を探すのには時間がかかるので、指定しない場合は探さない
+//! - `--config`：読み替え規定文の候補とする文言を設定したTOMLファイル（省略可）。`trigger_phrases = ["と読み替える", "」に読み替える", "読み替えて準用する"]`のように書き、鉤括弧の外側にいずれかの文言を含む文を解析する（省略した場合は「と読み替える」と「」に読み替える」。表で読み替えると書かれた文は文言に依らず解析する）。文言ごとに、その文言を含む条文・読み替えの組が取り出せた条文・読み替えの組の数を`--metrics-file`の`trigger_phrases`と`--quiet`のまとめに含める。`[[patterns]]`には、読み替えの組が取り出せなかった文に使うパターンを書ける（下記）
+//! - `--flatten`：JSON・NDJSONの解析結果を、条項ごとではなく法律番号・条項・読み替え前の語・読み替え後の語の組ごとに一件の平坦な形で出力する（CSV・SQLiteは元から組ごとに一行）
+//! - `--combined`：`-o`と`-e`の代わりに、実行の情報（`meta`）・解析結果（`results`）・エラー（`errors`）・警告（`warnings`）を`{ meta, results, errors, warnings }`の形でまとめた一つのJSONファイルを出力する（`--format`とは同時に指定できない）
+//! - `--promulgated-after`・`--promulgated-before`：`-i`のインデックス情報に書かれた公布日（`date`）が、指定した日（`YYYY-MM-DD`）以降・指定した日より前の法令だけを解析する（公布日が分からない法令は解析しない）
+//! - `--quiet`（`--summary-only`）：法令ごとのログを出さずに、解析の終わりに解析した法令の数・読み替えの組の数・種類ごとのエラーの数・かかった時間のまとめだけを表示する
+//! - `--checkpoint`：`--checkpoint-interval`で指定した数（省略した場合は100）の法令を解析するごとに、インデックス情報の中で次に解析する法令の位置と、出力ファイルに書き出したバイト数を記録したJSONファイルを出力する（`--unsorted`が必要）
+//! - `--resume-from`：`--checkpoint`で出力したJSONファイルを読み込み、`-o`と`-e`のファイルを記録したバイト数まで切り詰めて、その続きの法令から解析を再開する（`--unsorted`が必要。`sqlite`形式と`--combined`では使えない。`--manifest`・`--metrics-file`などは再開した後の法令だけを数える）
+//! - `--input-format`・`--input`：法令XMLの代わりに、idと条文の二つの欄を並べたTSV・CSVファイル（`--input-format tsv`または`csv`）を`--input`で指定して解析する（`-w`と`-i`は指定しない）。idは法律番号と条項を`:`で区切った形（`平成五年法律第八十八号:3`、項まで書く場合は`平成五年法律第八十八号:3:2`）で書き、一行目の一つ目の欄が`id`の場合は見出しとして読み飛ばす。`--audit`・`--egov-url`・`--revision`・`--include-title`・`--enriched-index`・`--promulgated-after`・`--promulgated-before`・`--checkpoint`・`--resume-from`・`--verify-determinism`とは同時に指定できない
+//! - `--verify-determinism`：開発用。`-o`と`-e`に書き出す代わりに、全ての法令を二回解析し、解析結果とエラーを一件ずつJSONにして並べ替えた形で比べる。片方にだけ現れたものを標準出力に書き出し、違いがあれば失敗する。二回の解析で並行して解析する法令の数は`--verify-jobs`で指定する（省略した場合は`1,4`）。解析は通常の解析と同じ処理で、`--config`・`--include-title`・`--qualifiers`・`--law-refs`・`--bracket-repairs`・`--egov-url`・`--revision`の指定に従う
+//!
+//! `server` featureを有効にしてビルドした場合は
+//!
+//! ```sh
+//! analysis_yomikae serve --listen 127.0.0.1:3000 --data output.json
+//! ```
+//!
+//! で、文や法令XMLを送って解析したり、`--data`で読み込んだ解析結果を検索したりできるHTTPサーバーを起動します。
+//!
+//! さらに`graphql` featureを有効にしてビルドした場合は、`/graphql`で法令・条項・読み替えの組・エラー・規定の間の読み替えの関係をGraphQLで引けるようになります。エラーは`--errors`で指定したJSONファイルから読み込みます。`GET /graphql`でクエリを試すためのGraphiQLを開けます。
+//!
+//! `tantivy` featureを有効にしてビルドした場合は
+//!
+//! ```sh
+//! analysis_yomikae index --data output.json --index-dir index
+//! analysis_yomikae search --index-dir index --target before 都道府県知事
+//! ```
+//!
+//! で、解析結果の読み替え前後の語の全文検索のインデックスを作り、その語を含む読み替えを検索できます。
+//!
+//! `tui` featureを有効にしてビルドした場合は
+//!
+//! ```sh
+//! analysis_yomikae browse -o output.json -e err.json -w law_xml -i index.json
+//! ```
+//!
+//! で、法令→条項→読み替えの組の順に解析結果とエラーを見て回れます。`-w`と`-i`を指定した場合は元の文を読み込み、取り出した語の箇所を強調して表示します。
+//!
+//! ```sh
+//! analysis_yomikae export-rdf --data output.json -i index.json --format turtle -o yomikae.ttl
+//! ```
+//!
+//! で、解析結果をRDF（Turtle形式または`--format nquads`でN-Quads形式）で出力します。`-i`を指定した場合は法令IDを使ったe-Gov法令検索のURIで法令を表します。
+//!
+//! ```sh
+//! analysis_yomikae coverage --data output.json -w law_xml -i index.json
+//! ```
+//!
+//! で、法令XMLに「と読み替える」などの文言があるのに解析結果が一つもない法令を一覧にします。`--config`を指定した場合は、そのファイルの`trigger_phrases`を文言として使います。`--json`を指定した場合は件数と一覧をJSONで出力します。
+//!
+//! ```sh
+//! analysis_yomikae repl
+//! ```
+//!
+//! で、標準入力から一行ずつ読んだ文について、鉤括弧による分割・閉じ鉤括弧ごとの状態の変わり方・取り出した読み替えの組を表示します。解析がうまくいかない文を調べるのに使います。
+//!
+//! ```sh
+//! analysis_yomikae lint draft.txt --target target.txt
+//! ```
+//!
+//! で、法令案の読み替え規定文について、鉤括弧の対応が取れていない文・「と読み替える」などの文言が無い文・同じ語を重ねて読み替える規定などを、重大さ（error/warning）と合わせて一覧にします。`--target`を指定した場合は、読み替え前の語がそのファイルの文に無いものも報告します。`--config`を指定した場合は、そのファイルの`trigger_phrases`を含む文を読み替え規定文として調べます。鉤括弧の対応が取れていない文には、鉤括弧を足したり消したりする直し方の候補も示します。`--json`を指定した場合はJSONで、`--sarif`を指定した場合はSARIF 2.1.0の形式で出力します。errorが一つでもあれば終了コードを0以外にします。
+//!
+//! ```sh
+//! analysis_yomikae export-sarif --errors err.json -o err.sarif
+//! ```
+//!
+//! で、解析のエラーをSARIF 2.1.0の形式で出力します。`lint`でも`--sarif`を指定するとSARIFで出力し、鉤括弧の直し方の候補を`fixes`として含めます。
+//!
+//! ```sh
+//! analysis_yomikae daemon --queue queue -o results -i index.json
+//! ```
+//!
+//! で、`queue`ディレクトリを見張り、置かれた法令XMLファイル（`<ジョブ名>.xml`）を解析して、`results`ディレクトリの`<ジョブ名>.json`に解析結果とエラーを書き出します。解析し終えたファイルは`queue/done`に移します。書き込み途中のファイルを読まないよう、「.」で始まる名前で書き込んでから名前を変えて置いてください。インデックス情報は起動したときに一度だけ読み込み、法律番号を得るのに使います（指定しない場合はジョブ名を法律番号とします）。`--poll-interval`でディレクトリを調べる間隔（ミリ秒）を、`--once`で置かれているファイルを解析し終えたら終了することを、`--config`で読み替え規定文の候補とする文言を設定したTOMLファイルを指定できます。
+//!
+//! ```sh
+//! analysis_yomikae readers --data output.json --law 民法 --article 第八百五十一条
+//! ```
+//!
+//! で、民法第八百五十一条（とその下の項や号）を読み替えている規定と、その読み替えの組を一覧にします。`--law`には読み替えの対象として書かれた法令の名前を、対象に法令の名前が書かれていない場合は読み替えている法令の法律番号を指定します。「同条」「前条」のような書き方の対象は探せません。`--json`を指定した場合はJSONで出力します。
+//!
+//! ```sh
+//! analysis_yomikae mine-connectives -w law_xml -i index.json
+//! ```
+//!
+//! で、読み替え規定文の候補を解析せずに鉤括弧で分割だけして、閉じ鉤括弧の後に続く文字列のうちまだ扱えていないもの（[`Connective::Other`]に分類されるもの）を、件数と最初に現れた文と合わせて件数の多い順に一覧にします。どの書き方に対応すればより多くの文を解析できるようになるかを調べるのに使います。`--config`で候補とする文言を、`--limit`で表示する文字列の最大数（既定は100）を指定できます。`--json`を指定した場合はJSONで出力します。
 //!
 //!
 //! # ライブラリを使う
@@ -32,6 +133,48 @@
 //!
 //! 解析結果が書かれたJSONファイルに書かれる構造体やエラーの定義がされており、デシリアライズが容易にできるようになっています。
 //!
+//! また、[`kaiseibun`]モジュールでは改め文から改正の対象となる文言と改正後の文言を取り出すことができます。
+//!
+//! CLIソフトウェアに必要な依存クレートはデフォルトで有効になっている`cli` featureに入っています。ライブラリとしてだけ使う場合は`default-features = false`を指定してください。
+//!
+//! [`parse_yomikae`]などの解析関数は同期的な関数で、tokioなどの非同期ランタイムを必要としません。
+//! 法令XMLや条文のリストをまとめて解析する非同期の関数は、`async` featureを有効にした[`batch`]モジュールにあります。
+//! `batch::analyze_laws`と`batch::run_analysis`は`watch::Receiver<bool>`を受け取り、`true`が送られるとまだ始めていない法令を解析せずに終わります（`batch::cancel_on_ctrl_c`でCtrl-Cに結び付けられます）。`run_analysis`のまとめ（`batch::AnalysisSummary`）には、解析を終えた法令と始めなかった法令が入ります。
+//! 一つの法令の条文のリストをCLIと同じように解析するには、[`pipeline::analyze_law_text_lst`]を使います。修飾句・他の法令の引用・鉤括弧の直し方の候補を含めるかどうかは[`pipeline::LawAnalysisOptions`]で選べ、条文の解析中にpanicが起きた場合はその条文の[`YomikaeError::InternalError`]になります。`batch`モジュールの関数もこれを使って解析します。
+//! 解析の進み具合を数える[`RunMetrics`]は、`metrics` feature（`cli`で有効になる）を有効にした場合だけ使えます。
+//! 解析結果をリストにためずに一つずつ受け取りたい場合は、[`visitor`]モジュールの[`visitor::YomikaeVisitor`]を実装してください。
+//! 同じ法令の文と表の両方に書かれた読み替えは、[`compare_forms`]で重複と食い違いを調べ、[`reconcile_forms`]で文の側の重複を取り除けます。
+//! 条項の読み替えの組を実際の条文に当てはめたり書き出したりするときは、[`replacement_maps`]で読み替えの対象となる規定ごとに、長い語から順に並べた置き換えの一覧にまとめられます。同じ語が違う語に読み替えられていた場合の扱いは[`ConflictPolicy`]で選べます。
+//! 法令の法律番号（[`YomikaeData`]・[`LawInfo`]の`num`）は[`LawNum`]で、空白を取り除き算用数字を漢数字にした形（「平成24年法律第65号」は「平成二十四年法律第六十五号」）にそろえて持ちます。シリアライズした形式はこれまでと同じ文字列です。[`LawNum::parts`]で元号・年・法令の種類・号数に分けられ、[`LawNum::from_law_id`]で法令IDから法律番号を作れます。
+//! 読み替え規定文の候補とする文言は[`ParserConfig`]の`trigger_phrases`で変えられます。[`select_yomikae_law_text_with_config`]と[`parse_yomikae_sentences_with_config`]に渡すと、その文言を鉤括弧の外側に含む文を解析します。
+//! 読み替え規定文を一番外側の鉤括弧で括られた部分（`Segment::Quoted`）と語をつなぐ鉤括弧の外側の部分（`Segment::Text`）に分けたものは、[`tokenize_yomikae_sentence`]で[`Segment`]の並びとして受け取れます。鉤括弧の対応が取れていない場合のエラーには、鉤括弧の直し方の候補が付きます。
+//! 読み替えの組が一つも取り出せなかった文には、文の形を宣言的に書いたパターン（[`pattern::SentencePattern`]）を使います。パターンは鉤括弧で囲まれた語（`quoted`。`role`は`before`・`after`・`ignore`）・決まった文字列（`literal`）・いずれか一つに合う並び（`alt`。空の並びを含めると省略できる）を順に並べたもので、組み込みのものは「「〜」は「〜」と」「「〜」を「〜」と」の形を扱います。[`ParserConfig`]の`patterns`で置き換えられ、`--config`のTOMLファイルでは次のように書きます。
+//!
+//! ```toml
+//! [[patterns]]
+//! name = "toiunowa"
+//! elements = [
+//!   { type = "quoted", role = "before" },
+//!   { type = "literal", text = "というのは" },
+//!   { type = "quoted", role = "after" },
+//! ]
+//! ```
+//!
+//! `wasm` featureを有効にして
+//!
+//! ```sh
+//! cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm
+//! ```
+//!
+//! のようにビルドすると、[`wasm`]モジュールの`parseYomikae`と`parseKaiseibun`をJavaScriptから呼び出せます。
+//!
+//! `python` featureを有効にした[`python`]モジュールはPythonの拡張モジュールになっており、
+//! [maturin](https://www.maturin.rs/)で`maturin build --release`とするとwheelを作れます。
+//!
+//! `ffi` featureを有効にすると、[`ffi`]モジュールの`yomikae_parse`などをC言語のABIで呼び出せます。
+//! 解析中にpanicが起きた場合も呼び出し側には巻き戻さず、`InternalError`のエラーをJSON文字列で返します。
+//! ヘッダファイルは`include/analysis_yomikae.h`にあります。
+//!
 //!
 //! ---
 //!
@@ -40,16 +183,177 @@
 //!
 
 use jplaw_text::{Article, LawContents, LawTableColumn, LawTableContents, LawText};
+use parser_state::YomikaeParser;
+use segment::{mask_quoted, split_sentences};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use thiserror::Error;
-use tokio_stream::StreamExt;
 use tracing::*;
+use validate::validate_yomikae_info_lst;
+
+#[cfg(feature = "proptest")]
+mod arbitrary;
+pub mod audit;
+#[cfg(feature = "async")]
+pub mod batch;
+#[cfg(feature = "tui")]
+pub mod browse;
+#[cfg(feature = "async")]
+pub mod checkpoint;
+mod content_hash;
+pub mod coverage;
+mod dataset;
+mod dedupe;
+pub mod determinism;
+#[cfg(feature = "miette")]
+pub mod diagnostic;
+mod display;
+pub mod egov;
+mod enrich;
+mod error_key;
+pub mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "async")]
+mod field_case;
+mod flatten;
+mod form_compare;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+pub mod intern;
+pub mod kaiseibun;
+mod law_index;
+mod law_num;
+pub mod law_ref;
+pub mod lint;
+mod message;
+pub mod mining;
+mod order;
+mod parser_config;
+mod parser_state;
+pub mod pattern;
+pub mod pipeline;
+pub mod prelude;
+pub mod promulgation;
+mod provenance;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod qualifier;
+#[cfg(feature = "async")]
+pub mod queue;
+pub mod rdf;
+pub mod repair;
+mod replace_map;
+mod revision;
+pub mod ruby;
+#[cfg(feature = "metrics")]
+mod run_metrics;
+pub mod sarif;
+pub mod schema;
+#[cfg(feature = "tantivy")]
+pub mod search_index;
+mod segment;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "async")]
+pub mod sink;
+mod source;
+mod table_style;
+pub mod target;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod text_input;
+mod title;
+pub mod validate;
+pub mod visitor;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use content_hash::{law_text_hash, normalize_sentence, sentence_hash};
+pub use dataset::{ReaderEntry, YomikaeDataSet};
+pub use dedupe::{dedupe_yomikae, DuplicateEntry, DuplicateReport};
+pub use display::article_to_string;
+pub use enrich::{enrich_index, LawStats};
+pub use error_key::ErrorKey;
+pub use flatten::{flatten_yomikae_data, FlatYomikaeRecord};
+pub use form_compare::{
+  compare_forms, form_conflict_messages, reconcile_forms, FormComparison, FormConflict,
+  FormDuplicate,
+};
+pub use law_index::merge_law_index;
+pub use law_num::{Era, LawNum, LawNumParts};
+pub use law_ref::LawRef;
+pub use message::{Lang, Message};
+pub use order::{cmp_article, sort_yomikae_data};
+pub use parser_config::{ParserConfig, DEFAULT_TRIGGER_PHRASES};
+pub use parser_state::reset_connectives;
+pub use provenance::article_xml_path;
+pub use qualifier::QualifierRef;
+pub use replace_map::{replacement_maps, ConflictPolicy, ReplacementConflict, ReplacementMap};
+pub use revision::{read_law_revision, LawRevision, RevisionKey};
+#[cfg(feature = "metrics")]
+pub use run_metrics::{PhraseStats, RunMetrics};
+pub use schema::{DocumentType, LawXmlSchema};
+pub use segment::{tokenize_yomikae_sentence, Segment, UnmatchedBrackets};
+pub use source::{parse_yomikae_source, SourceError, YomikaeSource};
+pub use table_style::{detect_table_style, TableDirection};
+pub use target::{parse_target_ref, ScopeGranularity, ScopedWord, TargetKind, TargetRef};
+pub use title::collect_law_titles;
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Hash, Deserialize)]
 pub struct LawInfo {
-  pub num: String,
+  pub num: LawNum,
   pub article: Article,
   pub contents: LawText,
+  /// 元の法令XMLファイル
+  #[serde(default, alias = "sourceFile", skip_serializing_if = "Option::is_none")]
+  pub source_file: Option<String>,
+  /// 法令XMLの中での条項の要素のパス
+  #[serde(default, alias = "xmlPath", skip_serializing_if = "Option::is_none")]
+  pub xml_path: Option<String>,
+  /// 条文を「。」で区切ったときの、エラーが起きた文の番号（0始まり）
+  #[serde(
+    default,
+    alias = "sentenceIndex",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub sentence_index: Option<usize>,
+  /// 鉤括弧の対応が取れていない場合の、文の直し方の候補
+  /// [`repair::attach_bracket_repairs`]で付けた場合のみ入る
+  #[serde(
+    default,
+    alias = "bracketRepairs",
+    skip_serializing_if = "Vec::is_empty"
+  )]
+  pub bracket_repairs: Vec<repair::BracketRepair>,
+  /// 法令名（`--include-title`を指定した場合）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub title: Option<String>,
+}
+
+impl LawInfo {
+  /// 法律番号・条項・条文から作る
+  pub fn new(num: impl Into<LawNum>, article: Article, contents: LawText) -> Self {
+    LawInfo {
+      num: num.into(),
+      article,
+      contents,
+      source_file: None,
+      xml_path: None,
+      sentence_index: None,
+      bracket_repairs: Vec::new(),
+      title: None,
+    }
+  }
+}
+
+/// 法令ファイルの情報
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Hash, Deserialize)]
+pub struct LawFileInfo {
+  /// 法律番号
+  pub num: String,
+  /// 法令XMLファイルへのpath
+  pub file: String,
 }
 
 #[derive(Debug, Error, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -60,196 +364,619 @@ pub enum YomikaeError {
   UnmatchedParen(LawInfo),
   #[error("Unexpected parallel words at {0:?}")]
   UnexpectedParallelWords(LawInfo),
+  #[error("Unmatched number of words for sorezore at {0:?}")]
+  UnmatchedSorezoreWords(LawInfo),
   #[error("Not found yomikae sentence at {0:?}")]
   NotFoundYomikae(LawInfo),
+  #[error("Failed to read law file at {0:?}: {1}")]
+  ReadLawFile(LawFileInfo, String),
+  #[error("Failed to parse law XML at {0:?}: {1}")]
+  ParseLawXml(LawFileInfo, String),
+  #[error("Internal error at {0:?}: {1}")]
+  InternalError(LawInfo, String),
+  /// 法令の解析がpanicなどで途中で止まり、どの条文のエラーかが分からない場合
+  #[error("Failed to analyze law file at {0:?}: {1}")]
+  AnalyzeLaw(LawFileInfo, String),
+}
+
+impl YomikaeError {
+  /// 条文についてのエラーであれば、その条文の情報を`f`で書き換える
+  /// 法令ファイルについてのエラーはそのまま返す
+  pub(crate) fn map_law_info(self, f: impl FnOnce(LawInfo) -> LawInfo) -> Self {
+    match self {
+      YomikaeError::ContentsOfTable(law_info) => YomikaeError::ContentsOfTable(f(law_info)),
+      YomikaeError::UnmatchedParen(law_info) => YomikaeError::UnmatchedParen(f(law_info)),
+      YomikaeError::UnexpectedParallelWords(law_info) => {
+        YomikaeError::UnexpectedParallelWords(f(law_info))
+      }
+      YomikaeError::UnmatchedSorezoreWords(law_info) => {
+        YomikaeError::UnmatchedSorezoreWords(f(law_info))
+      }
+      YomikaeError::NotFoundYomikae(law_info) => YomikaeError::NotFoundYomikae(f(law_info)),
+      YomikaeError::InternalError(law_info, msg) => YomikaeError::InternalError(f(law_info), msg),
+      err @ (YomikaeError::ReadLawFile(_, _)
+      | YomikaeError::ParseLawXml(_, _)
+      | YomikaeError::AnalyzeLaw(_, _)) => err,
+    }
+  }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct YomikaeInfo {
+  #[serde(alias = "beforeWords")]
   pub before_words: Vec<String>,
   /// 読み替えられた後の単語
+  #[serde(alias = "afterWord")]
   pub after_word: String,
+  /// 読み替えの種類
+  /// 以前の出力の`is_deletion`（読み替え前の単語を削るものかどうか）も読み込める
+  #[serde(
+    default,
+    alias = "is_deletion",
+    deserialize_with = "deserialize_operation"
+  )]
+  pub operation: YomikaeOperation,
+  /// 取り出した結果の確からしさ
+  #[serde(default, skip_serializing_if = "YomikaeConfidence::is_high")]
+  pub confidence: YomikaeConfidence,
+  /// 読み替え後の語の中で、経過措置などの規定を参照している箇所
+  /// [`qualifier::attach_qualifier_refs`]で取り出した場合のみ入る
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub qualifiers: Vec<QualifierRef>,
+  /// 読み替え後の語の中で、法律番号を付けて引用している他の法令
+  /// [`law_ref::attach_law_refs`]で取り出した場合のみ入る
+  #[serde(default, alias = "lawRefs", skip_serializing_if = "Vec::is_empty")]
+  pub law_refs: Vec<LawRef>,
+  /// 読み替えの対象となる規定（「同条第一項中」など）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub target: Option<TargetRef>,
+  /// 「同項中「A」とあり、及び同項第二号中「B」とあるのは」のように、読み替え前の語ごとに対象となる規定が違う場合の、
+  /// それぞれの語と対象となる規定（`before_words`と同じ順）
+  /// 全ての語の対象が[`YomikaeInfo::target`]と同じ場合は空にする
+  #[serde(
+    default,
+    alias = "scopedBeforeWords",
+    skip_serializing_if = "Vec::is_empty"
+  )]
+  pub scoped_before_words: Vec<ScopedWord>,
+  /// 表から取り出した場合の、元の表の中での行と列の位置
+  #[serde(default, alias = "tableCell", skip_serializing_if = "Option::is_none")]
+  pub table_cell: Option<TableCellRef>,
+}
+
+impl YomikaeInfo {
+  /// 読み替え前後の単語から作る
+  /// 読み替えの種類は[`YomikaeOperation::from_words`]で判定する
+  pub fn new<I, S>(before_words: I, after_word: impl Into<String>) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    let before_words = before_words
+      .into_iter()
+      .map(|s| s.into())
+      .collect::<Vec<_>>();
+    let after_word = after_word.into();
+    YomikaeInfo {
+      operation: YomikaeOperation::from_words(&before_words, &after_word),
+      before_words,
+      after_word,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: None,
+      scoped_before_words: Vec::new(),
+      table_cell: None,
+    }
+  }
+
+  /// 読み替えの対象となる規定を付ける
+  pub fn with_target(self, target: TargetRef) -> Self {
+    YomikaeInfo {
+      target: Some(target),
+      ..self
+    }
+  }
+}
+
+/// 読み替えの種類
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum YomikaeOperation {
+  /// 読み替え前の単語を読み替え後の単語に置き換える
+  #[default]
+  Replace,
+  /// 「」と読み替える場合のように、読み替え前の単語を削る
+  Delete,
+  /// 読み替え前の単語が空であり、読み替え後の単語を加える
+  Insert,
+}
+
+impl YomikaeOperation {
+  /// 読み替え前後の単語から読み替えの種類を判定する
+  pub fn from_words(before_words: &[String], after_word: &str) -> Self {
+    if after_word.is_empty() {
+      YomikaeOperation::Delete
+    } else if before_words.iter().all(|s| s.is_empty()) {
+      YomikaeOperation::Insert
+    } else {
+      YomikaeOperation::Replace
+    }
+  }
+}
+
+/// 読み替えの種類を、以前の出力の`is_deletion`の真偽値からも読み込む
+fn deserialize_operation<'de, D>(deserializer: D) -> Result<YomikaeOperation, D::Error>
+where
+  D: serde::Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum OperationOrDeletion {
+    Operation(YomikaeOperation),
+    IsDeletion(bool),
+  }
+  Ok(match OperationOrDeletion::deserialize(deserializer)? {
+    OperationOrDeletion::Operation(operation) => operation,
+    OperationOrDeletion::IsDeletion(true) => YomikaeOperation::Delete,
+    OperationOrDeletion::IsDeletion(false) => YomikaeOperation::Replace,
+  })
+}
+
+/// 取り出した読み替えの組の確からしさ
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum YomikaeConfidence {
+  /// 取り出した結果に疑わしいところはない
+  #[default]
+  High,
+  /// 読み替え前後の語の括弧の対応が取れていないなど、文の区切り方を誤っている可能性が高い
+  Low,
+}
+
+impl YomikaeConfidence {
+  pub fn is_high(&self) -> bool {
+    matches!(self, YomikaeConfidence::High)
+  }
+}
+
+/// 表から取り出した読み替えの組の、元の表の中での位置（いずれも0始まり）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TableCellRef {
+  /// 行の位置
+  pub row: usize,
+  /// 読み替え前の語の欄の列の位置
+  #[serde(alias = "beforeColumn")]
+  pub before_column: usize,
+  /// 読み替え後の語の欄の列の位置
+  #[serde(alias = "afterColumn")]
+  pub after_column: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct YomikaeData {
   /// 法律番号
-  pub num: String,
+  pub num: LawNum,
   /// その読み替え規定がある条項
   pub article: Article,
   /// 読み替え前後の語のリスト
   pub data: Vec<YomikaeInfo>,
+  /// その読み替え規定がある条項のe-Gov法令検索のURL
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub url: Option<String>,
+  /// 元の法令XMLファイル
+  #[serde(default, alias = "sourceFile", skip_serializing_if = "Option::is_none")]
+  pub source_file: Option<String>,
+  /// 法令XMLの中での条項の要素のパス
+  #[serde(default, alias = "xmlPath", skip_serializing_if = "Option::is_none")]
+  pub xml_path: Option<String>,
+  /// 元の条文の内容を正規化したもののハッシュ値（[`law_text_hash`]）
+  #[serde(
+    default,
+    alias = "contentHash",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub content_hash: Option<String>,
+  /// その条項がある法令の版の情報
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub revision: Option<LawRevision>,
+  /// 法令名（`--include-title`を指定した場合）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub title: Option<String>,
+}
+
+impl YomikaeData {
+  pub fn new(num: impl Into<LawNum>, article: Article, data: Vec<YomikaeInfo>) -> Self {
+    YomikaeData {
+      num: num.into(),
+      article,
+      data,
+      url: None,
+      source_file: None,
+      xml_path: None,
+      content_hash: None,
+      revision: None,
+      title: None,
+    }
+  }
+}
+
+/// [`Article`]を簡単に作るための関数
+pub trait ArticleExt {
+  /// 本則の条を作る
+  fn article(article: impl Into<String>) -> Self;
+  /// 項を指定する
+  fn with_paragraph(self, paragraph: impl Into<String>) -> Self;
+  /// 号を指定する
+  fn with_item(self, item: impl Into<String>) -> Self;
+  /// 附則の条項にする
+  fn in_suppl_provision(self, title: impl Into<String>) -> Self;
+}
+
+impl ArticleExt for Article {
+  fn article(article: impl Into<String>) -> Self {
+    Article {
+      article: article.into(),
+      paragraph: None,
+      item: None,
+      sub_item: None,
+      suppl_provision_title: None,
+    }
+  }
+  fn with_paragraph(self, paragraph: impl Into<String>) -> Self {
+    Article {
+      paragraph: Some(paragraph.into()),
+      ..self
+    }
+  }
+  fn with_item(self, item: impl Into<String>) -> Self {
+    Article {
+      item: Some(item.into()),
+      ..self
+    }
+  }
+  fn in_suppl_provision(self, title: impl Into<String>) -> Self {
+    Article {
+      suppl_provision_title: Some(title.into()),
+      ..self
+    }
+  }
+}
+
+/// 解析を実行した結果の概要
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct RunManifest {
+  /// 解析が完了した法令の法律番号のリスト
+  pub completed: Vec<String>,
+  /// 途中で解析が中断されたかどうか
+  pub is_cancelled: bool,
+  /// 解析が中断された場合に、次に解析するはずだった法令の法律番号
+  #[serde(default)]
+  pub stopped_at: Option<String>,
+  /// 法令ごとに判別した法令XMLのスキーマ
+  #[serde(default)]
+  pub schemas: BTreeMap<String, LawXmlSchema>,
+  /// 法令ごとに判別した文書の種類
+  #[serde(default)]
+  pub document_types: BTreeMap<String, DocumentType>,
+  /// 法令ごとに、解析の前に取り除いたルビの読みの数（取り除かなかった法令は含まない）
+  #[serde(default)]
+  pub ruby_readings_removed: BTreeMap<String, usize>,
+}
+
+/// 読み替え規定文であることを示す文言（既定では「と読み替える」と「」に読み替える」）のどれかを、鉤括弧の外側に含むかどうか
+/// 引用された文言の中にだけある場合は読み替え規定文として扱わない
+/// 表で読み替えると書かれた「同表の下欄の字句に読み替える」の形でもよい
+pub(crate) fn has_yomikae_phrase(s: &str, trigger_phrases: &[String]) -> bool {
+  let masked = mask_quoted(s);
+  trigger_phrases
+    .iter()
+    .any(|phrase| masked.contains(phrase.as_str()))
+    || detect_table_style(s).is_some()
+}
+
+/// 法令の条文のリストから、読み替え規定文と読み替えの表を取り出す
+/// 表で読み替えると書かれているのに、その条項に表が見つからなかった場合は警告のメッセージを返す
+pub fn select_yomikae_law_text(law_text_lst: Vec<LawText>) -> (Vec<LawText>, Vec<Message>) {
+  select_yomikae_law_text_with_config(law_text_lst, &ParserConfig::default())
+}
+
+/// [`select_yomikae_law_text`]と同じように取り出す
+/// 読み替え規定文の候補とする文言は`config`のものを使う
+pub fn select_yomikae_law_text_with_config(
+  law_text_lst: Vec<LawText>,
+  config: &ParserConfig,
+) -> (Vec<LawText>, Vec<Message>) {
+  let mut yomikae_law_text_lst = Vec::new();
+  let mut message_lst = Vec::new();
+  let mut is_yomikae_table = None;
+  for law_text in law_text_lst {
+    match &law_text.contents {
+      LawContents::Text(s) => {
+        if config.is_candidate(s) {
+          if detect_table_style(s).is_some() {
+            is_yomikae_table = Some(law_text.article_info);
+          } else {
+            yomikae_law_text_lst.push(law_text);
+            is_yomikae_table = None;
+          }
+        }
+      }
+      LawContents::Table(_) => match &is_yomikae_table {
+        Some(article) if article == &law_text.article_info => {
+          yomikae_law_text_lst.push(law_text);
+          is_yomikae_table = None;
+        }
+        Some(article) => message_lst.push(Message::TableNotFound(article.clone())),
+        _ => (),
+      },
+    }
+  }
+  (yomikae_law_text_lst, message_lst)
 }
 
 /// 読み替え規定文は
 /// 「((「〜〜」とあり)*「〜〜」とあるのは「〜〜」(と、|と))+読み替えるものとする。」
 /// のような形になっている（読点の有無等の違いは微妙にはある）
-#[allow(clippy::iter_nth_zero)]
-pub async fn parse_yomikae(
+pub fn parse_yomikae(
   law_text: &LawText,
   num: &str,
   article: &Article,
 ) -> Result<Vec<YomikaeInfo>, YomikaeError> {
-  let law_info = LawInfo {
-    num: num.to_string(),
-    article: article.clone(),
-    contents: law_text.clone(),
-  };
+  let law_info = LawInfo::new(num, article.clone(), law_text.clone());
   let input = &law_text.contents;
   match input {
     LawContents::Text(input) => {
       info!("[INPUT] {num} : {:?}", input);
 
-      let mut chars_stream = tokio_stream::iter(input.chars());
+      let mut parser = YomikaeParser::default();
+      if let Err(err) = parser.read(input) {
+        return Err(err(law_info));
+      }
+      Ok(parser.finish())
+    }
 
+    LawContents::Table(table) => {
       let mut yomikae_info_lst = Vec::new();
+      for (row_index, row) in table.iter().enumerate() {
+        let row = &row.row;
+        // 3列の場合は、1列目に読み替える規定が書かれている
+        let (before_column, after_column) = match row.len() {
+          2 => (0, 1),
+          3 => (1, 2),
+          _ => return Err(YomikaeError::ContentsOfTable(law_info)),
+        };
+        let yomikae_info = table_yomikae_info(&row[before_column], &row[after_column]);
+        yomikae_info_lst.push(YomikaeInfo {
+          table_cell: Some(TableCellRef {
+            row: row_index,
+            before_column,
+            after_column,
+          }),
+          ..yomikae_info
+        });
+      }
+      Ok(yomikae_info_lst)
+    }
+  }
+}
 
-      // 角カッコの開き
-      let mut open_kakko_depth: usize = 0;
-      // 角括弧の中にある文字
-      let mut word_in_kakko = String::new();
-
-      let mut before_words = Vec::new();
-      let mut is_before_words_end = false;
+/// 読み替え規定文や読み替えの表を、一文ずつ解析する
+///
+/// 文の場合は「。」で区切り、「と読み替える」を含む文だけを[`parse_yomikae`]で解析して、その結果を文の順に返す
+/// 表の場合は表全体の結果を一つだけ返す
+/// 読み替えの組が一つも取り出せなかった文は[`ParserConfig`]のパターンで取り出せるかを試し、それでも取り出せなければ[`YomikaeError::NotFoundYomikae`]にする
+/// 取り出した読み替え前後の語は[`validate::validate_yomikae_info`]で確かめる
+/// 文についてのエラーには、条文全体と[`LawInfo::sentence_index`]を記録する
+pub fn parse_yomikae_sentences(
+  law_text: &LawText,
+  num: &str,
+) -> Vec<Result<Vec<YomikaeInfo>, YomikaeError>> {
+  parse_yomikae_sentences_with_config(law_text, num, &ParserConfig::default())
+}
 
-      while let Some(c) = chars_stream.next().await {
-        match c {
-          '「' => {
-            if open_kakko_depth >= 1 {
-              // 鉤括弧内の鉤括弧であるので、鉤括弧も登場単語として登録する
-              word_in_kakko.push(c);
-            }
-            open_kakko_depth += 1;
-          }
-          '」' => {
-            if open_kakko_depth == 0 {
-              return Err(YomikaeError::UnmatchedParen(law_info));
-            } else if open_kakko_depth == 1 {
-              open_kakko_depth = 0;
-              // 「とあり」     => before_wordsに追加
-              // 「とある」     => before_wordsに追加し、そこで打ち止め
-              // 「と、」       => after_wordにし、yomikae_info_lstに追加し初期化
-              // 「と読み替える」 => yomikae_info_lstに追加し初期化
-              // 「と「」         => 「と、」と基本同じ
-              // それ以外         => すべて初期化
-              if let Some('と') = chars_stream.next().await {
-                if let Some(c_next2) = chars_stream.next().await {
-                  match c_next2 {
-                    'あ' => {
-                      if let Some(c_next3) = chars_stream.next().await {
-                        match c_next3 {
-                          'り' => {
-                            if is_before_words_end {
-                              return Err(YomikaeError::UnexpectedParallelWords(law_info));
-                            }
-                            before_words.push(word_in_kakko);
-                            word_in_kakko = String::new();
-                            is_before_words_end = false;
-                          }
-                          'る' => {
-                            before_words.push(word_in_kakko);
-                            word_in_kakko = String::new();
-                            is_before_words_end = true;
-                          }
-                          _ => before_words = vec![],
-                        }
-                      }
-                    }
-                    '、' => {
-                      let yomikae_info = YomikaeInfo {
-                        before_words: before_words.clone(),
-                        after_word: word_in_kakko.clone(),
-                      };
-                      if !before_words.is_empty() && !word_in_kakko.is_empty() {
-                        yomikae_info_lst.push(yomikae_info);
-                      }
-                      word_in_kakko = String::new();
-                      is_before_words_end = false;
-                      before_words = vec![];
-                    }
-                    '読' => {
-                      if let Some('み') = chars_stream.next().await {
-                        if let Some('替') = chars_stream.next().await {
-                          if let Some('え') = chars_stream.next().await {
-                            if let Some('る') = chars_stream.next().await {
-                              let yomikae_info = YomikaeInfo {
-                                before_words: before_words.clone(),
-                                after_word: word_in_kakko.clone(),
-                              };
-                              if !before_words.is_empty() && !word_in_kakko.is_empty() {
-                                yomikae_info_lst.push(yomikae_info);
-                              }
-                              word_in_kakko = String::new();
-                              is_before_words_end = false;
-                              before_words = vec![];
-                            }
-                          }
-                        }
-                      }
-                    }
-                    '「' => {
-                      // 終了処理をしてすぐに開始する
-                      let yomikae_info = YomikaeInfo {
-                        before_words: before_words.clone(),
-                        after_word: word_in_kakko.clone(),
-                      };
-                      if !before_words.is_empty() && !word_in_kakko.is_empty() {
-                        yomikae_info_lst.push(yomikae_info);
-                      }
-                      word_in_kakko = String::new();
-                      is_before_words_end = false;
-                      before_words = vec![];
-
-                      open_kakko_depth += 1;
-                    }
-                    _ => {
-                      before_words = vec![];
-                    }
-                  }
-                } else {
-                }
-              } else {
-                before_words = vec![];
-              }
-            } else {
-              // 鉤括弧内に出てきた閉じ鉤括弧
-              word_in_kakko.push(c);
-              open_kakko_depth -= 1;
-            }
-          }
-          _ => {
-            if open_kakko_depth >= 1 {
-              word_in_kakko.push(c);
+/// [`parse_yomikae_sentences`]と同じように解析する
+/// 読み替え規定文の候補とする文言は`config`のものを使う
+pub fn parse_yomikae_sentences_with_config(
+  law_text: &LawText,
+  num: &str,
+  config: &ParserConfig,
+) -> Vec<Result<Vec<YomikaeInfo>, YomikaeError>> {
+  let article = &law_text.article_info;
+  let not_found = |sentence_index| {
+    let law_info = LawInfo {
+      sentence_index,
+      ..LawInfo::new(num, article.clone(), law_text.clone())
+    };
+    YomikaeError::NotFoundYomikae(law_info)
+  };
+  match &law_text.contents {
+    LawContents::Text(input) => split_sentences(input)
+      .into_iter()
+      .enumerate()
+      .filter(|(_, sentence)| config.is_candidate(sentence))
+      .map(|(i, sentence)| {
+        let sentence_law_text = LawText {
+          article_info: article.clone(),
+          contents: LawContents::Text(sentence.clone()),
+        };
+        match parse_yomikae(&sentence_law_text, num, article) {
+          // 状態遷移で取り出せなかった文は、パターンで取り出せるかを試す
+          Ok(yomikae_info_lst) if yomikae_info_lst.is_empty() => {
+            match config.apply_patterns(&sentence) {
+              yomikae_info_lst if yomikae_info_lst.is_empty() => Err(not_found(Some(i))),
+              yomikae_info_lst => Ok(validate_yomikae_info_lst(yomikae_info_lst)),
             }
           }
+          Ok(yomikae_info_lst) => Ok(validate_yomikae_info_lst(yomikae_info_lst)),
+          Err(err) => Err(err.map_law_info(|law_info| LawInfo {
+            contents: law_text.clone(),
+            sentence_index: Some(i),
+            ..law_info
+          })),
         }
-      }
+      })
+      .collect(),
+    LawContents::Table(_) => match parse_yomikae(law_text, num, article) {
+      Ok(yomikae_info_lst) if yomikae_info_lst.is_empty() => vec![Err(not_found(None))],
+      res => vec![res.map(validate_yomikae_info_lst)],
+    },
+  }
+}
 
-      Ok(yomikae_info_lst)
+/// 鉤括弧で囲まれた語を並べる接続詞
+const CONJUNCTIONS: &[&str] = &["及び", "並びに", "又は", "若しくは"];
+
+/// 「、」か接続詞で始まっているかどうか
+fn starts_with_conjunction(s: &str) -> bool {
+  s.starts_with('、')
+    || CONJUNCTIONS
+      .iter()
+      .any(|conjunction| s.starts_with(conjunction))
+}
+
+/// 「、「」「及び「」「、並びに「」のように、語を並べる「、」や接続詞のすぐ後に次の鉤括弧が続く場合は、
+/// 次の鉤括弧の手前までの長さを返す
+fn listing_conjunction_len(s: &str) -> Option<usize> {
+  let rest = s.strip_prefix('、').unwrap_or(s);
+  let rest = CONJUNCTIONS
+    .iter()
+    .find_map(|conjunction| rest.strip_prefix(conjunction))
+    .unwrap_or(rest);
+  if rest.len() < s.len() && rest.starts_with('「') {
+    Some(s.len() - rest.len())
+  } else {
+    None
+  }
+}
+
+/// 鉤括弧で括られた語の後に続く文字列による、読み替え前後の語の組み立ての状態の変わり方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transition {
+  /// 「、「」「及び「」など：読み替え前後のどちらの語になるかを決めずに、次の語と並べる
+  Listing,
+  /// 「とあり、」「とあり、及び」など：読み替え前の語に加える
+  BeforeWord,
+  /// 「とある」：読み替え前の語に加え、読み替え前の語はそこで打ち止めにする
+  LastBeforeWord,
+  /// 「と、」「と読み替える」「とする」「とし、」「とみなす」「に、」「に読み替える」「と「」：読み替え後の語にして、読み替えの組を作る
+  AfterWord,
+  /// それ以外：組み立て途中の状態をすべて捨てる
+  Reset,
+}
+
+/// 閉じ鉤括弧の後に続く、語と語をつなぐ文字列の種類
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Connective {
+  /// 「、「」「及び「」「、並びに「」など：語を並べる
+  Listing,
+  /// 「とあり、」「とあり、及び」など、後に語を並べる「、」や接続詞が続く「とあり」
+  ToAri,
+  /// 「とある」
+  ToAru,
+  /// 「と、」
+  ToComma,
+  /// 「と「」：読み替え後の語の直後に次の語が続く
+  ToQuote,
+  /// 「と読み替える」
+  ToYomikaeru,
+  /// 「とする」「とし、」
+  ToSuru,
+  /// 「とみなす」
+  ToMinasu,
+  /// 「に、」
+  NiComma,
+  /// 「に読み替える」
+  NiYomikaeru,
+  /// それ以外（次の開き鉤括弧までの文字列）
+  Other(String),
+}
+
+impl Connective {
+  /// 読み替え前後の語の組み立ての状態の変わり方
+  pub fn transition(&self) -> Transition {
+    match self {
+      Connective::Listing => Transition::Listing,
+      Connective::ToAri => Transition::BeforeWord,
+      Connective::ToAru => Transition::LastBeforeWord,
+      Connective::ToComma
+      | Connective::ToQuote
+      | Connective::ToYomikaeru
+      | Connective::ToSuru
+      | Connective::ToMinasu
+      | Connective::NiComma
+      | Connective::NiYomikaeru => Transition::AfterWord,
+      Connective::Other(_) => Transition::Reset,
     }
+  }
+}
 
-    LawContents::Table(table) => {
-      let mut table_stream = tokio_stream::iter(table);
-      let mut yomikae_info_lst = Vec::new();
-      while let Some(row) = table_stream.next().await {
-        let row = &row.row;
-        let len = row.len();
-        if len == 2 {
-          yomikae_info_lst.push(YomikaeInfo {
-            before_words: vec![get_table_text(&row[0])],
-            after_word: get_table_text(&row[1]),
-          })
-        } else if len == 3 {
-          yomikae_info_lst.push(YomikaeInfo {
-            before_words: vec![get_table_text(&row[1])],
-            after_word: get_table_text(&row[2]),
-          })
-        } else {
-          return Err(YomikaeError::ContentsOfTable(law_info));
-        }
-      }
-      Ok(yomikae_info_lst)
+/// 読み替え後の語の後に続く文字列と、その種類
+const AFTER_WORD_ENDS: &[(&str, Connective)] = &[
+  ("と、", Connective::ToComma),
+  ("と読み替える", Connective::ToYomikaeru),
+  ("とする", Connective::ToSuru),
+  ("とし、", Connective::ToSuru),
+  ("とみなす", Connective::ToMinasu),
+  ("に、", Connective::NiComma),
+  ("に読み替える", Connective::NiYomikaeru),
+  ("と「", Connective::ToQuote),
+];
+
+/// 閉じ鉤括弧の後に続く文字列を分類し、その続きを読む前に読み飛ばす長さと合わせて返す
+/// 「と「」の場合は、次の語の開き鉤括弧まで読み飛ばす
+/// 「とあり」の後に語を並べる「、」や接続詞が続かない場合は、[`Connective::Other`]にして「とあり」だけを読み飛ばす
+pub fn classify_connective(rest: &str) -> (Connective, usize) {
+  if let Some(len) = listing_conjunction_len(rest) {
+    (Connective::Listing, len)
+  } else if let Some(after) = rest.strip_prefix("とあり") {
+    if starts_with_conjunction(after) {
+      (Connective::ToAri, "とあり".len())
+    } else {
+      (Connective::Other("とあり".to_string()), "とあり".len())
     }
+  } else if rest.starts_with("とある") {
+    (Connective::ToAru, "とある".len())
+  } else if let Some((end, connective)) = AFTER_WORD_ENDS
+    .iter()
+    .find(|(end, _)| rest.starts_with(*end))
+  {
+    (connective.clone(), end.len())
+  } else {
+    let text = rest.split('「').next().unwrap_or_default();
+    (Connective::Other(text.to_string()), 0)
+  }
+}
+
+/// 閉じ鉤括弧の後に続く文字列から、状態の変わり方と、その続きを読む前に読み飛ばす長さを決める
+/// 「と「」の場合は、次の語の開き鉤括弧まで読み飛ばす
+pub fn closing_transition(rest: &str) -> (Transition, usize) {
+  let (connective, len) = classify_connective(rest);
+  (connective.transition(), len)
+}
+
+/// 表の読み替え前と読み替え後の欄から読み替えの情報を作る
+/// 読み替え後の欄が「削る」の場合は削除として扱う
+fn table_yomikae_info(before: &LawTableColumn, after: &LawTableColumn) -> YomikaeInfo {
+  let before_words = vec![get_table_text(before)];
+  let after_word = get_table_text(after);
+  let after_word = if after_word == "削る" {
+    String::new()
+  } else {
+    after_word
+  };
+  YomikaeInfo {
+    operation: YomikaeOperation::from_words(&before_words, &after_word),
+    before_words,
+    after_word,
+    confidence: YomikaeConfidence::High,
+    qualifiers: Vec::new(),
+    law_refs: Vec::new(),
+    target: None,
+    scoped_before_words: Vec::new(),
+    table_cell: None,
   }
 }
 
@@ -259,8 +986,8 @@ fn get_table_text(column: &LawTableColumn) -> String {
   }
 }
 
-#[tokio::test]
-async fn check1() {
+#[test]
+fn check1() {
   let lawtext = LawText {
       article_info: Article {
         article: String::new(),
@@ -278,18 +1005,25 @@ async fn check1() {
     sub_item: None,
     suppl_provision_title: None,
   };
-  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).await.unwrap();
+  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).unwrap();
   assert_eq!(
     vec![YomikaeInfo {
       before_words: vec!["被後見人を代表する".to_string()],
-      after_word: "被保佐人を代表し、又は被保佐人がこれをすることに同意する".to_string()
+      after_word: "被保佐人を代表し、又は被保佐人がこれをすることに同意する".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("第八百五十一条第四号")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     }],
     yomikae_info_lst
   )
 }
 
-#[tokio::test]
-async fn check2() {
+#[test]
+fn check2() {
   let lawtext = LawText {
     article_info: Article {
       article: String::new(),
@@ -307,21 +1041,35 @@ async fn check2() {
     sub_item: None,
     suppl_provision_title: None,
   };
-  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).await.unwrap();
+  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).unwrap();
   assert_eq!(
     vec![YomikaeInfo {
       before_words: vec!["子ども・子育て支援法（平成二十四年法律第六十五号）第六十九条".to_string()],
-      after_word: "平成二十二年度等における子ども手当の支給に関する法律（平成二十二年法律第十九号）第二十条第一項の規定により適用される児童手当法の一部を改正する法律（平成二十四年法律第二十四号）附則第十一条の規定によりなおその効力を有するものとされた同法第一条の規定による改正前の児童手当法（昭和四十六年法律第七十三号）第二十条".to_string()
+      after_word: "平成二十二年度等における子ども手当の支給に関する法律（平成二十二年法律第十九号）第二十条第一項の規定により適用される児童手当法の一部を改正する法律（平成二十四年法律第二十四号）附則第十一条の規定によりなおその効力を有するものとされた同法第一条の規定による改正前の児童手当法（昭和四十六年法律第七十三号）第二十条".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("同条")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     },YomikaeInfo{
       before_words :vec!["子ども・子育て拠出金".to_string()],
-      after_word : "子ども手当拠出金".to_string()
+      after_word : "子ども手当拠出金".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("同条")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     }],
     yomikae_info_lst
   )
 }
 
-#[tokio::test]
-async fn check2_2() {
+#[test]
+fn check2_2() {
   let lawtext = LawText {
     article_info: Article {
       article: String::new(),
@@ -339,21 +1087,35 @@ async fn check2_2() {
     sub_item: None,
     suppl_provision_title: None,
   };
-  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).await.unwrap();
+  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).unwrap();
   assert_eq!(
     vec![YomikaeInfo {
       before_words: vec!["子ども・子育て支援法（平成二十四年法律第六十五号）第六十九条".to_string()],
-      after_word: "平成二十二年度等における子ども手当の支給に関する法律（平成二十二年法律第十九号）第二十条第一項の規定により適用される児童手当法の一部を改正する法律（平成二十四年法律第二十四号）附則第十一条の規定によりなおその効力を有するものとされた同法第一条の規定による改正前の児童手当法（昭和四十六年法律第七十三号）第二十条".to_string()
+      after_word: "平成二十二年度等における子ども手当の支給に関する法律（平成二十二年法律第十九号）第二十条第一項の規定により適用される児童手当法の一部を改正する法律（平成二十四年法律第二十四号）附則第十一条の規定によりなおその効力を有するものとされた同法第一条の規定による改正前の児童手当法（昭和四十六年法律第七十三号）第二十条".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("同条")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     },YomikaeInfo{
       before_words :vec!["子ども・子育て拠出金".to_string()],
-      after_word : "子ども手当拠出金".to_string()
+      after_word : "子ども手当拠出金".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("同条")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     }],
     yomikae_info_lst
   )
 }
 
-#[tokio::test]
-async fn check3() {
+#[test]
+fn check3() {
   let lawtext = LawText {
     article_info: Article {
       article: String::new(),
@@ -371,21 +1133,37 @@ async fn check3() {
     sub_item: None,
     suppl_provision_title: None,
   };
-  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).await.unwrap();
+  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).unwrap();
   assert_eq!(
     vec![YomikaeInfo {
       before_words: vec![
         "それぞれ同項各号に定める者".to_string(),
         "その者".to_string()
       ],
-      after_word: "都道府県の教育委員会".to_string()
+      after_word: "都道府県の教育委員会".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("同項")),
+      scoped_before_words: vec![
+        ScopedWord {
+          scope: Some(TargetRef::provision("同項")),
+          word: "それぞれ同項各号に定める者".to_string(),
+        },
+        ScopedWord {
+          scope: Some(TargetRef::provision("同項第二号")),
+          word: "その者".to_string(),
+        },
+      ],
+      table_cell: None,
     }],
     yomikae_info_lst
   )
 }
 
-#[tokio::test]
-async fn check4() {
+#[test]
+fn check4() {
   let lawtext = LawText {
     article_info: Article {
       article: String::new(),
@@ -403,45 +1181,87 @@ async fn check4() {
     sub_item: None,
     suppl_provision_title: None,
   };
-  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).await.unwrap();
+  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).unwrap();
   assert_eq!(
     vec![YomikaeInfo {
       before_words: vec![
         "保険関係が成立した".to_string()
       ],
-      after_word: "失業保険法及び労働者災害補償保険法の一部を改正する法律及び労働保険の保険料の徴収等に関する法律の施行に伴う関係法律の整備等に関する法律（昭和四十四年法律第八十五号。以下「整備法」という。）第十八条第一項若しくは第二項、第十八条の二第一項若しくは第二項又は第十八条の三第一項若しくは第二項の規定による保険給付が行なわれることとなつた".to_string()
+      after_word: "失業保険法及び労働者災害補償保険法の一部を改正する法律及び労働保険の保険料の徴収等に関する法律の施行に伴う関係法律の整備等に関する法律（昭和四十四年法律第八十五号。以下「整備法」という。）第十八条第一項若しくは第二項、第十八条の二第一項若しくは第二項又は第十八条の三第一項若しくは第二項の規定による保険給付が行なわれることとなつた".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("第二十七条及び第二十八条").with_law("徴収法施行規則")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     },YomikaeInfo {
       before_words: vec![
         "保険関係成立の日".to_string()
       ],
-      after_word: "当該保険給付が行なわれることとなつた日".to_string()
+      after_word: "当該保険給付が行なわれることとなつた日".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("第二十七条及び第二十八条").with_law("徴収法施行規則")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     },YomikaeInfo {
       before_words: vec![
         "全期間".to_string()
       ],
-      after_word: "整備法第十八条第一項若しくは第二項、第十八条の二第一項若しくは第二項又は第十八条の三第一項若しくは第二項の規定による保険給付が行なわれることとなつた日以後の期間（事業の終了する日前に失業保険法及び労働者災害補償保険法の一部を改正する法律及び労働保険の保険料の徴収等に関する法律の施行に伴う労働省令の整備等に関する省令（昭和四十七年労働省令第九号。以下「整備省令」という。）第八条の期間が経過するときは、その経過する日の前日までの期間）".to_string()
+      after_word: "整備法第十八条第一項若しくは第二項、第十八条の二第一項若しくは第二項又は第十八条の三第一項若しくは第二項の規定による保険給付が行なわれることとなつた日以後の期間（事業の終了する日前に失業保険法及び労働者災害補償保険法の一部を改正する法律及び労働保険の保険料の徴収等に関する法律の施行に伴う労働省令の整備等に関する省令（昭和四十七年労働省令第九号。以下「整備省令」という。）第八条の期間が経過するときは、その経過する日の前日までの期間）".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("第二十八条第一項").with_law("徴収法施行規則")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     },YomikaeInfo {
       before_words: vec![
         "第二十七条から前条まで".to_string()
       ],
-      after_word: "第二十七条から第三十条まで".to_string()
+      after_word: "第二十七条から第三十条まで".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("第三十二条").with_law("徴収法施行規則")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     },YomikaeInfo {
       before_words: vec![
         "法第十五条から法第十七条まで".to_string()
       ],
-      after_word: "法第十五条及び第十六条".to_string()
+      after_word: "法第十五条及び第十六条".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("第三十二条").with_law("徴収法施行規則")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     },YomikaeInfo {
       before_words: vec![
         "その事業の期間".to_string()
       ],
-      after_word: "整備法第十八条第一項若しくは第二項、第十八条の二第一項若しくは第二項又は第十八条の三第一項若しくは第二項の規定による保険給付が行なわれることとなつた日以後のその事業の期間（事業の終了する日前に整備省令第八条の期間が経過するときは、その経過する日の前日までの期間）".to_string()
+      after_word: "整備法第十八条第一項若しくは第二項、第十八条の二第一項若しくは第二項又は第十八条の三第一項若しくは第二項の規定による保険給付が行なわれることとなつた日以後のその事業の期間（事業の終了する日前に整備省令第八条の期間が経過するときは、その経過する日の前日までの期間）".to_string(),
+      operation: YomikaeOperation::Replace,
+      confidence: YomikaeConfidence::High,
+      qualifiers: Vec::new(),
+      law_refs: Vec::new(),
+      target: Some(TargetRef::provision("第三十二条").with_law("徴収法施行規則")),
+      scoped_before_words: Vec::new(),
+      table_cell: None,
     }],
     yomikae_info_lst
   )
 }
 
-#[tokio::test]
-async fn check5() {
+#[test]
+fn check5() {
   let lawtext = LawText {
     article_info: Article {
       article: String::new(),
@@ -459,34 +1279,467 @@ async fn check5() {
     sub_item: None,
     suppl_provision_title: None,
   };
-  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).await.unwrap();
+  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).unwrap();
   assert_eq!(
     vec![
       YomikaeInfo {
         before_words: vec!["法第六十九条の三十三第一項".to_string()],
-        after_word: "令第三十七条の七第一項".to_string()
+        after_word: "令第三十七条の七第一項".to_string(),
+        operation: YomikaeOperation::Replace,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: Some(TargetRef::provision("同条第一項")),
+        scoped_before_words: Vec::new(),
+        table_cell: None,
       },
       YomikaeInfo {
         before_words: vec!["前条".to_string()],
-        after_word: "第百十三条の三十七".to_string()
+        after_word: "第百十三条の三十七".to_string(),
+        operation: YomikaeOperation::Replace,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: Some(TargetRef::provision("同項第五号")),
+        scoped_before_words: Vec::new(),
+        table_cell: None,
       },
       YomikaeInfo {
         before_words: vec!["令第三十五条の十六第一項第二号イ".to_string()],
-        after_word: "令第三十七条の七第四項第三号イ".to_string()
+        after_word: "令第三十七条の七第四項第三号イ".to_string(),
+        operation: YomikaeOperation::Replace,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: Some(TargetRef::provision("同条第二項")),
+        scoped_before_words: Vec::new(),
+        table_cell: None,
       },
       YomikaeInfo {
         before_words: vec!["令第三十五条の十六第一項第二号ロ".to_string()],
-        after_word: "令第三十七条の七第四項第三号ロ".to_string()
+        after_word: "令第三十七条の七第四項第三号ロ".to_string(),
+        operation: YomikaeOperation::Replace,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: Some(TargetRef::provision("同条第三項")),
+        scoped_before_words: Vec::new(),
+        table_cell: None,
       },
       YomikaeInfo {
         before_words: vec!["令第三十五条の十六第一項第二号ハ".to_string()],
-        after_word: "令第三十七条の七第四項第三号ハ".to_string()
+        after_word: "令第三十七条の七第四項第三号ハ".to_string(),
+        operation: YomikaeOperation::Replace,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: Some(TargetRef::provision("同条第四項")),
+        scoped_before_words: Vec::new(),
+        table_cell: None,
       },
       YomikaeInfo {
         before_words: vec!["実務研修受講試験の合格年月日並びに研修の受講の開始年月日".to_string()],
-        after_word: "研修の受講の開始年月日".to_string()
+        after_word: "研修の受講の開始年月日".to_string(),
+        operation: YomikaeOperation::Replace,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: Some(TargetRef::provision("同条第四項")),
+        scoped_before_words: Vec::new(),
+        table_cell: None,
       }
     ],
     yomikae_info_lst
   )
 }
+
+#[test]
+fn check6() {
+  let lawtext = LawText {
+    article_info: Article {
+      article: String::new(),
+      paragraph: None,
+      item: None,
+      sub_item: None,
+      suppl_provision_title: None,
+    },
+    contents: LawContents::Text(
+      "この場合において、同条第一項中「、第三項」とあるのは「」と、「前項」とあるのは「第二項」と読み替えるものとする。"
+        .to_string(),
+    ),
+  };
+  let article = Article {
+    article: String::from("test"),
+    paragraph: None,
+    item: None,
+    sub_item: None,
+    suppl_provision_title: None,
+  };
+  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &article).unwrap();
+  assert_eq!(
+    vec![
+      YomikaeInfo {
+        before_words: vec!["、第三項".to_string()],
+        after_word: String::new(),
+        operation: YomikaeOperation::Delete,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: Some(TargetRef::provision("同条第一項")),
+        scoped_before_words: Vec::new(),
+        table_cell: None,
+      },
+      YomikaeInfo {
+        before_words: vec!["前項".to_string()],
+        after_word: "第二項".to_string(),
+        operation: YomikaeOperation::Replace,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: Some(TargetRef::provision("同条第一項")),
+        scoped_before_words: Vec::new(),
+        table_cell: None,
+      }
+    ],
+    yomikae_info_lst
+  )
+}
+
+#[test]
+fn check_operation() {
+  assert_eq!(
+    YomikaeOperation::Replace,
+    YomikaeOperation::from_words(&["前項".to_string()], "第二項")
+  );
+  assert_eq!(
+    YomikaeOperation::Delete,
+    YomikaeOperation::from_words(&["、第三項".to_string()], "")
+  );
+  assert_eq!(
+    YomikaeOperation::Insert,
+    YomikaeOperation::from_words(&[String::new()], "及び第三項")
+  );
+  let yomikae_info: YomikaeInfo =
+    serde_json::from_str(r#"{"before_words":["、第三項"],"after_word":"","is_deletion":true}"#)
+      .unwrap();
+  assert_eq!(YomikaeOperation::Delete, yomikae_info.operation);
+  let yomikae_info: YomikaeInfo =
+    serde_json::from_str(r#"{"before_words":["前項"],"after_word":"第二項","is_deletion":false}"#)
+      .unwrap();
+  assert_eq!(YomikaeOperation::Replace, yomikae_info.operation);
+  let yomikae_info: YomikaeInfo =
+    serde_json::from_str(r#"{"before_words":[""],"after_word":"及び第三項","operation":"Insert"}"#)
+      .unwrap();
+  assert_eq!(YomikaeOperation::Insert, yomikae_info.operation);
+}
+
+#[test]
+fn check_constructors() {
+  let article = Article::article("第三条")
+    .with_paragraph("第二項")
+    .in_suppl_provision("附則");
+  assert_eq!(
+    Article {
+      article: "第三条".to_string(),
+      paragraph: Some("第二項".to_string()),
+      item: None,
+      sub_item: None,
+      suppl_provision_title: Some("附則".to_string()),
+    },
+    article
+  );
+  assert_eq!(
+    YomikaeData {
+      num: LawNum::new("test"),
+      article: article.clone(),
+      data: vec![YomikaeInfo {
+        before_words: vec!["前項".to_string()],
+        after_word: "第二項".to_string(),
+        operation: YomikaeOperation::Replace,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: None,
+        scoped_before_words: Vec::new(),
+        table_cell: None,
+      }],
+      url: None,
+      source_file: None,
+      xml_path: None,
+      content_hash: None,
+      revision: None,
+      title: None,
+    },
+    YomikaeData::new("test", article, vec![YomikaeInfo::new(["前項"], "第二項")])
+  );
+}
+
+#[test]
+fn check_table() {
+  let lawtext = test_utils::LawTextBuilder::table(&[
+    &["第二条", "前条", "第一条"],
+    &["第三条", "、第二項", "削る"],
+    &["前項", "次項"],
+  ])
+  .build();
+  let yomikae_info_lst = parse_yomikae(&lawtext, "test", &test_utils::test_article()).unwrap();
+  assert_eq!(
+    vec![
+      YomikaeInfo {
+        table_cell: Some(TableCellRef {
+          row: 0,
+          before_column: 1,
+          after_column: 2,
+        }),
+        ..YomikaeInfo::new(["前条"], "第一条")
+      },
+      YomikaeInfo {
+        before_words: vec!["、第二項".to_string()],
+        after_word: String::new(),
+        operation: YomikaeOperation::Delete,
+        confidence: YomikaeConfidence::High,
+        qualifiers: Vec::new(),
+        law_refs: Vec::new(),
+        target: None,
+        scoped_before_words: Vec::new(),
+        table_cell: Some(TableCellRef {
+          row: 1,
+          before_column: 1,
+          after_column: 2,
+        }),
+      },
+      YomikaeInfo {
+        table_cell: Some(TableCellRef {
+          row: 2,
+          before_column: 0,
+          after_column: 1,
+        }),
+        ..YomikaeInfo::new(["前項"], "次項")
+      },
+    ],
+    yomikae_info_lst
+  )
+}
+
+#[test]
+fn check_parse_yomikae_sentences() {
+  let law_text = test_utils::law_text(&format!(
+    "{}前条の規定を準用する。「前項」とあるのは「次項」と読み替える。「第一条」と読み替える。",
+    test_utils::SENTENCE_SINGLE
+  ));
+  let res_lst = parse_yomikae_sentences(&law_text, "test");
+  assert_eq!(3, res_lst.len());
+  assert!(matches!(&res_lst[0], Ok(lst) if lst.len() == 1));
+  assert_eq!(Ok(vec![YomikaeInfo::new(["前項"], "次項")]), res_lst[1]);
+  let law_info = LawInfo {
+    sentence_index: Some(3),
+    ..LawInfo::new("test", test_utils::test_article(), law_text.clone())
+  };
+  assert_eq!(Err(YomikaeError::NotFoundYomikae(law_info)), res_lst[2]);
+}
+
+#[test]
+fn check_ni_yomikae() {
+  let law_text = test_utils::law_text(
+    "この場合において、同条中「都道府県知事」とあるのは「市長」に、「都道府県」とあるのは、「市」に読み替えるものとする。",
+  );
+  assert_eq!(
+    vec![
+      YomikaeInfo::new(["都道府県知事"], "市長").with_target(TargetRef::provision("同条")),
+      YomikaeInfo::new(["都道府県"], "市").with_target(TargetRef::provision("同条")),
+    ],
+    parse_yomikae(&law_text, "test", &test_utils::test_article()).unwrap()
+  );
+  assert_eq!(1, parse_yomikae_sentences(&law_text, "test").len());
+}
+
+#[test]
+fn check_sorezore_yomikae() {
+  let law_text = test_utils::law_text(
+    "この場合において、同条中「都道府県知事」及び「都道府県」とあるのは、それぞれ「市長」又は「市」と読み替えるものとする。",
+  );
+  assert_eq!(
+    vec![
+      YomikaeInfo::new(["都道府県知事"], "市長").with_target(TargetRef::provision("同条")),
+      YomikaeInfo::new(["都道府県"], "市").with_target(TargetRef::provision("同条")),
+    ],
+    parse_yomikae(&law_text, "test", &test_utils::test_article()).unwrap()
+  );
+  let law_text = test_utils::law_text(
+    "この場合において、同条中「都道府県知事」及び「都道府県」とあるのは、「市長」と読み替えるものとする。",
+  );
+  assert_eq!(
+    vec![YomikaeInfo::new(["都道府県知事", "都道府県"], "市長")
+      .with_target(TargetRef::provision("同条"))],
+    parse_yomikae(&law_text, "test", &test_utils::test_article()).unwrap()
+  );
+  let law_text = test_utils::law_text(
+    "この場合において、同条中「都道府県知事」、「都道府県」及び「知事」とあるのは、それぞれ「市長」又は「市」と読み替えるものとする。",
+  );
+  assert!(matches!(
+    parse_yomikae(&law_text, "test", &test_utils::test_article()),
+    Err(YomikaeError::UnmatchedSorezoreWords(_))
+  ));
+}
+
+#[test]
+fn check_parallel_before_words() {
+  let law_text = test_utils::law_text(
+    "この場合において、同条中「都道府県知事」とあり、「知事」とあり、及び「都道府県」とあるのは、「市長」と読み替えるものとする。",
+  );
+  assert_eq!(
+    vec![
+      YomikaeInfo::new(["都道府県知事", "知事", "都道府県"], "市長")
+        .with_target(TargetRef::provision("同条"))
+    ],
+    parse_yomikae(&law_text, "test", &test_utils::test_article()).unwrap()
+  );
+  let law_text = test_utils::law_text(
+    "この場合において、同条中「都道府県知事」、「知事」並びに「都道府県」とあるのは「市長」と、「国」とあるのは「市」と読み替えるものとする。",
+  );
+  assert_eq!(
+    vec![
+      YomikaeInfo::new(["都道府県知事", "知事", "都道府県"], "市長")
+        .with_target(TargetRef::provision("同条")),
+      YomikaeInfo::new(["国"], "市").with_target(TargetRef::provision("同条")),
+    ],
+    parse_yomikae(&law_text, "test", &test_utils::test_article()).unwrap()
+  );
+  let law_text = test_utils::law_text(
+    "この場合において、同条中「都道府県知事」とあるのは「市長」の規定を含み、「知事」とあり、及び「都道府県」とあるのは「市」と読み替えるものとする。",
+  );
+  // 読み替えの組にならなかった部分があっても、読み替えの対象は引き継ぐ
+  assert_eq!(
+    vec![YomikaeInfo::new(["知事", "都道府県"], "市").with_target(TargetRef::provision("同条"))],
+    parse_yomikae(&law_text, "test", &test_utils::test_article()).unwrap()
+  );
+  let law_text = test_utils::law_text(
+    "この場合において、同条中「都道府県知事」とあるのは「市長」とあり、及び「知事」とあるのは「市」と読み替えるものとする。",
+  );
+  assert!(matches!(
+    parse_yomikae(&law_text, "test", &test_utils::test_article()),
+    Err(YomikaeError::UnexpectedParallelWords(_))
+  ));
+}
+
+#[test]
+fn check_sorezore_scoped_before_words() {
+  let law_text = test_utils::law_text(
+    "この場合において、同項中「都道府県知事」とあり、及び同項第二号中「知事」とあるのは、それぞれ「市長」及び「市」と読み替えるものとする。",
+  );
+  assert_eq!(
+    vec![
+      YomikaeInfo::new(["都道府県知事"], "市長").with_target(TargetRef::provision("同項")),
+      YomikaeInfo::new(["知事"], "市").with_target(TargetRef::provision("同項第二号")),
+    ],
+    parse_yomikae(&law_text, "test", &test_utils::test_article()).unwrap()
+  );
+}
+
+#[test]
+fn check_table_row_target() {
+  let law_text = test_utils::law_text(
+    "この場合において、同法別表第二の特定疾病の項中「六十五歳」とあるのは「六十歳」と読み替えるものとする。",
+  );
+  let yomikae_info_lst = parse_yomikae(&law_text, "test", &test_utils::test_article()).unwrap();
+  assert_eq!(
+    vec![YomikaeInfo::new(["六十五歳"], "六十歳")
+      .with_target(TargetRef::table("別表第二", Some("特定疾病".to_string())).with_law("同法"))],
+    yomikae_info_lst
+  );
+  assert!(yomikae_info_lst[0].target.as_ref().unwrap().is_table());
+}
+
+#[test]
+fn check_has_yomikae_phrase() {
+  let trigger_phrases = ParserConfig::default().trigger_phrases;
+  assert!(has_yomikae_phrase(
+    test_utils::SENTENCE_SINGLE,
+    &trigger_phrases
+  ));
+  assert!(has_yomikae_phrase(
+    "同表の上欄に掲げる規定中同表の中欄に掲げる字句は、それぞれ同表の下欄の字句に読み替えるものとする。",
+    &trigger_phrases
+  ));
+  assert!(!has_yomikae_phrase(
+    "第五条中「「前項」とあるのは「次項」と読み替える」を削る。",
+    &trigger_phrases
+  ));
+  assert!(!has_yomikae_phrase(
+    test_utils::SENTENCE_SINGLE,
+    &["と読み替えて準用する".to_string()]
+  ));
+}
+
+#[test]
+fn check_select_yomikae_law_text() {
+  let other_article = test_utils::test_article().with_paragraph("2");
+  let law_text_lst = vec![
+    test_utils::law_text("前条の規定を準用する。"),
+    test_utils::law_text(test_utils::SENTENCE_SINGLE),
+    test_utils::law_text(test_utils::SENTENCE_TABLE),
+    test_utils::LawTextBuilder::table(&[&["第二条", "前条", "第一条"]])
+      .article(other_article)
+      .build(),
+  ];
+  let (yomikae_law_text_lst, message_lst) = select_yomikae_law_text(law_text_lst);
+  assert_eq!(
+    vec![test_utils::law_text(test_utils::SENTENCE_SINGLE)],
+    yomikae_law_text_lst
+  );
+  assert_eq!(
+    vec![Message::TableNotFound(test_utils::test_article())],
+    message_lst
+  );
+}
+
+#[test]
+fn check_classify_connective() {
+  assert_eq!(
+    (Connective::Listing, "、".len()),
+    classify_connective("、「次項」とあるのは")
+  );
+  assert_eq!(
+    (Connective::ToAri, "とあり".len()),
+    classify_connective("とあり、及び「次項」")
+  );
+  assert_eq!(
+    (Connective::Other("とあり".to_string()), "とあり".len()),
+    classify_connective("とありますが")
+  );
+  assert_eq!(
+    (Connective::ToAru, "とある".len()),
+    classify_connective("とあるのは「次項」")
+  );
+  assert_eq!(
+    (Connective::ToQuote, "と「".len()),
+    classify_connective("と「前条」とあるのは")
+  );
+  assert_eq!(
+    (Connective::ToSuru, "とする".len()),
+    classify_connective("とする。")
+  );
+  assert_eq!(
+    (Connective::Other("の規定中".to_string()), 0),
+    classify_connective("の規定中「前項」")
+  );
+  assert_eq!(Transition::AfterWord, Connective::ToMinasu.transition());
+  assert_eq!(
+    Transition::Reset,
+    Connective::Other(String::new()).transition()
+  );
+  let law_text =
+    test_utils::law_text("「前項」とあるのは「次項」とし、「前条」とあるのは「次条」とみなす。");
+  assert_eq!(
+    Ok(vec![
+      YomikaeInfo::new(["前項"], "次項"),
+      YomikaeInfo::new(["前条"], "次条")
+    ]),
+    parse_yomikae(&law_text, "test", &test_utils::test_article())
+  );
+  let law_text = test_utils::law_text("「前項」とあるのは「次項」とする。");
+  assert_eq!(
+    Ok(vec![YomikaeInfo::new(["前項"], "次項")]),
+    parse_yomikae(&law_text, "test", &test_utils::test_article())
+  );
+}
@@ -0,0 +1,268 @@
+//! 法令案の読み替え規定文の構造上の問題を調べる
+//!
+//! 起案中の文を解析し、鉤括弧の対応が取れていない箇所や、読み替えの対象の規定に無い読み替え前の語などを報告する
+
+use crate::error_key::error_kind;
+use crate::repair::BracketRepair;
+use crate::segment::{mask_quoted, split_sentences};
+use crate::validate::unbalanced_words;
+use crate::{
+  parse_yomikae, tokenize_yomikae_sentence, ArticleExt, LawInfo, ParserConfig, YomikaeError,
+  YomikaeInfo,
+};
+use jplaw_text::{Article, LawContents, LawText};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 問題の重大さ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+  /// 読み替えの組が正しく取り出せない
+  Error,
+  /// 読み替えの組は取り出せるが、意図と違う可能性がある
+  Warning,
+}
+
+impl LintSeverity {
+  /// 出力に使う名前
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      LintSeverity::Error => "error",
+      LintSeverity::Warning => "warning",
+    }
+  }
+}
+
+/// 問題の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintKind {
+  /// 鉤括弧や括弧の対応が取れていない
+  UnbalancedBrackets,
+  /// 読み替え前の語が、読み替えの対象の規定の文に無い
+  BeforeWordNotFound,
+  /// 同じ読み替え前の語を読み替える規定が複数ある
+  DuplicatedMapping,
+  /// 「とあるのは」があるのに「と読み替える」などの文言が無い
+  MissingTerminalPhrase,
+  /// 読み替え規定文として解析できない
+  ParseError,
+}
+
+impl LintKind {
+  /// 出力に使う名前
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      LintKind::UnbalancedBrackets => "unbalanced_brackets",
+      LintKind::BeforeWordNotFound => "before_word_not_found",
+      LintKind::DuplicatedMapping => "duplicated_mapping",
+      LintKind::MissingTerminalPhrase => "missing_terminal_phrase",
+      LintKind::ParseError => "parse_error",
+    }
+  }
+}
+
+/// 一つの問題
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LintIssue {
+  /// 問題の重大さ
+  pub severity: LintSeverity,
+  /// 問題の種類
+  pub kind: LintKind,
+  /// 問題のある文の位置（0始まり）
+  pub sentence_index: usize,
+  /// 問題のある文
+  pub sentence: String,
+  /// 問題のある語
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub word: Option<String>,
+  /// 問題の説明
+  pub message: String,
+  /// 鉤括弧の対応が取れていない場合の、文の直し方の候補
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub repairs: Vec<BracketRepair>,
+}
+
+/// 法令案の文を一文ずつ調べ、見つかった問題を文の順に返す
+/// `target_text`を与えた場合は、読み替え前の語がその中にあるかも調べる
+/// 読み替え規定文とする文言と、状態遷移で取り出せない文に試すパターンは`config`のものを使う
+pub fn lint_draft(text: &str, target_text: Option<&str>, config: &ParserConfig) -> Vec<LintIssue> {
+  let mut issues = Vec::new();
+  // 読み替え前の語ごとの、最初に読み替えた文の位置と読み替え後の語
+  let mut mappings: HashMap<String, (usize, String)> = HashMap::new();
+  for (i, sentence) in split_sentences(text).into_iter().enumerate() {
+    let mut push = |severity, kind, word: Option<&str>, message: String| {
+      issues.push(LintIssue {
+        severity,
+        kind,
+        sentence_index: i,
+        sentence: sentence.clone(),
+        word: word.map(|s| s.to_string()),
+        message,
+        repairs: Vec::new(),
+      })
+    };
+    // 鉤括弧の直し方の候補は、分割できなかったときのエラーに付いているものを使う
+    if let Err(err) = tokenize_yomikae_sentence(&sentence) {
+      push(
+        LintSeverity::Error,
+        LintKind::UnbalancedBrackets,
+        None,
+        "unmatched 「 or 」 in the sentence".to_string(),
+      );
+      if let Some(issue) = issues.last_mut() {
+        issue.repairs = err.repairs;
+      }
+      continue;
+    }
+    if !config.is_candidate(&sentence) {
+      if mask_quoted(&sentence).contains("とあるのは") {
+        push(
+          LintSeverity::Error,
+          LintKind::MissingTerminalPhrase,
+          None,
+          "「とあるのは」 is not followed by 「と読み替える」".to_string(),
+        );
+      }
+      continue;
+    }
+    let law_text = LawText {
+      article_info: Article::article("1"),
+      contents: LawContents::Text(sentence.clone()),
+    };
+    let article = &law_text.article_info;
+    let res = match parse_yomikae(&law_text, "draft", article) {
+      // 状態遷移で取り出せなかった文は、パターンで取り出せるかを試す
+      Ok(yomikae_info_lst) if yomikae_info_lst.is_empty() => {
+        match config.apply_patterns(&sentence) {
+          yomikae_info_lst if yomikae_info_lst.is_empty() => Err(YomikaeError::NotFoundYomikae(
+            LawInfo::new("draft", article.clone(), law_text.clone()),
+          )),
+          yomikae_info_lst => Ok(yomikae_info_lst),
+        }
+      }
+      res => res,
+    };
+    let yomikae_info_lst = match res {
+      Ok(yomikae_info_lst) => yomikae_info_lst,
+      Err(err) => {
+        push(
+          LintSeverity::Error,
+          LintKind::ParseError,
+          None,
+          format!("failed to parse the sentence: {}", error_kind(&err)),
+        );
+        continue;
+      }
+    };
+    for yomikae_info in yomikae_info_lst.iter() {
+      lint_yomikae_info(yomikae_info, target_text, i, &mut mappings, &mut push);
+    }
+  }
+  issues
+}
+
+fn lint_yomikae_info(
+  yomikae_info: &YomikaeInfo,
+  target_text: Option<&str>,
+  sentence_index: usize,
+  mappings: &mut HashMap<String, (usize, String)>,
+  push: &mut impl FnMut(LintSeverity, LintKind, Option<&str>, String),
+) {
+  for word in unbalanced_words(yomikae_info) {
+    push(
+      LintSeverity::Warning,
+      LintKind::UnbalancedBrackets,
+      Some(word),
+      format!("unbalanced brackets in 「{word}」"),
+    );
+  }
+  for before_word in yomikae_info.before_words.iter() {
+    if let Some(target_text) = target_text {
+      if !target_text.contains(before_word.as_str()) {
+        push(
+          LintSeverity::Warning,
+          LintKind::BeforeWordNotFound,
+          Some(before_word),
+          format!("「{before_word}」 is not found in the target text"),
+        );
+      }
+    }
+    match mappings.get(before_word) {
+      Some((first_index, after_word)) => {
+        let message = if *after_word == yomikae_info.after_word {
+          format!("「{before_word}」 is already read as 「{after_word}」 in sentence {first_index}")
+        } else {
+          format!(
+            "「{before_word}」 is read as 「{after_word}」 in sentence {first_index} and as 「{}」 here",
+            yomikae_info.after_word
+          )
+        };
+        push(
+          LintSeverity::Warning,
+          LintKind::DuplicatedMapping,
+          Some(before_word),
+          message,
+        );
+      }
+      None => {
+        mappings.insert(
+          before_word.clone(),
+          (sentence_index, yomikae_info.after_word.clone()),
+        );
+      }
+    }
+  }
+}
+
+#[test]
+fn check_lint_draft() {
+  let text = "前条中「前項」とあるのは「第二項」と読み替える。\
+    第五条中「前項」とあるのは「第三項」と読み替える。\
+    第六条中「同項」とあるのは「第四項」とする。\
+    第七条中「同項とあるのは「第五項」と読み替える。";
+  let config = ParserConfig::default();
+  let issues = lint_draft(text, Some("前項の規定による届出"), &config);
+  assert_eq!(
+    vec![
+      (LintKind::DuplicatedMapping, 1),
+      (LintKind::MissingTerminalPhrase, 2),
+      (LintKind::UnbalancedBrackets, 3),
+    ],
+    issues
+      .iter()
+      .map(|issue| (issue.kind, issue.sentence_index))
+      .collect::<Vec<_>>()
+  );
+  assert_eq!(Some("前項"), issues[0].word.as_deref());
+  assert!(issues[0].repairs.is_empty());
+  assert!(issues[2].repairs[0].parses);
+  assert_eq!(LintSeverity::Warning, issues[0].severity);
+  let issues = lint_draft(
+    "前条中「前項」とあるのは「第二項」と読み替える。",
+    Some("次項"),
+    &config,
+  );
+  assert_eq!(1, issues.len());
+  assert_eq!(LintKind::BeforeWordNotFound, issues[0].kind);
+  assert!(lint_draft(
+    "前条中「前項」とあるのは「第二項」と読み替える。",
+    None,
+    &config
+  )
+  .is_empty());
+  // 設定した文言を含まない文は、読み替え規定文として調べない
+  let config = ParserConfig::with_trigger_phrases(["と読み替えるものとする"]);
+  assert_eq!(
+    vec![LintKind::MissingTerminalPhrase],
+    lint_draft(
+      "前条中「前項」とあるのは「第二項」と読み替える。",
+      None,
+      &config
+    )
+    .iter()
+    .map(|issue| issue.kind)
+    .collect::<Vec<_>>()
+  );
+}
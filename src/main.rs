@@ -2,179 +2,1442 @@ use analysis_yomikae::*;
 use anyhow::Result;
 use clap::Parser;
 use jplaw_text::{xml_to_law_text, LawContents};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
 use std::path::Path;
+use std::time::Instant;
 use tokio::{
   self,
   fs::*,
-  io::{AsyncReadExt, AsyncWriteExt},
+  io::{AsyncBufReadExt, AsyncWriteExt},
+  sync::watch,
 };
 use tokio_stream::StreamExt;
 use tracing::*;
+use tracing_subscriber::{
+  filter::LevelFilter, fmt::format::FmtSpan, layer::SubscriberExt, Layer, Registry,
+};
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Args {
-  /// 解析結果を出力するJSONファイルへのpath
+  #[clap(subcommand)]
+  command: Option<Command>,
+  /// 解析結果を出力するファイルへのpath
+  #[clap(short, long, required_unless_present_any = ["combined", "verify_determinism"])]
+  output: Option<String>,
+  /// エラーが出た条文の情報を出力するファイルへのpath
+  #[clap(short, long, required_unless_present_any = ["combined", "verify_determinism"])]
+  error_output: Option<String>,
+  /// 解析結果・エラー・警告をまとめて一つのJSONファイルに出力する場合の、そのファイルへのpath
+  #[clap(long, conflicts_with_all = ["output", "error_output", "format"])]
+  combined: Option<String>,
+  /// 解析結果とエラーを出力する形式（json/ndjson/csv/sqlite）
+  #[clap(long, default_value = "json")]
+  format: sink::OutputFormat,
+  /// JSON・NDJSON・CSVで出力するキーや列の名前の形式（snake/camel）
+  #[clap(long, default_value = "snake")]
+  key_case: sink::KeyCase,
+  /// 法令XMLファイル群が置かれている作業ディレクトリへのpath
+  #[clap(short, long, required_unless_present = "input_format")]
+  work: Option<String>,
+  /// 法令ファイルのインデックス情報が書かれたJSONファイル、またはそれらが置かれたディレクトリへのpath
+  /// 複数指定した場合は順につなげ、同じ法律番号の法令は先に現れたものだけを解析する
+  #[clap(short, long, required_unless_present = "input_format")]
+  index_file: Vec<String>,
+  /// 法令XMLの代わりに、idと条文を並べたファイルを解析する場合の、その形式（tsv/csv）
+  #[clap(
+    long,
+    requires = "input",
+    conflicts_with_all = [
+      "work",
+      "index_file",
+      "audit",
+      "egov_url",
+      "revision",
+      "include_title",
+      "enriched_index",
+      "promulgated_after",
+      "promulgated_before",
+      "checkpoint",
+      "resume_from",
+      "verify_determinism",
+    ]
+  )]
+  input_format: Option<text_input::TextInputFormat>,
+  /// `--input-format`で指定した形式の、解析するファイルへのpath
+  #[clap(long, requires = "input_format")]
+  input: Vec<String>,
+  /// 解析が完了した法令の一覧を出力するJSONファイルへのpath
+  #[clap(long)]
+  manifest: Option<String>,
+  /// 法令ファイルの読み込みに失敗した時点で解析全体を終了する
+  #[clap(long)]
+  fail_fast: bool,
+  /// 解析結果を並べ替えずに、解析が終わったものから順に出力する
+  #[clap(long)]
+  unsorted: bool,
+  /// エラーや警告のメッセージの言語（ja/en）
+  #[clap(long, default_value = "en")]
+  lang: Lang,
+  /// トレースを送るOTLPのエンドポイント（`otlp` featureが必要）
+  #[clap(long)]
+  otlp_endpoint: Option<String>,
+  /// 件数や時間などの集計をJSONで出力するファイルへのpath
+  #[clap(long)]
+  metrics_file: Option<String>,
+  /// 解析結果に、読み替え規定がある条項のe-Gov法令検索のURLを含める
+  #[clap(long)]
+  egov_url: bool,
+  /// 解析結果に、法令XMLファイル名と`Law`要素の属性から読んだ法令の版（法令ID・施行日・改正法令・公布日）を含める
+  #[clap(long)]
+  revision: bool,
+  /// 解析結果と条文についてのエラーに、インデックス情報の法令名（無い場合は法令XMLの`LawTitle`要素）を含める
+  #[clap(long)]
+  include_title: bool,
+  /// 法令ごとの解析結果の集計を書き加えたインデックス情報を出力するJSONファイルへのpath
+  #[clap(long)]
+  enriched_index: Option<String>,
+  /// Prometheus形式のメトリクスを公開するアドレス（`prometheus` featureが必要）
+  #[clap(long)]
+  prometheus_listen: Option<SocketAddr>,
+  /// 「と読み替える」を含む全ての文の最終的な扱いをNDJSONで出力するファイルへのpath
+  #[clap(long)]
+  audit: Option<String>,
+  /// 読み替え後の語から「なお従前の例による」などの修飾句が参照している規定を取り出して含める
+  #[clap(long)]
+  qualifiers: bool,
+  /// 読み替え後の語から、法律番号を付けて引用している他の法令を取り出して含める
+  #[clap(long)]
+  law_refs: bool,
+  /// 鉤括弧の対応が取れていない文のエラーに、鉤括弧の直し方の候補を含める
+  #[clap(long)]
+  bracket_repairs: bool,
+  /// 読み替え規定文の候補とする文言（`trigger_phrases`）を設定したTOMLファイルへのpath
+  /// 文言ごとの件数は`--metrics-file`と`--quiet`のまとめに含める
+  #[clap(long)]
+  config: Option<String>,
+  /// JSON・NDJSONの解析結果を、読み替え前の語一つごとに一件の平坦な形で出力する
+  #[clap(long)]
+  flatten: bool,
+  /// この日（YYYY-MM-DD）以降に公布された法令だけを解析する
+  #[clap(long)]
+  promulgated_after: Option<promulgation::PromulgationDate>,
+  /// この日（YYYY-MM-DD）より前に公布された法令だけを解析する
+  #[clap(long)]
+  promulgated_before: Option<promulgation::PromulgationDate>,
+  /// 法令ごとのログを出さずに、解析の終わりに件数やかかった時間のまとめだけを表示する
+  #[clap(long, alias = "summary-only")]
+  quiet: bool,
+  /// 解析を再開するためのチェックポイントを定期的に書き出すJSONファイルへのpath
+  #[clap(long, requires = "unsorted", conflicts_with = "combined")]
+  checkpoint: Option<String>,
+  /// チェックポイントを書き出す間隔（法令の数）
+  #[clap(long, default_value = "100")]
+  checkpoint_interval: usize,
+  /// チェックポイントのJSONファイルを読み込んで、その続きから解析を再開する
+  #[clap(long, requires = "unsorted", conflicts_with = "combined")]
+  resume_from: Option<String>,
+  /// 法令を二回解析し、順番に依らない形にそろえた解析結果とエラーが一致するかを確かめる（開発用）
+  #[clap(long, conflicts_with_all = ["combined", "checkpoint", "resume_from"])]
+  verify_determinism: bool,
+  /// `--verify-determinism`で一回目と二回目に並行して解析する法令の数（「1,8」のように二つ指定すると、それぞれで変える）
+  #[clap(
+    long,
+    value_delimiter = ',',
+    default_value = "1,4",
+    requires = "verify_determinism"
+  )]
+  verify_jobs: Vec<usize>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+  /// 解析と解析結果の検索をHTTPで提供するサーバーを起動する
+  #[cfg(feature = "server")]
+  Serve(ServeArgs),
+  /// 解析結果から全文検索のインデックスを作る
+  #[cfg(feature = "tantivy")]
+  Index(IndexArgs),
+  /// インデックスから読み替え前後の語を検索する
+  #[cfg(feature = "tantivy")]
+  Search(SearchArgs),
+  /// 解析結果とエラーをTUIで見て回る
+  #[cfg(feature = "tui")]
+  Browse(BrowseArgs),
+  /// 解析結果をRDFのトリプルとして出力する
+  ExportRdf(ExportRdfArgs),
+  /// 読み替え規定の文言を含むのに解析結果が一つもない法令を調べる
+  Coverage(CoverageArgs),
+  /// 標準入力から一行ずつ文を読み、解析の過程と結果を表示する
+  Repl,
+  /// 法令案の読み替え規定文の構造上の問題を調べる
+  Lint(LintArgs),
+  /// 解析のエラーをSARIF 2.1.0の形式で出力する
+  ExportSarif(ExportSarifArgs),
+  /// ディレクトリに置かれた法令XMLファイルを見張り、置かれるたびに解析して結果を書き出す
+  Daemon(DaemonArgs),
+  /// 指定した法令の条項を読み替えている規定を、解析結果から探す
+  Readers(ReadersArgs),
+  /// 読み替え規定文の候補を鉤括弧で分割だけして、まだ扱えていない語と語をつなぐ文字列を件数の多い順に一覧にする
+  MineConnectives(MineConnectivesArgs),
+}
+
+#[cfg(feature = "server")]
+#[derive(clap::Args, Debug)]
+struct ServeArgs {
+  /// サーバーが待ち受けるアドレス
+  #[clap(long, default_value = "127.0.0.1:3000")]
+  listen: SocketAddr,
+  /// 検索の対象にする、解析結果が書かれたJSONファイルへのpath
+  #[clap(long)]
+  data: Option<String>,
+  /// GraphQLで引けるようにする、エラーが書かれたJSONファイルへのpath
+  #[cfg(feature = "graphql")]
+  #[clap(long)]
+  errors: Option<String>,
+}
+
+#[cfg(feature = "tantivy")]
+#[derive(clap::Args, Debug)]
+struct IndexArgs {
+  /// 解析結果が書かれたJSONファイルへのpath
+  #[clap(long)]
+  data: String,
+  /// インデックスを保存するディレクトリへのpath
+  #[clap(long)]
+  index_dir: String,
+}
+
+#[cfg(feature = "tantivy")]
+#[derive(clap::Args, Debug)]
+struct SearchArgs {
+  /// インデックスが保存されているディレクトリへのpath
+  #[clap(long)]
+  index_dir: String,
+  /// 検索する語
+  word: String,
+  /// 検索する対象（before/after/any）
+  #[clap(long, default_value = "any")]
+  target: search_index::SearchTarget,
+  /// 表示する解析結果の最大数
+  #[clap(long, default_value = "100")]
+  limit: usize,
+  /// 検索結果をJSONで出力する
+  #[clap(long)]
+  json: bool,
+}
+
+#[cfg(feature = "tui")]
+#[derive(clap::Args, Debug)]
+struct BrowseArgs {
+  /// 解析結果が書かれたJSONファイルへのpath
+  #[clap(short, long)]
+  output: String,
+  /// エラーが出た条文の情報が書かれたJSONファイルへのpath
+  #[clap(short, long)]
+  error_output: Option<String>,
+  /// 元の文を表示するための、法令XMLファイル群が置かれている作業ディレクトリへのpath
+  #[clap(short, long, requires = "index_file")]
+  work: Option<String>,
+  /// 元の文を表示するための、法令ファイルのインデックス情報が書かれたJSONファイルへのpath
+  #[clap(short, long, requires = "work")]
+  index_file: Option<String>,
+  /// エラーメッセージの言語（ja/en）
+  #[clap(long, default_value = "en")]
+  lang: Lang,
+}
+
+#[derive(clap::Args, Debug)]
+struct ExportRdfArgs {
+  /// 解析結果が書かれたJSONファイルへのpath
+  #[clap(long)]
+  data: String,
+  /// 法令IDを得るための、法令ファイルのインデックス情報が書かれたJSONファイルへのpath
+  #[clap(short, long)]
+  index_file: Option<String>,
+  /// 出力する形式（turtle/nquads）
+  #[clap(long, default_value = "turtle")]
+  format: rdf::RdfFormat,
+  /// N-Quads形式で出力する場合のグラフのIRI
+  #[clap(long)]
+  graph: Option<String>,
+  /// 出力するファイルへのpath（省略した場合は標準出力）
+  #[clap(short, long)]
+  output: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct CoverageArgs {
+  /// 解析結果が書かれたJSONファイルへのpath
+  #[clap(long)]
+  data: String,
+  /// 法令XMLファイル群が置かれている作業ディレクトリへのpath
+  #[clap(short, long)]
+  work: String,
+  /// 法令ファイルのインデックス情報が書かれたJSONファイルへのpath
+  #[clap(short, long)]
+  index_file: String,
+  /// 読み替え規定文の候補とする文言（`trigger_phrases`）を設定したTOMLファイルへのpath
+  #[clap(long)]
+  config: Option<String>,
+  /// 調べた結果をJSONで出力する
+  #[clap(long)]
+  json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct LintArgs {
+  /// 法令案の文が書かれたテキストファイルへのpath
+  file: String,
+  /// 読み替えの対象の規定の文が書かれたテキストファイルへのpath
+  #[clap(long)]
+  target: Option<String>,
+  /// 見つかった問題をJSONで出力する
+  #[clap(long)]
+  json: bool,
+  /// 見つかった問題をSARIF 2.1.0の形式で出力する
+  #[clap(long, conflicts_with = "json")]
+  sarif: bool,
+  /// 読み替え規定文の候補とする文言（`trigger_phrases`）を設定したTOMLファイルへのpath
+  #[clap(long)]
+  config: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ExportSarifArgs {
+  /// エラーが書かれたJSONファイルへのpath
+  #[clap(long)]
+  errors: String,
+  /// 出力するファイルへのpath（省略した場合は標準出力）
+  #[clap(short, long)]
+  output: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct DaemonArgs {
+  /// 解析する法令XMLファイルが置かれるディレクトリへのpath
+  #[clap(long)]
+  queue: String,
+  /// ジョブごとの解析結果を書き出すディレクトリへのpath
   #[clap(short, long)]
   output: String,
-  /// エラーが出た条文の情報を出力するJSONファイルへのpath
+  /// 法令XMLファイル名から法律番号を得るための、法令ファイルのインデックス情報が書かれたJSONファイルへのpath
   #[clap(short, long)]
-  error_output: String,
+  index_file: Option<String>,
+  /// キューのディレクトリを調べる間隔（ミリ秒）
+  #[clap(long, default_value = "1000")]
+  poll_interval: u64,
+  /// 解析結果にe-Gov法令検索のURLを含める
+  #[clap(long)]
+  egov_url: bool,
+  /// 置かれている法令XMLファイルを全て解析したら終了する
+  #[clap(long)]
+  once: bool,
+  /// 読み替え規定文の候補とする文言（`trigger_phrases`）を設定したTOMLファイルへのpath
+  #[clap(long)]
+  config: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+struct ReadersArgs {
+  /// 解析結果が書かれたJSONファイルへのpath
+  #[clap(long)]
+  data: String,
+  /// 読み替えられる法令の名前、または法律番号
+  #[clap(long)]
+  law: String,
+  /// 読み替えられる条項（「第八百五十一条」など）
+  #[clap(long)]
+  article: String,
+  /// 探した結果をJSONで出力する
+  #[clap(long)]
+  json: bool,
+}
+
+#[derive(clap::Args, Debug)]
+struct MineConnectivesArgs {
   /// 法令XMLファイル群が置かれている作業ディレクトリへのpath
   #[clap(short, long)]
   work: String,
   /// 法令ファイルのインデックス情報が書かれたJSONファイルへのpath
   #[clap(short, long)]
   index_file: String,
+  /// 読み替え規定文の候補とする文言（`trigger_phrases`）を設定したTOMLファイルへのpath
+  #[clap(long)]
+  config: Option<String>,
+  /// 表示する文字列の最大数
+  #[clap(long, default_value = "100")]
+  limit: usize,
+  /// 集めた結果をJSONで出力する
+  #[clap(long)]
+  json: bool,
+}
+
+/// サブコマンドを指定しない場合に必要な引数を取り出す
+fn required_arg<'a>(arg: &'a Option<String>, name: &str) -> Result<&'a str> {
+  arg
+    .as_deref()
+    .ok_or_else(|| anyhow::anyhow!("--{name} is required"))
 }
 
-async fn init_logger() -> Result<()> {
-  let subscriber = tracing_subscriber::fmt()
-    .with_max_level(tracing::Level::INFO)
-    .finish();
+/// 法令や条文ごとのspanの開始と終了をログに出す
+/// `quiet`の場合はログを出さない
+/// OTLPのエンドポイントが指定された場合はトレースをそこにも送る
+async fn init_logger(otlp_endpoint: Option<&str>, quiet: bool) -> Result<()> {
+  let otlp_layer = match otlp_endpoint {
+    Some(endpoint) => Some(otlp_layer(endpoint)?),
+    None => None,
+  };
+  let fmt_layer = tracing_subscriber::fmt::layer()
+    .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+    .with_filter(if quiet {
+      LevelFilter::OFF
+    } else {
+      LevelFilter::INFO
+    });
+  let subscriber = tracing_subscriber::registry()
+    .with(otlp_layer)
+    .with(fmt_layer);
   tracing::subscriber::set_global_default(subscriber)?;
   Ok(())
 }
 
+#[cfg(feature = "otlp")]
+fn otlp_layer(endpoint: &str) -> Result<impl Layer<Registry>> {
+  use opentelemetry_otlp::WithExportConfig;
+  let tracer = opentelemetry_otlp::new_pipeline()
+    .tracing()
+    .with_exporter(
+      opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(endpoint),
+    )
+    .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+      opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+        "service.name",
+        env!("CARGO_PKG_NAME"),
+      )]),
+    ))
+    .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+  Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+#[cfg(not(feature = "otlp"))]
+fn otlp_layer(_endpoint: &str) -> Result<impl Layer<Registry>> {
+  Err::<tracing_subscriber::layer::Identity, _>(anyhow::anyhow!(
+    "--otlp-endpoint requires the `otlp` feature"
+  ))
+}
+
+/// Prometheusがメトリクスを取得するためのHTTPサーバーを立てる
+#[cfg(feature = "prometheus")]
+fn install_prometheus(addr: SocketAddr) -> Result<()> {
+  metrics_exporter_prometheus::PrometheusBuilder::new()
+    .with_http_listener(addr)
+    .install()?;
+  Ok(())
+}
+
+#[cfg(not(feature = "prometheus"))]
+fn install_prometheus(_addr: SocketAddr) -> Result<()> {
+  Err(anyhow::anyhow!(
+    "--prometheus-listen requires the `prometheus` feature"
+  ))
+}
+
+/// HTTPサーバーを起動する
+#[cfg(feature = "server")]
+async fn serve(serve_args: ServeArgs) -> Result<()> {
+  let dataset: Vec<YomikaeData> = match &serve_args.data {
+    Some(path) => serde_json::from_slice(&read(path).await?)?,
+    None => Vec::new(),
+  };
+  #[cfg(feature = "graphql")]
+  let app = {
+    let error_lst: Vec<YomikaeError> = match &serve_args.errors {
+      Some(path) => serde_json::from_slice(&read(path).await?)?,
+      None => Vec::new(),
+    };
+    server::router(dataset.clone()).merge(graphql::router(dataset, error_lst))
+  };
+  #[cfg(not(feature = "graphql"))]
+  let app = server::router(dataset);
+  let listener = tokio::net::TcpListener::bind(serve_args.listen).await?;
+  info!("listening on {}", serve_args.listen);
+  axum::serve(listener, app).await?;
+  Ok(())
+}
+
+/// 解析結果のJSONファイルからインデックスを作る
+#[cfg(feature = "tantivy")]
+async fn build_index(index_args: IndexArgs) -> Result<()> {
+  let yomikae_data_lst: Vec<YomikaeData> = serde_json::from_slice(&read(&index_args.data).await?)?;
+  search_index::build_index(Path::new(&index_args.index_dir), &yomikae_data_lst)?;
+  info!(
+    "indexed {} entries into {}",
+    yomikae_data_lst.len(),
+    index_args.index_dir
+  );
+  Ok(())
+}
+
+/// インデックスから語を検索して標準出力に書き出す
+#[cfg(feature = "tantivy")]
+async fn search_word(search_args: SearchArgs) -> Result<()> {
+  let yomikae_data_lst = search_index::search(
+    Path::new(&search_args.index_dir),
+    &search_args.word,
+    search_args.target,
+    search_args.limit,
+  )?;
+  for yomikae_data in yomikae_data_lst {
+    if search_args.json {
+      println!("{}", serde_json::to_string(&yomikae_data)?);
+    } else {
+      println!("{yomikae_data}");
+    }
+  }
+  Ok(())
+}
+
+/// 解析結果とエラーのJSONファイルを読み込んでTUIを起動する
+#[cfg(feature = "tui")]
+async fn browse_results(browse_args: BrowseArgs) -> Result<()> {
+  let yomikae_data_lst = serde_json::from_slice(&read(&browse_args.output).await?)?;
+  let error_lst = match &browse_args.error_output {
+    Some(path) => serde_json::from_slice(&read(path).await?)?,
+    None => Vec::new(),
+  };
+  let loader = match (&browse_args.work, &browse_args.index_file) {
+    (Some(work), Some(index_file)) => {
+      let files = listup_law::get_law_from_index(index_file)
+        .await?
+        .into_iter()
+        .map(|law_data| (law_data.num, law_data.file))
+        .collect();
+      Some(browse::SourceLoader {
+        work_dir: Path::new(work).to_path_buf(),
+        files,
+      })
+    }
+    _ => None,
+  };
+  let laws = browse::group_results(yomikae_data_lst, error_lst);
+  browse::run(laws, loader, browse_args.lang).await?;
+  Ok(())
+}
+
+/// 解析結果のJSONファイルをRDFにして出力する
+async fn export_rdf(export_rdf_args: ExportRdfArgs) -> Result<()> {
+  let yomikae_data_lst: Vec<YomikaeData> =
+    serde_json::from_slice(&read(&export_rdf_args.data).await?)?;
+  let law_ids = match &export_rdf_args.index_file {
+    Some(index_file) => listup_law::get_law_from_index(index_file)
+      .await?
+      .into_iter()
+      .filter_map(|law_data| egov::law_id_from_file(&law_data.file).map(|id| (law_data.num, id)))
+      .collect(),
+    None => HashMap::new(),
+  };
+  let rdf_str = rdf::to_rdf(
+    &yomikae_data_lst,
+    &law_ids,
+    export_rdf_args.format,
+    export_rdf_args.graph.as_deref(),
+  );
+  match &export_rdf_args.output {
+    Some(path) => {
+      let mut output_file = File::create(path).await?;
+      output_file.write_all(rdf_str.as_bytes()).await?;
+      output_file.flush().await?;
+    }
+    None => print!("{rdf_str}"),
+  }
+  Ok(())
+}
+
+async fn export_sarif(export_sarif_args: ExportSarifArgs) -> Result<()> {
+  let error_lst: Vec<YomikaeError> =
+    serde_json::from_slice(&read(&export_sarif_args.errors).await?)?;
+  let sarif_str = serde_json::to_string(&sarif::errors_to_sarif(&error_lst))?;
+  match &export_sarif_args.output {
+    Some(path) => {
+      let mut output_file = File::create(path).await?;
+      output_file.write_all(sarif_str.as_bytes()).await?;
+      output_file.flush().await?;
+    }
+    None => println!("{sarif_str}"),
+  }
+  Ok(())
+}
+
+/// 指定した条項を読み替えている規定と、その読み替えの組を標準出力に書き出す
+async fn find_readers(readers_args: ReadersArgs) -> Result<()> {
+  let dataset: YomikaeDataSet = serde_json::from_slice(&read(&readers_args.data).await?)?;
+  let readers = dataset.readers_of(&readers_args.law, &readers_args.article);
+  if readers_args.json {
+    println!("{}", serde_json::to_string(&readers)?);
+  } else {
+    for reader in &readers {
+      println!("{}\t{}", reader.num, article_to_string(&reader.article));
+      for yomikae_info in &reader.pairs {
+        println!("  {yomikae_info}");
+      }
+    }
+  }
+  Ok(())
+}
+
+/// キューのディレクトリに置かれた法令XMLファイルを、割り込まれるまで解析し続ける
+/// インデックス情報は起動したときに一度だけ読み込む
+async fn run_daemon(daemon_args: DaemonArgs, lang: Lang) -> Result<()> {
+  let nums = match &daemon_args.index_file {
+    Some(index_file) => listup_law::get_law_from_index(index_file)
+      .await?
+      .into_iter()
+      .map(|law_data| (law_data.file, law_data.num))
+      .collect(),
+    None => HashMap::new(),
+  };
+  let queue = queue::JobQueue::new(&daemon_args.queue, &daemon_args.output).with_nums(nums);
+  let config = batch::AnalyzeConfig {
+    egov_url: daemon_args.egov_url,
+    lang,
+    options: pipeline::LawAnalysisOptions {
+      parser_config: load_parser_config(daemon_args.config.as_deref()).await?,
+      ..Default::default()
+    },
+    ..Default::default()
+  };
+  let poll_interval = std::time::Duration::from_millis(daemon_args.poll_interval);
+  loop {
+    for job_result in queue.process_pending_jobs(&config).await? {
+      info!(
+        "[JOB] {} ({}): {} results, {} errors",
+        job_result.job,
+        job_result.num,
+        job_result.results.len(),
+        job_result.errors.len()
+      );
+    }
+    if daemon_args.once {
+      return Ok(());
+    }
+    tokio::select! {
+      _ = tokio::time::sleep(poll_interval) => (),
+      _ = tokio::signal::ctrl_c() => {
+        warn!("{}", Message::Interrupted.to_message(lang));
+        return Ok(());
+      }
+    }
+  }
+}
+
+/// Ctrl-Cが押されると`true`になる受け取り側を作る
+/// 一度目の割り込みでは警告を出すだけで、二度目の割り込みでは出力を待たずに終了する
+fn cancel_on_interrupt(lang: Lang) -> watch::Receiver<bool> {
+  let cancel_rx = batch::cancel_on_ctrl_c();
+  let mut interrupted_rx = cancel_rx.clone();
+  tokio::spawn(async move {
+    if interrupted_rx.changed().await.is_ok() {
+      warn!("{}", Message::Interrupted.to_message(lang));
+      if tokio::signal::ctrl_c().await.is_ok() {
+        std::process::exit(130);
+      }
+    }
+  });
+  cancel_rx
+}
+
+/// 法令を二回解析して、順番に依らない形にそろえた結果を比べ、違いを標準出力に書き出す
+/// 解析のしかたは通常の解析と同じ`options`と、`--include-title`などの指定に従う
+/// 違いがあった場合はエラーにする
+async fn verify_determinism(args: &Args, options: &pipeline::LawAnalysisOptions) -> Result<()> {
+  let work = required_arg(&args.work, "work")?;
+  if args.index_file.is_empty() {
+    return Err(anyhow::anyhow!("--index-file is required"));
+  }
+  let index_file_lst = expand_index_files(&args.index_file).await?;
+  let mut index_lst = Vec::new();
+  for index_file in index_file_lst.iter() {
+    let law_data_lst = listup_law::get_law_from_index(index_file).await?;
+    index_lst.push((index_file.clone(), law_data_lst));
+  }
+  let (law_data_lst, _) = merge_law_index(index_lst, |law_data| law_data.num.as_str());
+  let laws = law_data_lst
+    .into_iter()
+    .map(|law_data| LawFileInfo {
+      num: law_data.num,
+      file: law_data.file,
+    })
+    .collect::<Vec<_>>();
+  // 通常の解析と同じように、インデックスファイルの法令名を法令XMLの法令名より優先する
+  let mut law_titles = HashMap::new();
+  if args.include_title {
+    for index_file in index_file_lst.iter() {
+      let index: serde_json::Value = serde_json::from_slice(&read(index_file).await?)?;
+      collect_law_titles(&index, &mut law_titles);
+    }
+  }
+  let config = batch::AnalyzeConfig {
+    work_dir: Path::new(work).to_path_buf(),
+    egov_url: args.egov_url,
+    lang: args.lang,
+    revision: args.revision,
+    include_title: args.include_title,
+    titles: law_titles.into_iter().collect(),
+    options: options.clone(),
+  };
+  let cancel_rx = cancel_on_interrupt(args.lang);
+  let mut output_lst = Vec::new();
+  for run in 0..2 {
+    let jobs = args
+      .verify_jobs
+      .get(run)
+      .or_else(|| args.verify_jobs.first())
+      .copied()
+      .unwrap_or(1);
+    info!("[VERIFY] run {} with {jobs} jobs", run + 1);
+    let res_lst = batch::analyze_laws(
+      tokio_stream::iter(laws.clone()),
+      config.clone(),
+      jobs,
+      cancel_rx.clone(),
+    )
+    .collect::<Vec<_>>()
+    .await;
+    // 途中で止めた結果を比べても意味が無いので、比べずに終了する
+    if *cancel_rx.borrow() {
+      return Err(anyhow::anyhow!("the verification was interrupted"));
+    }
+    output_lst.push(determinism::CanonicalOutput::new(&res_lst));
+  }
+  let report = determinism::compare_outputs(&output_lst[0], &output_lst[1]);
+  println!(
+    "first run: {}, second run: {}",
+    report.first_count, report.second_count
+  );
+  for s in &report.only_first {
+    println!("- {s}");
+  }
+  for s in &report.only_second {
+    println!("+ {s}");
+  }
+  if !report.is_deterministic() {
+    return Err(anyhow::anyhow!(
+      "the two runs produced different results ({} only in the first run, {} only in the second run)",
+      report.only_first.len(),
+      report.only_second.len()
+    ));
+  }
+  Ok(())
+}
+
+/// 読み替え規定の文言を含む法令と解析結果を比べて、解析結果がない法令を標準出力に書き出す
+async fn report_coverage(coverage_args: CoverageArgs) -> Result<()> {
+  let yomikae_data_lst: Vec<YomikaeData> =
+    serde_json::from_slice(&read(&coverage_args.data).await?)?;
+  let parser_config = load_parser_config(coverage_args.config.as_deref()).await?;
+  let law_data_lst = listup_law::get_law_from_index(&coverage_args.index_file).await?;
+  let work_dir_path = Path::new(&coverage_args.work);
+  let mut laws = Vec::new();
+  let mut law_data_stream = tokio_stream::iter(law_data_lst);
+  while let Some(law_data) = law_data_stream.next().await {
+    let has_phrase = match read(work_dir_path.join(&law_data.file)).await {
+      Ok(buf) => {
+        coverage::law_xml_has_yomikae_phrase(&ruby::strip_ruby_readings(&buf).0, &parser_config)
+      }
+      Err(e) => {
+        warn!("failed to read {}: {e}", law_data.file);
+        false
+      }
+    };
+    let law_file_info = LawFileInfo {
+      num: law_data.num,
+      file: law_data.file,
+    };
+    laws.push((law_file_info, has_phrase));
+  }
+  let report = coverage::coverage_report(&laws, &yomikae_data_lst);
+  if coverage_args.json {
+    println!("{}", serde_json::to_string(&report)?);
+  } else {
+    println!(
+      "{} / {} laws with yomikae phrases have output",
+      report.laws_with_output, report.laws_with_phrase
+    );
+    for law_file_info in &report.missing {
+      println!("{}\t{}", law_file_info.num, law_file_info.file);
+    }
+  }
+  Ok(())
+}
+
+async fn mine_connectives(mine_connectives_args: MineConnectivesArgs) -> Result<()> {
+  let parser_config = load_parser_config(mine_connectives_args.config.as_deref()).await?;
+  let mut miner = mining::ConnectiveMiner::with_config(parser_config);
+  let law_data_lst = listup_law::get_law_from_index(&mine_connectives_args.index_file).await?;
+  let work_dir_path = Path::new(&mine_connectives_args.work);
+  let mut law_data_stream = tokio_stream::iter(law_data_lst);
+  while let Some(law_data) = law_data_stream.next().await {
+    let buf = match read(work_dir_path.join(&law_data.file)).await {
+      Ok(buf) => buf,
+      Err(e) => {
+        warn!("failed to read {}: {e}", law_data.file);
+        continue;
+      }
+    };
+    match xml_to_law_text(&ruby::strip_ruby_readings(&buf).0).await {
+      Ok(law_text_lst) => miner.add_law_text_lst(&law_text_lst),
+      Err(e) => warn!("failed to parse {}: {e}", law_data.file),
+    }
+  }
+  let mut report = miner.into_report();
+  report.connectives.truncate(mine_connectives_args.limit);
+  if mine_connectives_args.json {
+    println!("{}", serde_json::to_string(&report)?);
+  } else {
+    println!(
+      "{} sentences ({} with unmatched brackets)",
+      report.sentences, report.unmatched_sentences
+    );
+    for frequency in &report.connectives {
+      println!(
+        "{}\t{:?}\t{}",
+        frequency.count, frequency.text, frequency.example
+      );
+    }
+  }
+  Ok(())
+}
+
+async fn repl() -> Result<()> {
+  let mut lines = tokio::io::BufReader::new(tokio::io::stdin()).lines();
+  let mut stdout = tokio::io::stdout();
+  stdout.write_all(b"> ").await?;
+  stdout.flush().await?;
+  while let Some(line) = lines.next_line().await? {
+    let sentence = line.trim();
+    if !sentence.is_empty() {
+      let explanation = explain::explain_sentence(sentence);
+      stdout
+        .write_all(format!("{explanation}\n").as_bytes())
+        .await?;
+    }
+    stdout.write_all(b"> ").await?;
+    stdout.flush().await?;
+  }
+  Ok(())
+}
+
+async fn lint_draft_file(lint_args: LintArgs) -> Result<()> {
+  let text = read_to_string(&lint_args.file).await?;
+  let target_text = match &lint_args.target {
+    Some(target) => Some(read_to_string(target).await?),
+    None => None,
+  };
+  let parser_config = load_parser_config(lint_args.config.as_deref()).await?;
+  let issues = lint::lint_draft(&text, target_text.as_deref(), &parser_config);
+  if lint_args.sarif {
+    let sarif = sarif::lint_to_sarif(&lint_args.file, &text, &issues);
+    println!("{}", serde_json::to_string(&sarif)?);
+  } else if lint_args.json {
+    println!("{}", serde_json::to_string(&issues)?);
+  } else {
+    for issue in &issues {
+      println!(
+        "{}: sentence {}: {}",
+        issue.severity.as_str(),
+        issue.sentence_index,
+        issue.message
+      );
+      for repair in &issue.repairs {
+        let edits = repair
+          .edits
+          .iter()
+          .map(|edit| edit.to_string())
+          .collect::<Vec<_>>()
+          .join(", ");
+        println!("  suggestion: {edits}");
+      }
+    }
+  }
+  let error_count = issues
+    .iter()
+    .filter(|issue| issue.severity == lint::LintSeverity::Error)
+    .count();
+  if error_count == 0 {
+    Ok(())
+  } else {
+    Err(anyhow::anyhow!("{error_count} errors found"))
+  }
+}
+
+/// 指定されたインデックス情報のpathのうち、ディレクトリであるものをその中のJSONファイルのpathに置き換える
+/// ディレクトリの中のJSONファイルはファイル名の順に並べる
+async fn expand_index_files(index_file_lst: &[String]) -> Result<Vec<String>> {
+  let mut expanded = Vec::new();
+  for index_file in index_file_lst {
+    if !metadata(index_file).await?.is_dir() {
+      expanded.push(index_file.clone());
+      continue;
+    }
+    let mut json_file_lst = Vec::new();
+    let mut entries = read_dir(index_file).await?;
+    while let Some(entry) = entries.next_entry().await? {
+      let path = entry.path();
+      if path.extension().map_or(false, |ext| ext == "json") {
+        json_file_lst.push(path.display().to_string());
+      }
+    }
+    if json_file_lst.is_empty() {
+      return Err(anyhow::anyhow!("no index files found in {index_file}"));
+    }
+    json_file_lst.sort();
+    expanded.extend(json_file_lst);
+  }
+  Ok(expanded)
+}
+
+/// 同じ識別子のエラーをまだ出力していなければ書き出す
+async fn write_error(
+  output_sink: &mut dyn sink::OutputSink,
+  error_keys: &mut HashSet<ErrorKey>,
+  err: YomikaeError,
+) -> Result<()> {
+  if error_keys.insert(err.key()) {
+    output_sink.write_error(&err).await?;
+  };
+  Ok(())
+}
+
+/// 書き出し先に書き出したバイト数を記録したチェックポイントを書き出す
+/// 書き出している途中で止まっても前のチェックポイントが壊れないように、一時ファイルに書いてから置き換える
+async fn write_checkpoint(
+  output_sink: &mut dyn sink::OutputSink,
+  checkpoint_path: &str,
+  next_index: usize,
+  last_num: Option<&String>,
+) -> Result<()> {
+  let offsets = match output_sink.offsets().await? {
+    Some(offsets) => offsets,
+    None => return Ok(()),
+  };
+  let checkpoint = checkpoint::Checkpoint {
+    next_index,
+    last_num: last_num.cloned(),
+    offsets,
+  };
+  let tmp_path = format!("{checkpoint_path}.tmp");
+  write(&tmp_path, serde_json::to_string(&checkpoint)?).await?;
+  rename(&tmp_path, checkpoint_path).await?;
+  Ok(())
+}
+
+/// `-o`と`-e`、または`--combined`で指定した書き出し先を作る
+/// チェックポイントが指定された場合は、記録したバイト数まで切り詰めてその続きに書き出す
+async fn create_output_sink(
+  args: &Args,
+  resume_checkpoint: Option<&checkpoint::Checkpoint>,
+) -> Result<Box<dyn sink::OutputSink>> {
+  let output_sink: Box<dyn sink::OutputSink> = match &args.combined {
+    Some(combined) => Box::new(
+      sink::CombinedSink::create(combined)
+        .await?
+        .with_key_case(args.key_case)
+        .with_flatten(args.flatten),
+    ),
+    None => {
+      let output = required_arg(&args.output, "output")?;
+      let error_output = required_arg(&args.error_output, "error-output")?;
+      match resume_checkpoint {
+        Some(resume_checkpoint) => {
+          sink::resume_sink(
+            args.format,
+            args.key_case,
+            args.flatten,
+            output,
+            error_output,
+            resume_checkpoint.offsets,
+          )
+          .await?
+        }
+        None => {
+          sink::create_sink(
+            args.format,
+            args.key_case,
+            args.flatten,
+            output,
+            error_output,
+          )
+          .await?
+        }
+      }
+    }
+  };
+  Ok(output_sink)
+}
+
+/// `--input-format`で指定した形式のファイルから条文を読み込み、法令XMLの場合と同じように
+/// 読み替え規定文を選んで解析し、結果を書き出す
+async fn analyze_text_input(
+  args: &Args,
+  input_format: text_input::TextInputFormat,
+  options: &pipeline::LawAnalysisOptions,
+) -> Result<()> {
+  let start_time = Instant::now();
+  let mut run_metrics = RunMetrics::default();
+  let mut records = Vec::new();
+  for input in args.input.iter() {
+    let text = read_to_string(input).await?;
+    let record_lst = text_input::parse_text_input(&text, input_format)
+      .map_err(|e| anyhow::anyhow!("{input}: {e}"))?;
+    records.extend(record_lst);
+  }
+  let mut output_sink = create_output_sink(args, None).await?;
+  let mut error_keys = HashSet::new();
+  let mut manifest = RunManifest::default();
+  let mut yomikae_data_lst = Vec::new();
+  for (num, law_text_lst) in text_input::group_by_num(&records) {
+    let law_span = info_span!("law", num = %num);
+    let law_start_time = Instant::now();
+    let law_analysis = law_span.in_scope(|| {
+      pipeline::analyze_law_text_lst(law_text_lst, &pipeline::LawSource::new(&num), options)
+    });
+    for message in law_analysis.select_messages {
+      warn!(parent: &law_span, "{}", message.to_message(args.lang));
+      output_sink.write_warning(&message).await?;
+    }
+    for article in law_analysis.articles {
+      let pair_count = article.pair_count();
+      if let LawContents::Text(s) = &article.law_text.contents {
+        let phrases = options.parser_config.matched_phrases(s);
+        run_metrics.record_phrases(&phrases, pair_count);
+      }
+      if article.is_parsed {
+        run_metrics.record_article(pair_count);
+      }
+      for message in article.messages {
+        warn!(parent: &law_span, "{}", message.to_message(args.lang));
+        output_sink.write_warning(&message).await?;
+      }
+      if let Some(yomikae_data) = article.data {
+        if args.unsorted {
+          output_sink.write_record(&yomikae_data).await?;
+        } else {
+          yomikae_data_lst.push(yomikae_data);
+        }
+      }
+      for err in article.errors {
+        if !matches!(err, YomikaeError::NotFoundYomikae(_)) {
+          error!(parent: &law_span, "{}", err.to_message(args.lang));
+        }
+        run_metrics.record_error(&err);
+        write_error(output_sink.as_mut(), &mut error_keys, err).await?;
+      }
+    }
+    for message in law_analysis.conflict_messages {
+      warn!(parent: &law_span, "{}", message.to_message(args.lang));
+      output_sink.write_warning(&message).await?;
+    }
+    run_metrics.record_law(law_start_time.elapsed());
+    manifest.completed.push(num);
+  }
+  sort_yomikae_data(&mut yomikae_data_lst);
+  for yomikae_data in yomikae_data_lst.iter() {
+    output_sink.write_record(yomikae_data).await?;
+  }
+  output_sink.finish().await?;
+
+  if let Some(manifest_path) = &args.manifest {
+    write(manifest_path, serde_json::to_string(&manifest)?).await?;
+  }
+  run_metrics.finish(start_time.elapsed());
+  if args.quiet {
+    println!("{}", run_metrics.summary(args.lang));
+  }
+  if let Some(metrics_path) = &args.metrics_file {
+    write(metrics_path, serde_json::to_string(&run_metrics)?).await?;
+  }
+  Ok(())
+}
+
+/// `--config`で指定したTOMLファイルから読み替え規定文の候補を見つけるための設定を読み込む
+/// 指定しない場合は既定の設定にする
+async fn load_parser_config(config_path: Option<&str>) -> Result<ParserConfig> {
+  match config_path {
+    Some(config_path) => {
+      let parser_config = toml::from_str(&read_to_string(config_path).await?)
+        .map_err(|e| anyhow::anyhow!("{config_path}: {e}"))?;
+      Ok(parser_config)
+    }
+    None => Ok(ParserConfig::default()),
+  }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-  let args = Args::parse();
+  let mut args = Args::parse();
 
-  init_logger().await?;
+  init_logger(args.otlp_endpoint.as_deref(), args.quiet).await?;
+  if let Some(addr) = args.prometheus_listen {
+    install_prometheus(addr)?;
+  }
 
-  info!("[START] get law data: {:?}", &args.index_file);
-  let law_data_lst = listup_law::get_law_from_index(&args.index_file).await?;
-  info!("[END] get law data: {:?}", &args.index_file);
-  let mut law_data_stream = tokio_stream::iter(law_data_lst);
+  if let Some(command) = args.command.take() {
+    return match command {
+      #[cfg(feature = "server")]
+      Command::Serve(serve_args) => serve(serve_args).await,
+      #[cfg(feature = "tantivy")]
+      Command::Index(index_args) => build_index(index_args).await,
+      #[cfg(feature = "tantivy")]
+      Command::Search(search_args) => search_word(search_args).await,
+      #[cfg(feature = "tui")]
+      Command::Browse(browse_args) => browse_results(browse_args).await,
+      Command::ExportRdf(export_rdf_args) => export_rdf(export_rdf_args).await,
+      Command::Coverage(coverage_args) => report_coverage(coverage_args).await,
+      Command::Repl => repl().await,
+      Command::Lint(lint_args) => lint_draft_file(lint_args).await,
+      Command::ExportSarif(export_sarif_args) => export_sarif(export_sarif_args).await,
+      Command::Daemon(daemon_args) => run_daemon(daemon_args, args.lang).await,
+      Command::Readers(readers_args) => find_readers(readers_args).await,
+      Command::MineConnectives(mine_connectives_args) => {
+        mine_connectives(mine_connectives_args).await
+      }
+    };
+  }
+  let options = pipeline::LawAnalysisOptions {
+    parser_config: load_parser_config(args.config.as_deref()).await?,
+    qualifiers: args.qualifiers,
+    law_refs: args.law_refs,
+    bracket_repairs: args.bracket_repairs,
+  };
+  if let Some(input_format) = args.input_format {
+    return analyze_text_input(&args, input_format, &options).await;
+  }
+  if args.verify_determinism {
+    return verify_determinism(&args, &options).await;
+  }
+  let work = required_arg(&args.work, "work")?;
+  if args.index_file.is_empty() {
+    return Err(anyhow::anyhow!("--index-file is required"));
+  }
 
-  let work_dir_path = Path::new(&args.work);
+  let start_time = Instant::now();
+  let mut run_metrics = RunMetrics::default();
+  let mut law_stats_map: HashMap<String, LawStats> = HashMap::new();
 
-  let mut error_lst = Vec::new();
-  let mut error_output_file = File::create(&args.error_output).await?;
-  info!("[START] write error output file");
-  error_output_file.write_all("[".as_bytes()).await?;
+  let resume_checkpoint: Option<checkpoint::Checkpoint> = match &args.resume_from {
+    Some(resume_path) => Some(serde_json::from_slice(&read(resume_path).await?)?),
+    None => None,
+  };
 
-  let mut output_file = File::create(&args.output).await?;
-  info!("[START] write json file");
-  output_file.write_all("[".as_bytes()).await?;
+  let index_file_lst = expand_index_files(&args.index_file).await?;
+  let mut index_lst = Vec::new();
+  for index_file in index_file_lst.iter() {
+    let law_data_lst = listup_law::get_law_from_index(index_file)
+      .instrument(info_span!("stage", stage = "index", index_file = %index_file))
+      .await?;
+    index_lst.push((index_file.clone(), law_data_lst));
+  }
+  let (mut law_data_lst, index_message_lst) =
+    merge_law_index(index_lst, |law_data| law_data.num.as_str());
+  let promulgation_filter = promulgation::PromulgationFilter {
+    after: args.promulgated_after,
+    before: args.promulgated_before,
+  };
+  if !promulgation_filter.is_empty() {
+    let mut dates = HashMap::new();
+    for index_file in index_file_lst.iter() {
+      let index: serde_json::Value = serde_json::from_slice(&read(index_file).await?)?;
+      promulgation::collect_promulgation_dates(&index, &mut dates);
+    }
+    let law_count = law_data_lst.len();
+    law_data_lst.retain(|law_data| promulgation_filter.contains(dates.get(&law_data.num)));
+    info!(
+      "{} of {law_count} laws match the promulgation date filter",
+      law_data_lst.len()
+    );
+  }
+  let mut law_titles = HashMap::new();
+  if args.include_title {
+    for index_file in index_file_lst.iter() {
+      let index: serde_json::Value = serde_json::from_slice(&read(index_file).await?)?;
+      collect_law_titles(&index, &mut law_titles);
+    }
+  }
+  let start_index = match &resume_checkpoint {
+    Some(resume_checkpoint) => {
+      let nums = law_data_lst
+        .iter()
+        .map(|law_data| law_data.num.clone())
+        .collect::<Vec<_>>();
+      if !resume_checkpoint.matches_index(&nums) {
+        return Err(anyhow::anyhow!(
+          "the index file does not match the checkpoint"
+        ));
+      }
+      resume_checkpoint.next_index
+    }
+    None => 0,
+  };
+  let mut law_data_stream =
+    tokio_stream::iter(law_data_lst.into_iter().enumerate().skip(start_index));
 
-  let mut is_head = true;
-  let mut is_error_head = true;
-  while let Some(law_data) = law_data_stream.next().await {
+  let work_dir_path = Path::new(work);
+
+  let mut error_keys = HashSet::new();
+  let mut output_sink = create_output_sink(&args, resume_checkpoint.as_ref()).await?;
+
+  for message in index_message_lst {
+    warn!("{}", message.to_message(args.lang));
+    output_sink.write_warning(&message).await?;
+  }
+
+  // trueになると、次の法令に進まずに解析を終了する
+  let lang = args.lang;
+  let cancel_rx = cancel_on_interrupt(lang);
+  let mut manifest = RunManifest::default();
+  let mut audit_file = match &args.audit {
+    Some(audit_path) => Some(File::create(audit_path).await?),
+    None => None,
+  };
+  // 組み立て途中の状態を捨てる原因になった文字列の、全ての法令を通した件数
+  let mut reset_tally = audit::ResetTally::default();
+
+  // 並べ替えてから出力するために解析結果をためておく
+  // 同じ文字列が何度も現れるので、共有してメモリを節約する
+  let mut interner = intern::Interner::default();
+  let mut yomikae_data_lst = Vec::new();
+
+  while let Some((law_index, law_data)) = law_data_stream.next().await {
     let num = law_data.num;
+    if *cancel_rx.borrow() {
+      let message = Message::StoppedBefore(num.clone());
+      warn!("{}", message.to_message(lang));
+      output_sink.write_warning(&message).await?;
+      manifest.is_cancelled = true;
+      manifest.stopped_at = Some(num);
+      break;
+    }
     let file_name = law_data.file;
-    let file_path = work_dir_path.join(file_name);
-    info!("[START] work({num:?}): {file_path:?}");
-    let mut f = File::open(&file_path).await?;
-    let mut buf = Vec::new();
-    f.read_to_end(&mut buf).await?;
-    let law_text_lst = xml_to_law_text(&buf).await?;
-    let mut law_text_stream = tokio_stream::iter(law_text_lst);
-    let mut yomikae_law_text_lst = Vec::new();
-    let mut is_yomikae_table = None;
-    while let Some(law_text) = law_text_stream.next().await {
-      match &law_text.contents {
-        LawContents::Text(s) => {
-          if s.contains("と読み替える") {
-            if s.contains("下欄に掲げる字句と読み替える")
-              || s.contains("下欄の字句と読み替える")
-              || s.contains("下欄に掲げる日又は月と読み替える")
-            {
-              is_yomikae_table = Some(law_text.article_info);
-            } else {
-              yomikae_law_text_lst.push(law_text);
-              is_yomikae_table = None;
-            }
+    let law_id = egov::law_id_from_file(&file_name);
+    let file_path = work_dir_path.join(&file_name);
+    let law_span = info_span!("law", num = %num, file = %file_path.display());
+    let law_start_time = Instant::now();
+    let law_stats = law_stats_map.entry(num.clone()).or_default();
+    let law_file_info = LawFileInfo {
+      num: num.clone(),
+      file: file_path.display().to_string(),
+    };
+    let mut warning_lst = Vec::new();
+    let mut law_revision = None;
+    let mut law_title = law_titles.get(&num).cloned();
+    let law_text_lst_res = async {
+      match read(&file_path).await {
+        Ok(buf) => {
+          if args.revision {
+            law_revision = Some(read_law_revision(&file_name, &buf));
           }
-        }
-        LawContents::Table(_) => match &is_yomikae_table {
-          Some(article) if article == &law_text.article_info => {
-            yomikae_law_text_lst.push(law_text);
-            is_yomikae_table = None;
+          let document_type = schema::detect_document_type(&buf);
+          manifest.document_types.insert(num.clone(), document_type);
+          let (law_xml_schema, xml) = schema::normalize_law_xml(&buf);
+          if let LawXmlSchema::Unknown(root) = &law_xml_schema {
+            let message = Message::UnknownLawXmlSchema(law_file_info.clone(), root.clone());
+            warn!("{}", message.to_message(lang));
+            warning_lst.push(message);
+          }
+          manifest.schemas.insert(num.clone(), law_xml_schema);
+          let (xml, ruby_count) = ruby::strip_ruby_readings(&xml);
+          if args.include_title && law_title.is_none() {
+            law_title = schema::law_title(&xml);
           }
-          Some(article) => {
-            warn!("[WARNING] table not found: {:?}", article)
+          if ruby_count > 0 {
+            let message = Message::RubyReadingsRemoved(law_file_info.clone(), ruby_count);
+            info!("{}", message.to_message(lang));
+            manifest
+              .ruby_readings_removed
+              .insert(num.clone(), ruby_count);
           }
-          _ => (),
-        },
-      }
-    }
-    let mut yomikae_law_text_stream = tokio_stream::iter(yomikae_law_text_lst);
-    while let Some(law_text) = yomikae_law_text_stream.next().await {
-      info!("[START] work({num:?}->{:?})", law_text.article_info);
-      let yomikae_info_lst_res =
-        analysis_yomikae::parse_yomikae(&law_text, &num, &law_text.article_info).await;
-      match yomikae_info_lst_res {
-        Ok(yomikae_info_lst) => {
-          if !yomikae_info_lst.is_empty() {
-            let yomikae_data = YomikaeData {
-              num: num.clone(),
-              article: law_text.article_info.clone(),
-              data: yomikae_info_lst,
-            };
-            let yomikae_info_json_str = serde_json::to_string(&yomikae_data)?;
-            if is_head {
-              output_file.write_all("\n".as_bytes()).await?;
-              is_head = false;
-            } else {
-              output_file.write_all(",\n".as_bytes()).await?;
-            };
-            output_file
-              .write_all(yomikae_info_json_str.as_bytes())
-              .await?;
-          } else {
-            let law_info = LawInfo {
-              num: num.to_string(),
-              article: law_text.article_info.clone(),
-              contents: law_text.clone(),
-            };
-            let err = YomikaeError::NotFoundYomikae(law_info);
-            let mut error_stream = tokio_stream::iter(&error_lst);
-            let is_err_exist = error_stream.any(|e| e == &err).await;
-            if !is_err_exist {
-              error_lst.push(err.clone());
-              if is_error_head {
-                error_output_file.write_all("\n".as_bytes()).await?;
-                is_error_head = false;
-              } else {
-                error_output_file.write_all(",\n".as_bytes()).await?;
-              };
-              error_output_file
-                .write_all(serde_json::to_string(&err)?.as_bytes())
-                .await?;
-            };
+          match xml_to_law_text(&xml).await {
+            Ok(law_text_lst) => {
+              let (law_text_lst, labeled_count) = schema::label_unnumbered_articles(law_text_lst);
+              if labeled_count > 0 {
+                let message =
+                  Message::UnnumberedArticlesLabeled(law_file_info.clone(), labeled_count);
+                info!("{}", message.to_message(lang));
+              }
+              Ok(law_text_lst)
+            }
+            Err(e) => Err(YomikaeError::ParseLawXml(law_file_info, e.to_string())),
           }
         }
-        Err(err) => {
-          error!("{err}");
-          let mut error_stream = tokio_stream::iter(&error_lst);
-          let is_err_exist = error_stream.any(|e| e == &err).await;
-          if !is_err_exist {
-            error_lst.push(err.clone());
-            if is_error_head {
-              error_output_file.write_all("\n".as_bytes()).await?;
-              is_error_head = false;
-            } else {
-              error_output_file.write_all(",\n".as_bytes()).await?;
-            };
-            error_output_file
-              .write_all(serde_json::to_string(&err)?.as_bytes())
-              .await?;
-          };
+        Err(e) => Err(YomikaeError::ReadLawFile(law_file_info, e.to_string())),
+      }
+    }
+    .instrument(info_span!(parent: &law_span, "stage", stage = "read"))
+    .await;
+    for message in warning_lst {
+      output_sink.write_warning(&message).await?;
+    }
+    let law_text_lst = match law_text_lst_res {
+      Ok(law_text_lst) => law_text_lst,
+      Err(err) => {
+        error!(parent: &law_span, "{}", err.to_message(lang));
+        run_metrics.record_error(&err);
+        law_stats.add_error();
+        if args.fail_fast {
+          return Err(err.into());
+        }
+        write_error(output_sink.as_mut(), &mut error_keys, err).await?;
+        continue;
+      }
+    };
+    let mut audit_log = audit::AuditLog::new(&num, &law_text_lst, &options.parser_config);
+    let law_source = pipeline::LawSource {
+      num: num.clone(),
+      file: Some(file_name.clone()),
+      law_id: law_id.filter(|_| args.egov_url),
+      revision: law_revision,
+      title: law_title,
+    };
+    let law_analysis = info_span!(parent: &law_span, "stage", stage = "parse")
+      .in_scope(|| pipeline::analyze_law_text_lst(law_text_lst, &law_source, &options));
+    for message in law_analysis.select_messages {
+      warn!(parent: &law_span, "{}", message.to_message(lang));
+      output_sink.write_warning(&message).await?;
+    }
+    let mut article_stream = tokio_stream::iter(law_analysis.articles);
+    while let Some(article) = article_stream.next().await {
+      let article_span = info_span!(
+        parent: &law_span,
+        "stage",
+        stage = "parse",
+        article = %article_to_string(&article.law_text.article_info)
+      );
+      let pair_count = article.pair_count();
+      match (pair_count, article.errors.first()) {
+        (0, Some(err)) => audit_log.record(&article.law_text, Err(err)),
+        (pair_count, _) => audit_log.record(&article.law_text, Ok(pair_count)),
+      }
+      if let LawContents::Text(s) = &article.law_text.contents {
+        let phrases = options.parser_config.matched_phrases(s);
+        run_metrics.record_phrases(&phrases, pair_count);
+      }
+      if article.is_parsed {
+        run_metrics.record_article(pair_count);
+        law_stats.add_pairs(pair_count);
+      }
+      for message in article.messages {
+        warn!(parent: &article_span, "{}", message.to_message(lang));
+        output_sink.write_warning(&message).await?;
+      }
+      if let Some(yomikae_data) = article.data {
+        if args.unsorted {
+          output_sink.write_record(&yomikae_data).await?;
+        } else {
+          yomikae_data_lst.push(interner.intern_yomikae_data(yomikae_data));
         }
       }
-      info!("[END] work({num:?}->{:?})", law_text.article_info);
+      for err in article.errors {
+        if !matches!(err, YomikaeError::NotFoundYomikae(_)) {
+          error!(parent: &article_span, "{}", err.to_message(lang));
+        }
+        run_metrics.record_error(&err);
+        law_stats.add_error();
+        write_error(output_sink.as_mut(), &mut error_keys, err).await?;
+      }
+    }
+    for message in law_analysis.conflict_messages {
+      warn!(parent: &law_span, "{}", message.to_message(lang));
+      output_sink.write_warning(&message).await?;
+    }
+    if let Some(audit_file) = &mut audit_file {
+      let audit_records = audit_log.into_records();
+      reset_tally.add_records(&audit_records);
+      for audit_record in audit_records {
+        let line = format!("{}\n", serde_json::to_string(&audit_record)?);
+        audit_file.write_all(line.as_bytes()).await?;
+      }
+    }
+    run_metrics.record_law(law_start_time.elapsed());
+    manifest.completed.push(num);
+    if let Some(checkpoint_path) = &args.checkpoint {
+      if checkpoint::Checkpoint::is_due(law_index, args.checkpoint_interval) {
+        write_checkpoint(
+          output_sink.as_mut(),
+          checkpoint_path,
+          law_index + 1,
+          manifest.completed.last(),
+        )
+        .await?;
+      }
+    }
+  }
+
+  intern::sort_interned_yomikae_data(&mut yomikae_data_lst);
+  drop(interner);
+  let mut yomikae_data_stream = tokio_stream::iter(yomikae_data_lst);
+  while let Some(yomikae_data) = yomikae_data_stream.next().await {
+    output_sink
+      .write_record(&YomikaeData::from(yomikae_data))
+      .await?;
+  }
+  output_sink.finish().await?;
+  if let Some(audit_file) = &mut audit_file {
+    // 最後の行に、組み立て途中の状態を捨てる原因になった文字列ごとの件数を書く
+    let line = format!("{}\n", serde_json::to_string(&reset_tally)?);
+    audit_file.write_all(line.as_bytes()).await?;
+    audit_file.flush().await?;
+    for (text, count) in reset_tally.most_common().into_iter().take(10) {
+      info!("reset connective {text:?}: {count}");
     }
-    info!("[END] work({num:?}): {file_path:?}");
   }
 
-  output_file.write_all("\n]".as_bytes()).await?;
-  info!("[END] write json file");
-  output_file.flush().await?;
+  if let Some(manifest_path) = &args.manifest {
+    let mut manifest_file = File::create(manifest_path).await?;
+    manifest_file
+      .write_all(serde_json::to_string(&manifest)?.as_bytes())
+      .await?;
+    manifest_file.flush().await?;
+  }
+
+  if let Some(enriched_index_path) = &args.enriched_index {
+    let mut index_lst = Vec::new();
+    for index_file in index_file_lst.iter() {
+      let mut index: serde_json::Value = serde_json::from_slice(&read(index_file).await?)?;
+      enrich_index(&mut index, &law_stats_map);
+      index_lst.push(index);
+    }
+    // インデックス情報が一つの場合は、元と同じ形のまま書き出す
+    let index = if index_lst.len() == 1 {
+      index_lst.remove(0)
+    } else {
+      serde_json::Value::Array(index_lst)
+    };
+    let mut enriched_index_file = File::create(enriched_index_path).await?;
+    enriched_index_file
+      .write_all(serde_json::to_string(&index)?.as_bytes())
+      .await?;
+    enriched_index_file.flush().await?;
+  }
+
+  run_metrics.finish(start_time.elapsed());
+  if args.quiet {
+    println!("{}", run_metrics.summary(args.lang));
+  }
+  if let Some(metrics_path) = &args.metrics_file {
+    let mut metrics_file = File::create(metrics_path).await?;
+    metrics_file
+      .write_all(serde_json::to_string(&run_metrics)?.as_bytes())
+      .await?;
+    metrics_file.flush().await?;
+  }
 
-  error_output_file.write_all("\n]".as_bytes()).await?;
-  info!("[END] write error output file");
-  error_output_file.flush().await?;
+  #[cfg(feature = "otlp")]
+  opentelemetry::global::shutdown_tracer_provider();
 
   Ok(())
 }
@@ -0,0 +1,192 @@
+//! エラーや警告などのメッセージを日本語または英語で表示する
+
+use crate::display::article_to_string;
+use crate::{FormConflict, LawFileInfo, LawInfo, YomikaeError};
+use jplaw_text::Article;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// メッセージの言語
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum Lang {
+  /// 日本語
+  Ja,
+  /// 英語
+  #[default]
+  En,
+}
+
+impl FromStr for Lang {
+  type Err = String;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "ja" => Ok(Lang::Ja),
+      "en" => Ok(Lang::En),
+      _ => Err(format!("unknown language: {s} (expected \"ja\" or \"en\")")),
+    }
+  }
+}
+
+/// 解析中に出力する警告や報告のメッセージ
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Message {
+  /// 表で読み替えると書かれているのに、その条項に表が見つからなかった
+  TableNotFound(Article),
+  /// 割り込みを受け付けたので、今の法令を終えたら止める
+  Interrupted,
+  /// 割り込みによってこの法令の前で止まった
+  StoppedBefore(String),
+  /// 法令XMLのスキーマを判別できなかった（ルート要素の名前）
+  UnknownLawXmlSchema(LawFileInfo, String),
+  /// 解析の前にルビの読みを取り除いた（取り除いた数）
+  RubyReadingsRemoved(LawFileInfo, usize),
+  /// 条番号の無い条文に「本則」と名前を付けた（名前を付けた数）
+  UnnumberedArticlesLabeled(LawFileInfo, usize),
+  /// 取り出した読み替え前後の語の括弧の対応が取れていない（法律番号、条項、語）
+  UnbalancedBrackets(String, Article, String),
+  /// 複数のインデックス情報に同じ法律番号の法令があったので、後のものを使わなかった（法律番号、使ったインデックス情報、使わなかったインデックス情報）
+  DuplicateLaw(String, String, String),
+  /// 同じ法令の文と表とで、同じ語が違うように読み替えられていた
+  FormConflict(FormConflict),
+}
+
+impl Message {
+  pub fn to_message(&self, lang: Lang) -> String {
+    match (self, lang) {
+      (Message::TableNotFound(article), Lang::Ja) => {
+        format!(
+          "[WARNING] 読み替えの表が見つかりません: {}",
+          article_to_string(article)
+        )
+      }
+      (Message::TableNotFound(article), Lang::En) => {
+        format!("[WARNING] table not found: {article:?}")
+      }
+      (Message::Interrupted, Lang::Ja) => {
+        "[INTERRUPT] 解析中の法令が終わり次第停止します".to_string()
+      }
+      (Message::Interrupted, Lang::En) => "[INTERRUPT] finish the current law and stop".to_string(),
+      (Message::StoppedBefore(num), Lang::Ja) => format!("[INTERRUPT] {num:?}の前で停止しました"),
+      (Message::StoppedBefore(num), Lang::En) => format!("[INTERRUPT] stopped before {num:?}"),
+      (Message::UnknownLawXmlSchema(law_file_info, root), Lang::Ja) => format!(
+        "[WARNING] 法令XMLのスキーマを判別できません（ルート要素：{root:?}）: {}",
+        law_file_info_ja(law_file_info)
+      ),
+      (Message::UnknownLawXmlSchema(law_file_info, root), Lang::En) => {
+        format!("[WARNING] unknown law XML schema (root element: {root:?}) at {law_file_info:?}")
+      }
+      (Message::RubyReadingsRemoved(law_file_info, count), Lang::Ja) => format!(
+        "[NOTE] ルビの読みを{count}個取り除きました: {}",
+        law_file_info_ja(law_file_info)
+      ),
+      (Message::RubyReadingsRemoved(law_file_info, count), Lang::En) => {
+        format!("[NOTE] removed {count} ruby readings at {law_file_info:?}")
+      }
+      (Message::UnnumberedArticlesLabeled(law_file_info, count), Lang::Ja) => format!(
+        "[NOTE] 条番号の無い条文{count}個に「本則」と名前を付けました: {}",
+        law_file_info_ja(law_file_info)
+      ),
+      (Message::UnnumberedArticlesLabeled(law_file_info, count), Lang::En) => format!(
+        "[NOTE] labeled {count} unnumbered articles as \"本則\" at {law_file_info:?}"
+      ),
+      (Message::UnbalancedBrackets(num, article, word), Lang::Ja) => format!(
+        "[WARNING] 取り出した語の括弧の対応が取れていません（{word:?}）: {num}（{}）",
+        article_to_string(article)
+      ),
+      (Message::UnbalancedBrackets(num, article, word), Lang::En) => {
+        format!("[WARNING] unbalanced brackets in extracted word {word:?} at {num:?} {article:?}")
+      }
+      (Message::DuplicateLaw(num, kept, skipped), Lang::Ja) => {
+        format!("[WARNING] {num}は{kept}にもあるので、{skipped}のものは使いません")
+      }
+      (Message::DuplicateLaw(num, kept, skipped), Lang::En) => format!(
+        "[WARNING] duplicate law {num:?} in {skipped:?} is skipped (already listed in {kept:?})"
+      ),
+      (Message::FormConflict(conflict), Lang::Ja) => format!(
+        "[WARNING] 「{}」の読み替え後の語が、文（{}）では「{}」、表（{}）では「{}」と食い違っています: {}",
+        conflict.before_word,
+        article_to_string(&conflict.sentence_article),
+        conflict.sentence_after_word,
+        article_to_string(&conflict.table_article),
+        conflict.table_after_word,
+        conflict.num
+      ),
+      (Message::FormConflict(conflict), Lang::En) => format!(
+        "[WARNING] {:?} is read as {:?} in the sentence at {:?} but as {:?} in the table at {:?} in {:?}",
+        conflict.before_word,
+        conflict.sentence_after_word,
+        conflict.sentence_article,
+        conflict.table_after_word,
+        conflict.table_article,
+        conflict.num
+      ),
+    }
+  }
+}
+
+fn law_info_ja(law_info: &LawInfo) -> String {
+  format!(
+    "{}（{}）",
+    law_info.num,
+    article_to_string(&law_info.article)
+  )
+}
+
+fn law_file_info_ja(law_file_info: &LawFileInfo) -> String {
+  format!("{}（{}）", law_file_info.num, law_file_info.file)
+}
+
+impl YomikaeError {
+  /// 指定した言語でエラーメッセージを作る
+  /// 英語の場合は`Display`と同じものになる
+  pub fn to_message(&self, lang: Lang) -> String {
+    match lang {
+      Lang::En => self.to_string(),
+      Lang::Ja => match self {
+        YomikaeError::ContentsOfTable(law_info) => {
+          format!("表の内容を解析できませんでした: {}", law_info_ja(law_info))
+        }
+        YomikaeError::UnmatchedParen(law_info) => {
+          format!("括弧の対応が取れていません: {}", law_info_ja(law_info))
+        }
+        YomikaeError::UnexpectedParallelWords(law_info) => {
+          format!("読み替え前の語の並びが不正です: {}", law_info_ja(law_info))
+        }
+        YomikaeError::UnmatchedSorezoreWords(law_info) => format!(
+          "「それぞれ」で対応させる語の数が合いません: {}",
+          law_info_ja(law_info)
+        ),
+        YomikaeError::NotFoundYomikae(law_info) => {
+          format!(
+            "読み替え規定が見つかりませんでした: {}",
+            law_info_ja(law_info)
+          )
+        }
+        YomikaeError::ReadLawFile(law_file_info, msg) => format!(
+          "法令ファイルを読み込めませんでした: {}: {msg}",
+          law_file_info_ja(law_file_info)
+        ),
+        YomikaeError::ParseLawXml(law_file_info, msg) => format!(
+          "法令XMLを解析できませんでした: {}: {msg}",
+          law_file_info_ja(law_file_info)
+        ),
+        YomikaeError::InternalError(law_info, msg) => {
+          format!("内部エラーが発生しました: {}: {msg}", law_info_ja(law_info))
+        }
+        YomikaeError::AnalyzeLaw(law_file_info, msg) => format!(
+          "法令の解析中に内部エラーが発生しました: {}: {msg}",
+          law_file_info_ja(law_file_info)
+        ),
+      },
+    }
+  }
+}
+
+#[test]
+fn check_lang_from_str() {
+  assert_eq!(Ok(Lang::Ja), "ja".parse());
+  assert_eq!(Ok(Lang::En), "en".parse());
+  assert!("fr".parse::<Lang>().is_err());
+}
@@ -0,0 +1,178 @@
+//! 読み替え規定文の候補から、まだ扱えていない語と語をつなぐ文字列を集める
+//!
+//! 解析はせずに文を鉤括弧で分割するだけで、閉じ鉤括弧の後に続く文字列のうち[`Connective::Other`]に分類されるものを数え、
+//! どの書き方に対応すればより多くの文を解析できるようになるかを調べるために使う
+
+use crate::segment::{split_segments, split_sentences};
+use crate::{classify_connective, Connective, ParserConfig, Segment};
+use jplaw_text::{LawContents, LawText};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 文を鉤括弧で分割し、閉じ鉤括弧の後に続く文字列のうち[`Connective::Other`]に分類されるものを順に返す
+/// 鉤括弧の対応が取れていない文は`None`を返す
+pub fn other_connectives(sentence: &str) -> Option<Vec<String>> {
+  // 鉤括弧の直し方は使わないので、直し方を探すtokenize_yomikae_sentenceは使わない
+  let segments = split_segments(sentence)?;
+  let mut lst = Vec::new();
+  for (i, segment) in segments.iter().enumerate() {
+    if !segment.is_quoted() {
+      continue;
+    }
+    // 閉じ鉤括弧の後に続く文字列と、さらにその後に鉤括弧が続くかどうかから、[`classify_connective`]に渡す文字列を作る
+    let mut rest = String::new();
+    let mut following = segments[i + 1..].iter();
+    if let Some(Segment::Text(text)) = following.clone().next() {
+      rest.push_str(text);
+      following.next();
+    }
+    if following.next().map_or(false, Segment::is_quoted) {
+      rest.push('「');
+    }
+    if let (Connective::Other(text), _) = classify_connective(&rest) {
+      lst.push(text);
+    }
+  }
+  Some(lst)
+}
+
+/// まだ扱えていない文字列一つ分の件数
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ConnectiveFrequency {
+  /// 閉じ鉤括弧の後に続く文字列（次の開き鉤括弧の手前まで）
+  pub text: String,
+  /// 現れた回数
+  pub count: usize,
+  /// その文字列が最初に現れた文
+  pub example: String,
+}
+
+/// 集めた結果
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct MiningReport {
+  /// 調べた読み替え規定文の候補の数
+  pub sentences: usize,
+  /// 鉤括弧の対応が取れていないため分割できなかった文の数
+  pub unmatched_sentences: usize,
+  /// 件数の多い順（件数が同じ場合は文字列の順）に並べた文字列ごとの件数
+  pub connectives: Vec<ConnectiveFrequency>,
+}
+
+/// 読み替え規定文の候補を一つずつ受け取り、まだ扱えていない文字列を数えるもの
+#[derive(Debug, Clone, Default)]
+pub struct ConnectiveMiner {
+  /// 読み替え規定文の候補とする文を決める設定
+  config: ParserConfig,
+  sentences: usize,
+  unmatched_sentences: usize,
+  /// 文字列ごとの件数と、最初に現れた文
+  counts: BTreeMap<String, (usize, String)>,
+}
+
+impl ConnectiveMiner {
+  /// 読み替え規定文の候補とする文を決める設定を指定して作る
+  pub fn with_config(config: ParserConfig) -> Self {
+    ConnectiveMiner {
+      config,
+      ..Default::default()
+    }
+  }
+
+  /// 文を一つ数える（読み替え規定文の候補でない文は数えない）
+  pub fn add_sentence(&mut self, sentence: &str) {
+    if !self.config.is_candidate(sentence) {
+      return;
+    }
+    self.sentences += 1;
+    match other_connectives(sentence) {
+      Some(lst) => {
+        for text in lst {
+          self
+            .counts
+            .entry(text)
+            .or_insert_with(|| (0, sentence.to_string()))
+            .0 += 1;
+        }
+      }
+      None => self.unmatched_sentences += 1,
+    }
+  }
+
+  /// 条文のリストに含まれる文を全て数える
+  pub fn add_law_text_lst(&mut self, law_text_lst: &[LawText]) {
+    for law_text in law_text_lst {
+      if let LawContents::Text(s) = &law_text.contents {
+        for sentence in split_sentences(s) {
+          self.add_sentence(&sentence);
+        }
+      }
+    }
+  }
+
+  /// 集めた結果を取り出す
+  pub fn into_report(self) -> MiningReport {
+    let mut connectives = self
+      .counts
+      .into_iter()
+      .map(|(text, (count, example))| ConnectiveFrequency {
+        text,
+        count,
+        example,
+      })
+      .collect::<Vec<_>>();
+    connectives.sort_by(|f1, f2| f2.count.cmp(&f1.count).then(f1.text.cmp(&f2.text)));
+    MiningReport {
+      sentences: self.sentences,
+      unmatched_sentences: self.unmatched_sentences,
+      connectives,
+    }
+  }
+}
+
+#[test]
+fn check_other_connectives() {
+  assert_eq!(
+    Some(vec!["を".to_string(), "とあり".to_string()]),
+    other_connectives("「前項」を「次項」と、「前条」とあり次条と読み替える。")
+  );
+  assert_eq!(
+    Some(Vec::new()),
+    other_connectives(crate::test_utils::SENTENCE_SINGLE)
+  );
+  assert_eq!(
+    Some(vec![String::new()]),
+    other_connectives("「前項」「次項」と読み替える。")
+  );
+  assert_eq!(
+    None,
+    other_connectives("「前項」とあるのは「次項と読み替える。")
+  );
+}
+
+#[test]
+fn check_connective_miner() {
+  let law_text_lst = vec![
+    crate::test_utils::law_text(
+      "「前項」を「次項」と読み替える。「市長」とあるのは「知事」と読み替える。「市」と定める。",
+    ),
+    crate::test_utils::law_text("「第一項」は「第二項」と、「町長」を「知事」と読み替える。"),
+    crate::test_utils::law_text("「前項」とあるのは「次項」」と読み替える。"),
+  ];
+  let mut miner = ConnectiveMiner::default();
+  miner.add_law_text_lst(&law_text_lst);
+  let report = miner.into_report();
+  assert_eq!(4, report.sentences);
+  assert_eq!(1, report.unmatched_sentences);
+  assert_eq!(
+    vec![("を", 2), ("は", 1)],
+    report
+      .connectives
+      .iter()
+      .map(|f| (f.text.as_str(), f.count))
+      .collect::<Vec<_>>()
+  );
+  assert_eq!(
+    "「前項」を「次項」と読み替える。",
+    report.connectives[0].example
+  );
+}
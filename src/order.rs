@@ -0,0 +1,163 @@
+//! 解析結果を決まった順番に並べる
+
+use crate::YomikaeData;
+use jplaw_text::Article;
+use std::cmp::Ordering;
+
+/// イロハ順で号の細分に使われる文字
+const IROHA: &str =
+  "イロハニホヘトチリヌルヲワカヨタレソツネナラムウヰノオクヤマケフコエテアサキユメミシヱヒモセス";
+
+/// 条・項・号の番号を比較するためのキー
+/// 番号として読めたものは番号の順に、読めなかったものはその後ろに文字列の順に並べる
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum NumKey {
+  Num(Vec<u64>),
+  Str(String),
+}
+
+/// 「〇一二三四五六七八九十百千万」で書かれた漢数字を数値にする
+pub(crate) fn parse_kansuji(s: &str) -> Option<u64> {
+  if s.is_empty() {
+    return None;
+  }
+  let mut total: u64 = 0;
+  let mut section: u64 = 0;
+  let mut num: u64 = 0;
+  for c in s.chars() {
+    let digit = "〇一二三四五六七八九".chars().position(|d| d == c);
+    match (digit, c) {
+      (Some(d), _) => num = num * 10 + d as u64,
+      (None, '十') => {
+        section += num.max(1) * 10;
+        num = 0;
+      }
+      (None, '百') => {
+        section += num.max(1) * 100;
+        num = 0;
+      }
+      (None, '千') => {
+        section += num.max(1) * 1000;
+        num = 0;
+      }
+      (None, '万') => {
+        total += (section + num).max(1) * 10000;
+        section = 0;
+        num = 0;
+      }
+      _ => return None,
+    }
+  }
+  Some(total + section + num)
+}
+
+/// 「第三条の二」「3_2」「２」「イ」「（１）」のような番号の一部分を数値にする
+fn parse_num_part(s: &str) -> Option<u64> {
+  let s = s.trim_start_matches('第');
+  let s = s.trim_end_matches(['条', '項', '号']);
+  let s = s.trim_matches(['（', '）', '(', ')']);
+  if s.is_empty() {
+    return None;
+  }
+  let halfwidth = s
+    .chars()
+    .map(|c| match c {
+      '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+      _ => c,
+    })
+    .collect::<String>();
+  if let Ok(n) = halfwidth.parse::<u64>() {
+    return Some(n);
+  }
+  let mut chars = s.chars();
+  if let (Some(c), None) = (chars.next(), chars.next()) {
+    if let Some(i) = IROHA.chars().position(|r| r == c) {
+      return Some(i as u64 + 1);
+    }
+  }
+  parse_kansuji(s)
+}
+
+fn num_key(s: &str) -> NumKey {
+  s.split(['の', '_'])
+    .map(parse_num_part)
+    .collect::<Option<Vec<_>>>()
+    .map(NumKey::Num)
+    .unwrap_or_else(|| NumKey::Str(s.to_string()))
+}
+
+fn option_num_key(s: &Option<String>) -> Option<NumKey> {
+  s.as_deref().map(num_key)
+}
+
+/// 条項を法令に書かれている順番で比較する
+/// 本則を附則より前にし、その中では条（枝番を含む）・項・号・号の細分の番号の順に並べる
+pub fn cmp_article(a: &Article, b: &Article) -> Ordering {
+  let sub_item_key = |article: &Article| {
+    article
+      .sub_item
+      .iter()
+      .flatten()
+      .map(|s| num_key(s))
+      .collect::<Vec<_>>()
+  };
+  a.suppl_provision_title
+    .cmp(&b.suppl_provision_title)
+    .then_with(|| num_key(&a.article).cmp(&num_key(&b.article)))
+    .then_with(|| option_num_key(&a.paragraph).cmp(&option_num_key(&b.paragraph)))
+    .then_with(|| option_num_key(&a.item).cmp(&option_num_key(&b.item)))
+    .then_with(|| sub_item_key(a).cmp(&sub_item_key(b)))
+}
+
+/// 法律番号、版、条項の順に並べ替える
+/// 同じ法律番号・版・条項のものは元の順番を保ち、読み替え前後の語のリストの順番も変えない
+pub fn sort_yomikae_data(yomikae_data_lst: &mut [YomikaeData]) {
+  yomikae_data_lst.sort_by(|a, b| {
+    a.num
+      .cmp(&b.num)
+      .then_with(|| a.revision.cmp(&b.revision))
+      .then_with(|| cmp_article(&a.article, &b.article))
+  });
+}
+
+#[test]
+fn check_parse_num_part() {
+  assert_eq!(Some(3), parse_num_part("第三条"));
+  assert_eq!(Some(1050), parse_num_part("第千五十条"));
+  assert_eq!(Some(24), parse_num_part("二十四"));
+  assert_eq!(Some(12), parse_num_part("１２"));
+  assert_eq!(Some(2), parse_num_part("ロ"));
+  assert_eq!(Some(1), parse_num_part("（１）"));
+  assert_eq!(None, parse_num_part("附則"));
+}
+
+#[test]
+fn check_cmp_article() {
+  let article = |article: &str, paragraph: Option<&str>, suppl: Option<&str>| Article {
+    article: article.to_string(),
+    paragraph: paragraph.map(|s| s.to_string()),
+    item: None,
+    sub_item: None,
+    suppl_provision_title: suppl.map(|s| s.to_string()),
+  };
+  let mut lst = vec![
+    article("第二条", None, Some("附則")),
+    article("第十条", None, None),
+    article("第三条の二", Some("2"), None),
+    article("第三条", None, None),
+    article("第三条の二", Some("1"), None),
+    article("第九条", None, None),
+  ];
+  lst.sort_by(cmp_article);
+  assert_eq!(
+    vec![
+      article("第三条", None, None),
+      article("第三条の二", Some("1"), None),
+      article("第三条の二", Some("2"), None),
+      article("第九条", None, None),
+      article("第十条", None, None),
+      article("第二条", None, Some("附則")),
+    ],
+    lst
+  );
+}
@@ -0,0 +1,93 @@
+//! 読み替え規定文の候補を見つけるための設定
+//!
+//! 「読み替えて準用する」「適用する場合においては」のような広い文言で候補を探して試せるように、
+//! 候補とする文言を設定で変えられるようにする
+//! 読み替えの組が取り出せなかった文に使う[`SentencePattern`]も設定で与えられる
+//! CLIでは`--config`で指定したTOMLファイルから読み込む
+
+use crate::has_yomikae_phrase;
+use crate::pattern::{apply_patterns, default_patterns, SentencePattern};
+use crate::segment::mask_quoted;
+use crate::YomikaeInfo;
+use serde::{Deserialize, Serialize};
+
+/// 何も設定しない場合に、読み替え規定文の候補とする文言
+pub const DEFAULT_TRIGGER_PHRASES: &[&str] = &["と読み替える", "」に読み替える"];
+
+/// 読み替え規定文の候補を見つけるための設定
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParserConfig {
+  /// 鉤括弧の外側に含まれていれば、読み替え規定文の候補とする文言
+  pub trigger_phrases: Vec<String>,
+  /// 読み替えの組が取り出せなかった文に使うパターン（`None`の場合は組み込みのパターン）
+  pub patterns: Option<Vec<SentencePattern>>,
+}
+
+impl Default for ParserConfig {
+  fn default() -> Self {
+    ParserConfig {
+      trigger_phrases: DEFAULT_TRIGGER_PHRASES
+        .iter()
+        .map(|phrase| phrase.to_string())
+        .collect(),
+      patterns: None,
+    }
+  }
+}
+
+impl ParserConfig {
+  /// 文言の一覧から作る
+  pub fn with_trigger_phrases<I, S>(trigger_phrases: I) -> Self
+  where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+  {
+    ParserConfig {
+      trigger_phrases: trigger_phrases.into_iter().map(Into::into).collect(),
+      ..Default::default()
+    }
+  }
+
+  /// 候補とする文言のうち、鉤括弧の外側に含まれているものを設定の順に返す
+  pub fn matched_phrases(&self, s: &str) -> Vec<&str> {
+    let masked = mask_quoted(s);
+    self
+      .trigger_phrases
+      .iter()
+      .filter(|phrase| masked.contains(phrase.as_str()))
+      .map(|phrase| phrase.as_str())
+      .collect()
+  }
+
+  /// 読み替え規定文の候補かどうか
+  /// 候補とする文言を含まなくても、表で読み替えると書かれていれば候補とする
+  pub fn is_candidate(&self, s: &str) -> bool {
+    has_yomikae_phrase(s, &self.trigger_phrases)
+  }
+
+  /// パターンで文から読み替えの組を取り出す
+  pub fn apply_patterns(&self, sentence: &str) -> Vec<YomikaeInfo> {
+    match &self.patterns {
+      Some(patterns) => apply_patterns(patterns, sentence),
+      None => apply_patterns(&default_patterns(), sentence),
+    }
+  }
+}
+
+#[test]
+fn check_parser_config() {
+  let config = ParserConfig::default();
+  assert!(config.is_candidate("「前項」とあるのは「次項」と読み替えるものとする。"));
+  assert!(!config.is_candidate("前条の規定は、前項の場合について読み替えて準用する。"));
+  let config = ParserConfig::with_trigger_phrases(["と読み替える", "読み替えて準用する"]);
+  assert_eq!(
+    vec!["読み替えて準用する"],
+    config.matched_phrases("前条の規定は、前項の場合について読み替えて準用する。")
+  );
+  assert!(config
+    .matched_phrases("第五条中「読み替えて準用する」を削る。")
+    .is_empty());
+  let config: ParserConfig = serde_json::from_str("{}").unwrap();
+  assert_eq!(ParserConfig::default(), config);
+}
@@ -0,0 +1,477 @@
+//! 読み替え規定文を一文字ずつ読み進めるときの状態と、その遷移
+//!
+//! [`parse_yomikae`](crate::parse_yomikae)は、鉤括弧の外側か内側かの状態（[`ParserState`]）と、
+//! 読み替え前後の語の組み立ての状態（[`PairState`]）を、開き鉤括弧・閉じ鉤括弧・それ以外の文字ごとに遷移させる
+//! 新しい文の形を扱うときに、遷移を一つずつ書き換えて確かめられるように、遷移ごとに関数を分けている
+
+use crate::{
+  classify_connective, parse_target_ref, Connective, LawInfo, ScopedWord, TargetRef, Transition,
+  YomikaeConfidence, YomikaeError, YomikaeInfo,
+};
+
+/// 鉤括弧の外側と内側のどちらを読んでいるか
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ParserState {
+  /// 鉤括弧の外側
+  Outside {
+    /// 文の先頭または直前の閉じ鉤括弧から後の、鉤括弧の外側の文字列
+    text: String,
+  },
+  /// 鉤括弧の内側
+  InQuote {
+    /// 鉤括弧の開きの深さ（一番外側の鉤括弧の中では1）
+    depth: usize,
+    /// 一番外側の鉤括弧の中にある文字
+    word: String,
+  },
+}
+
+impl Default for ParserState {
+  fn default() -> Self {
+    ParserState::Outside {
+      text: String::new(),
+    }
+  }
+}
+
+/// 読み替え規定文を読み進める途中の状態
+#[derive(Debug, Clone, Default)]
+pub(crate) struct YomikaeParser {
+  /// 鉤括弧の外側と内側のどちらを読んでいるか
+  state: ParserState,
+  /// 読み替え前後の語の組み立ての状態
+  pair: PairState,
+  /// 取り出した読み替えの組
+  yomikae_info_lst: Vec<YomikaeInfo>,
+  /// 組み立て途中の状態を捨てる原因になった、閉じ鉤括弧の後の文字列
+  resets: Vec<String>,
+}
+
+impl YomikaeParser {
+  /// 文字列を最後まで読む
+  pub(crate) fn read(&mut self, s: &str) -> Result<(), fn(LawInfo) -> YomikaeError> {
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+      let rest = chars.as_str();
+      let len = self.step(c, rest)?;
+      // 接続詞などを読み飛ばし、その続きから読む
+      chars = rest[len..].chars();
+    }
+    Ok(())
+  }
+
+  /// 一文字読み、その後に続く文字列`rest`のうち読み飛ばす長さを返す
+  pub(crate) fn step(&mut self, c: char, rest: &str) -> Result<usize, fn(LawInfo) -> YomikaeError> {
+    match c {
+      '「' => {
+        self.open_quote();
+        Ok(0)
+      }
+      '」' => self.close_quote(rest),
+      _ => {
+        self.push_char(c);
+        Ok(0)
+      }
+    }
+  }
+
+  /// 開き鉤括弧を読む
+  /// 一番外側の鉤括弧が開く場合は、それまでの鉤括弧の外側の文字列から「それぞれ」や対象の規定を読み取る
+  fn open_quote(&mut self) {
+    match &mut self.state {
+      ParserState::InQuote { depth, word } => {
+        // 鉤括弧内の鉤括弧であるので、鉤括弧も登場単語として登録する
+        word.push('「');
+        *depth += 1;
+      }
+      ParserState::Outside { text } => {
+        self.pair.read_outside_text(text);
+        self.state = ParserState::InQuote {
+          depth: 1,
+          word: String::new(),
+        };
+      }
+    }
+  }
+
+  /// 閉じ鉤括弧を読み、その後に続く文字列`rest`のうち読み飛ばす長さを返す
+  /// 一番外側の鉤括弧が閉じる場合は、後に続く接続詞などに応じて読み替え前後の語の組み立てを進め、接続詞などを読み飛ばす
+  fn close_quote(&mut self, rest: &str) -> Result<usize, fn(LawInfo) -> YomikaeError> {
+    match &mut self.state {
+      ParserState::Outside { .. } => Err(YomikaeError::UnmatchedParen),
+      ParserState::InQuote { depth, word } if *depth > 1 => {
+        // 鉤括弧内に出てきた閉じ鉤括弧
+        word.push('」');
+        *depth -= 1;
+        Ok(0)
+      }
+      ParserState::InQuote { word, .. } => {
+        let word = std::mem::take(word);
+        let (connective, len) = classify_connective(rest);
+        self.state = ParserState::default();
+        self.close_word(word, &connective)?;
+        Ok(len)
+      }
+    }
+  }
+
+  /// 一番外側の鉤括弧の中の語を、後に続く接続詞などに応じて読み替え前後の語にする
+  fn close_word(
+    &mut self,
+    word: String,
+    connective: &Connective,
+  ) -> Result<(), fn(LawInfo) -> YomikaeError> {
+    match connective.transition() {
+      Transition::Listing => {
+        self.pair.push_listed_word(word);
+        Ok(())
+      }
+      Transition::BeforeWord => self
+        .pair
+        .push_before_word(&mut self.yomikae_info_lst, word, false),
+      Transition::LastBeforeWord => {
+        self
+          .pair
+          .push_before_word(&mut self.yomikae_info_lst, word, true)
+      }
+      Transition::AfterWord => {
+        if *connective == Connective::ToQuote {
+          // 終了処理をしてすぐに開始する
+          self.state = ParserState::InQuote {
+            depth: 1,
+            word: String::new(),
+          };
+        }
+        self.pair.push_after_word(&mut self.yomikae_info_lst, word)
+      }
+      Transition::Reset => {
+        if let Connective::Other(text) = connective {
+          self.resets.push(text.clone());
+        }
+        // 読み替え後の語が決まったときと同じように、読み替えの対象は同じ文の続きの組にも引き継ぐ
+        let target = self.pair.target.take();
+        self.pair = PairState {
+          word_scope: target.clone(),
+          target,
+          ..Default::default()
+        };
+        Ok(())
+      }
+    }
+  }
+
+  /// 鉤括弧以外の文字を読む
+  fn push_char(&mut self, c: char) {
+    match &mut self.state {
+      ParserState::Outside { text } => text.push(c),
+      ParserState::InQuote { word, .. } => word.push(c),
+    }
+  }
+
+  /// 読み終えて、取り出した読み替えの組を返す
+  pub(crate) fn finish(self) -> Vec<YomikaeInfo> {
+    self.yomikae_info_lst
+  }
+}
+
+/// 読み替え規定文を読み進める途中で、想定していない接続詞などが閉じ鉤括弧の後に来て組み立て途中の状態を捨てたときの、その文字列を順に返す
+/// 文字列は次の開き鉤括弧の手前まで（「とあり」の後に「、」や接続詞が続かない場合は「とあり」）で、
+/// 鉤括弧の対応が取れていないなどで読み進められなくなった場合は、それまでのものを返す
+pub fn reset_connectives(sentence: &str) -> Vec<String> {
+  let mut parser = YomikaeParser::default();
+  let _ = parser.read(sentence);
+  parser.resets
+}
+
+/// 読み替え規定文を読み進める途中の、読み替え前後の語の組み立ての状態
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PairState {
+  /// 「とあり」「とある」が付いた読み替え前の語
+  before_words: Vec<String>,
+  /// `before_words`のそれぞれの語の対象となる規定
+  before_scopes: Vec<Option<TargetRef>>,
+  /// 「及び」「又は」などで並べられていて、読み替え前後のどちらの語になるかがまだ決まっていない語
+  listed_words: Vec<String>,
+  /// `listed_words`のそれぞれの語の対象となる規定
+  listed_scopes: Vec<Option<TargetRef>>,
+  /// 「とある」が来て、読み替え前の語が出そろったかどうか
+  is_before_words_end: bool,
+  /// 「それぞれ」で、読み替え前の語と読み替え後の語を順に対応させるかどうか
+  is_sorezore: bool,
+  /// 読み替えの対象となる規定
+  target: Option<TargetRef>,
+  /// 今読んでいる語の対象となる規定
+  word_scope: Option<TargetRef>,
+}
+
+impl PairState {
+  /// 一番外側の鉤括弧が開く前に、その直前の鉤括弧の外側の文字列から「それぞれ」や対象の規定を読み取る
+  fn read_outside_text(&mut self, text: &str) {
+    if self.is_before_words_end && text.contains("それぞれ") {
+      self.is_sorezore = true;
+    }
+    if self.before_words.is_empty() && self.listed_words.is_empty() {
+      // 「同条第一項中」のような対象の規定が書かれていなければ、同じ文の前の組の対象を引き継ぐ
+      if let Some(target) = parse_target_ref(text) {
+        self.target = Some(target);
+      } else if text.contains('。') {
+        self.target = None;
+      }
+      self.word_scope = self.target.clone();
+    } else if !self.is_before_words_end {
+      // 「とあり、及び同項第二号中「」のように、読み替え前の語ごとに対象の規定が書かれていることがある
+      if let Some(scope) = parse_target_ref(text) {
+        self.word_scope = Some(scope);
+      }
+    }
+  }
+
+  /// 「及び」「又は」などで並べられた語を加える
+  fn push_listed_word(&mut self, word: String) {
+    self.listed_words.push(word);
+    self.listed_scopes.push(self.word_scope.clone());
+  }
+
+  /// 「とあり」「とある」が付いた語を読み替え前の語に加える
+  /// 「〜」とあるのは「〜」、「〜」とあるのは のように前の組が「、」で終わっていた場合は、先にその組を`yomikae_info_lst`に加える
+  fn push_before_word(
+    &mut self,
+    yomikae_info_lst: &mut Vec<YomikaeInfo>,
+    word: String,
+    is_end: bool,
+  ) -> Result<(), fn(LawInfo) -> YomikaeError> {
+    if self.is_before_words_end {
+      if let Some(after_word) = self.listed_words.pop() {
+        let word_scope = self.word_scope.clone();
+        self.push_after_word(yomikae_info_lst, after_word)?;
+        self.word_scope = word_scope;
+      } else if !is_end {
+        // 読み替え前の語が出そろった後に、さらに「とあり」で読み替え前の語が並べられている
+        return Err(YomikaeError::UnexpectedParallelWords);
+      }
+    }
+    self.before_words.append(&mut self.listed_words);
+    self.before_scopes.append(&mut self.listed_scopes);
+    self.before_words.push(word);
+    self.before_scopes.push(self.word_scope.clone());
+    self.is_before_words_end = is_end;
+    Ok(())
+  }
+
+  /// 読み替え後の語が決まったときに、読み替えの組を作って`yomikae_info_lst`に加え、読み替えの対象以外の状態を初期化する
+  /// 「それぞれ」がある場合は、並べられた読み替え前の語と読み替え後の語を順に組にする
+  /// 「それぞれ」がないのに読み替え後の語が並べられている場合は、最後の語を読み替え後の語にして確からしさを下げる
+  fn push_after_word(
+    &mut self,
+    yomikae_info_lst: &mut Vec<YomikaeInfo>,
+    after_word: String,
+  ) -> Result<(), fn(LawInfo) -> YomikaeError> {
+    let PairState {
+      before_words,
+      before_scopes,
+      listed_words: mut after_words,
+      is_sorezore,
+      target,
+      ..
+    } = std::mem::take(self);
+    self.target = target.clone();
+    self.word_scope = target.clone();
+    after_words.push(after_word);
+    if before_words.is_empty() {
+      return Ok(());
+    }
+    if is_sorezore && (before_words.len() > 1 || after_words.len() > 1) {
+      if before_words.len() != after_words.len() {
+        return Err(YomikaeError::UnmatchedSorezoreWords);
+      }
+      // 読み替え前の語が一つずつの組になるので、それぞれの語の対象をその組の対象にする
+      for ((before_word, after_word), scope) in
+        before_words.into_iter().zip(after_words).zip(before_scopes)
+      {
+        yomikae_info_lst.push(YomikaeInfo {
+          target: scope,
+          ..YomikaeInfo::new([before_word], after_word)
+        });
+      }
+    } else {
+      let is_listed = after_words.len() > 1;
+      let after_word = after_words.pop().unwrap_or_default();
+      let scoped_before_words = if before_scopes.iter().any(|scope| *scope != target) {
+        before_words
+          .iter()
+          .zip(before_scopes)
+          .map(|(word, scope)| ScopedWord {
+            scope,
+            word: word.clone(),
+          })
+          .collect()
+      } else {
+        Vec::new()
+      };
+      let yomikae_info = YomikaeInfo {
+        target,
+        scoped_before_words,
+        ..YomikaeInfo::new(before_words, after_word)
+      };
+      yomikae_info_lst.push(if is_listed {
+        YomikaeInfo {
+          confidence: YomikaeConfidence::Low,
+          ..yomikae_info
+        }
+      } else {
+        yomikae_info
+      });
+    }
+    Ok(())
+  }
+}
+
+#[test]
+fn check_open_quote() {
+  let mut parser = YomikaeParser::default();
+  parser.push_char('中');
+  assert_eq!(
+    ParserState::Outside {
+      text: "中".to_string()
+    },
+    parser.state
+  );
+  parser.open_quote();
+  parser.push_char('前');
+  parser.open_quote();
+  assert_eq!(
+    ParserState::InQuote {
+      depth: 2,
+      word: "前「".to_string()
+    },
+    parser.state
+  );
+}
+
+#[test]
+fn check_close_quote() {
+  let law_info = LawInfo::new(
+    "test",
+    crate::test_utils::test_article(),
+    crate::test_utils::law_text("」"),
+  );
+  let mut parser = YomikaeParser::default();
+  let err = parser.close_quote("").unwrap_err();
+  assert!(matches!(err(law_info), YomikaeError::UnmatchedParen(_)));
+  let mut parser = YomikaeParser::default();
+  parser.read("「「前項」").unwrap();
+  assert_eq!(
+    ParserState::InQuote {
+      depth: 1,
+      word: "「前項」".to_string()
+    },
+    parser.state
+  );
+  parser.read("前段").unwrap();
+  assert_eq!(Ok("とある".len()), parser.close_quote("とあるのは「"));
+  assert_eq!(ParserState::default(), parser.state);
+  assert_eq!(vec!["「前項」前段".to_string()], parser.pair.before_words);
+  assert!(parser.pair.is_before_words_end);
+}
+
+#[test]
+fn check_close_word() {
+  let mut parser = YomikaeParser::default();
+  parser
+    .close_word("前項".to_string(), &Connective::Listing)
+    .unwrap();
+  assert_eq!(vec!["前項".to_string()], parser.pair.listed_words);
+  parser
+    .close_word("前条".to_string(), &Connective::ToAri)
+    .unwrap();
+  assert_eq!(
+    vec!["前項".to_string(), "前条".to_string()],
+    parser.pair.before_words
+  );
+  assert!(!parser.pair.is_before_words_end);
+  parser
+    .close_word("次条".to_string(), &Connective::ToAru)
+    .unwrap();
+  assert!(parser.pair.is_before_words_end);
+  parser
+    .close_word("次項".to_string(), &Connective::ToQuote)
+    .unwrap();
+  assert_eq!(
+    vec![YomikaeInfo::new(["前項", "前条", "次条"], "次項")],
+    parser.yomikae_info_lst
+  );
+  assert_eq!(
+    ParserState::InQuote {
+      depth: 1,
+      word: String::new()
+    },
+    parser.state
+  );
+  parser
+    .close_word("市長".to_string(), &Connective::ToAru)
+    .unwrap();
+  let err = parser
+    .close_word("知事".to_string(), &Connective::ToAri)
+    .unwrap_err();
+  let law_info = LawInfo::new(
+    "test",
+    crate::test_utils::test_article(),
+    crate::test_utils::law_text(""),
+  );
+  assert!(matches!(
+    err(law_info),
+    YomikaeError::UnexpectedParallelWords(_)
+  ));
+  let target = Some(TargetRef::provision("第三条第一項"));
+  parser.pair.target = target.clone();
+  parser
+    .close_word("条".to_string(), &Connective::Other("を".to_string()))
+    .unwrap();
+  assert!(parser.pair.before_words.is_empty());
+  assert!(!parser.pair.is_before_words_end);
+  // 読み替えの対象は引き継ぐ
+  assert_eq!(target, parser.pair.target);
+  assert_eq!(target, parser.pair.word_scope);
+}
+
+#[test]
+fn check_read_outside_text() {
+  let mut pair = PairState::default();
+  pair.read_outside_text("この場合において、第三条第一項中");
+  assert!(pair.target.is_some());
+  assert_eq!(pair.target, pair.word_scope);
+  pair.read_outside_text("。");
+  assert_eq!(None, pair.target);
+  let mut pair = PairState {
+    before_words: vec!["前項".to_string()],
+    is_before_words_end: true,
+    ..Default::default()
+  };
+  pair.read_outside_text("とあるのはそれぞれ");
+  assert!(pair.is_sorezore);
+}
+
+#[test]
+fn check_finish() {
+  let mut parser = YomikaeParser::default();
+  parser
+    .read("第三条中「前項」とあるのは「次項」と、「市長」とあるのは「知事」と読み替える。")
+    .unwrap();
+  let yomikae_info_lst = parser.finish();
+  assert_eq!(2, yomikae_info_lst.len());
+  assert_eq!(vec!["市長".to_string()], yomikae_info_lst[1].before_words);
+  assert_eq!("知事", yomikae_info_lst[1].after_word);
+}
+
+#[test]
+fn check_reset_connectives() {
+  assert_eq!(
+    vec!["を".to_string(), "とあり".to_string()],
+    reset_connectives("「前項」を「次項」と、「前条」とあり次条と読み替える。")
+  );
+  assert!(reset_connectives(crate::test_utils::SENTENCE_SINGLE).is_empty());
+  assert_eq!(
+    vec!["を削り、".to_string(), "と」読み替える。".to_string()],
+    reset_connectives("「前項」を削り、「次項」とあるのは「前項」と」読み替える。")
+  );
+}
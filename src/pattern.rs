@@ -0,0 +1,221 @@
+//! 文の形を宣言的に書いたパターンで、読み替え前後の語を取り出す
+//!
+//! [`parse_yomikae`](crate::parse_yomikae)の文字ごとの状態遷移で扱えない形の文を、コードを書き換えずに扱えるようにする
+//! パターンは鉤括弧で囲まれた語・決まった文字列・いずれか一つに合う並びを順に並べたもので、
+//! 組み込みのもの（`src/patterns.json`）のほか、`--config`で指定したTOMLファイルの`patterns`で与えられる
+//! 状態遷移で読み替えの組が一つも取り出せなかった文にだけ使う
+
+use crate::YomikaeInfo;
+use serde::{Deserialize, Serialize};
+
+/// 組み込みのパターン
+const DEFAULT_PATTERNS_JSON: &str = include_str!("patterns.json");
+
+/// 鉤括弧で囲まれた語の役割
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotedRole {
+  /// 読み替え前の語
+  Before,
+  /// 読み替え後の語
+  After,
+  /// 取り出さない
+  Ignore,
+}
+
+/// パターンを組み立てる要素
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PatternElement {
+  /// 鉤括弧で囲まれた語（中に鉤括弧があってもよい）
+  Quoted { role: QuotedRole },
+  /// 決まった文字列
+  Literal { text: String },
+  /// 並びのうち、いずれか一つに合うもの
+  /// 先に書いた並びから順に試し、空の並びを含めるとその要素は省略できる
+  Alt { options: Vec<Vec<PatternElement>> },
+}
+
+/// 読み替えの組一つ分の文の形
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SentencePattern {
+  /// パターンの名前
+  pub name: String,
+  /// 順に合わせる要素
+  pub elements: Vec<PatternElement>,
+}
+
+/// パターンに合った部分から取り出した語
+#[derive(Debug, Clone, Default)]
+struct Captures {
+  before_words: Vec<String>,
+  after_words: Vec<String>,
+}
+
+/// 組み込みのパターン
+pub fn default_patterns() -> Vec<SentencePattern> {
+  serde_json::from_str(DEFAULT_PATTERNS_JSON).expect("default patterns")
+}
+
+/// 先頭の鉤括弧と対応する閉じ鉤括弧までの長さと、その中身を返す
+fn quoted_len(s: &str) -> Option<(usize, &str)> {
+  let rest = s.strip_prefix('「')?;
+  let mut depth = 1;
+  for (i, c) in rest.char_indices() {
+    match c {
+      '「' => depth += 1,
+      '」' => {
+        depth -= 1;
+        if depth == 0 {
+          return Some(('「'.len_utf8() + i + '」'.len_utf8(), &rest[..i]));
+        }
+      }
+      _ => (),
+    }
+  }
+  None
+}
+
+/// 要素の並びを文字列の先頭から合わせ、合った長さと取り出した語を返す
+fn match_elements(
+  elements: &[PatternElement],
+  s: &str,
+  captures: Captures,
+) -> Option<(usize, Captures)> {
+  let (element, rest) = match elements.split_first() {
+    Some(split) => split,
+    None => return Some((0, captures)),
+  };
+  match element {
+    PatternElement::Quoted { role } => {
+      let (len, word) = quoted_len(s)?;
+      let mut captures = captures;
+      match role {
+        QuotedRole::Before => captures.before_words.push(word.to_string()),
+        QuotedRole::After => captures.after_words.push(word.to_string()),
+        QuotedRole::Ignore => (),
+      }
+      let (rest_len, captures) = match_elements(rest, &s[len..], captures)?;
+      Some((len + rest_len, captures))
+    }
+    PatternElement::Literal { text } => {
+      let s = s.strip_prefix(text.as_str())?;
+      let (rest_len, captures) = match_elements(rest, s, captures)?;
+      Some((text.len() + rest_len, captures))
+    }
+    PatternElement::Alt { options } => options.iter().find_map(|option| {
+      let mut seq = option.clone();
+      seq.extend_from_slice(rest);
+      match_elements(&seq, s, captures.clone())
+    }),
+  }
+}
+
+impl SentencePattern {
+  /// 文字列の先頭からパターンに合わせ、合った長さと読み替えの組を返す
+  /// 読み替え前の語が無いか、読み替え後の語がちょうど一つでない場合は合わなかったものとする
+  fn match_at(&self, s: &str) -> Option<(usize, YomikaeInfo)> {
+    let (len, captures) = match_elements(&self.elements, s, Captures::default())?;
+    match (
+      captures.before_words.is_empty(),
+      captures.after_words.as_slice(),
+    ) {
+      (false, [after_word]) => Some((len, YomikaeInfo::new(captures.before_words, after_word))),
+      _ => None,
+    }
+  }
+}
+
+/// 文の中でパターンに合う部分を前から順に探し、読み替えの組を取り出す
+/// 同じ位置で複数のパターンが合う場合は先に書いたものを使い、パターンに合わない鉤括弧の中は探さない
+pub fn apply_patterns(patterns: &[SentencePattern], sentence: &str) -> Vec<YomikaeInfo> {
+  let mut yomikae_info_lst = Vec::new();
+  let mut i = 0;
+  while let Some(c) = sentence[i..].chars().next() {
+    let rest = &sentence[i..];
+    if let Some((len, yomikae_info)) = patterns.iter().find_map(|pattern| pattern.match_at(rest)) {
+      yomikae_info_lst.push(yomikae_info);
+      i += len.max(c.len_utf8());
+      continue;
+    }
+    i += match quoted_len(rest) {
+      Some((len, _)) => len,
+      None => c.len_utf8(),
+    };
+  }
+  yomikae_info_lst
+}
+
+#[test]
+fn check_apply_patterns() {
+  let patterns = default_patterns();
+  assert_eq!(
+    vec![YomikaeInfo::new(["前項"], "次項")],
+    apply_patterns(
+      &patterns,
+      "この場合において、同条中「前項」は「次項」と読み替えるものとする。"
+    )
+  );
+  assert_eq!(
+    vec![
+      YomikaeInfo::new(["前項"], "次項"),
+      YomikaeInfo::new(["市長"], "知事"),
+    ],
+    apply_patterns(
+      &patterns,
+      "この場合において、同条中「前項」を「次項」と、「市長」を、「知事」と読み替えるものとする。"
+    )
+  );
+  assert!(apply_patterns(&patterns, "「「前項」は「次項」と」を削る。").is_empty());
+  let patterns = vec![SentencePattern {
+    name: "toiunowa".to_string(),
+    elements: vec![
+      PatternElement::Quoted {
+        role: QuotedRole::Before,
+      },
+      PatternElement::Alt {
+        options: vec![
+          vec![PatternElement::Literal {
+            text: "及び".to_string(),
+          }],
+          vec![],
+        ],
+      },
+      PatternElement::Alt {
+        options: vec![
+          vec![PatternElement::Quoted {
+            role: QuotedRole::Before,
+          }],
+          vec![],
+        ],
+      },
+      PatternElement::Literal {
+        text: "というのは".to_string(),
+      },
+      PatternElement::Quoted {
+        role: QuotedRole::After,
+      },
+    ],
+  }];
+  assert_eq!(
+    vec![YomikaeInfo::new(["前項", "前条"], "次項")],
+    apply_patterns(
+      &patterns,
+      "「前項」及び「前条」というのは「次項」と読み替える。"
+    )
+  );
+  assert!(apply_patterns(&patterns, "「前項」というのは、次項と読み替える。").is_empty());
+  let law_text = crate::test_utils::law_text("同条中「前項」は「次項」と読み替えるものとする。");
+  assert_eq!(
+    vec![Ok(vec![YomikaeInfo::new(["前項"], "次項")])],
+    crate::parse_yomikae_sentences(&law_text, "test")
+  );
+  let config = crate::ParserConfig {
+    patterns: Some(Vec::new()),
+    ..Default::default()
+  };
+  assert!(matches!(
+    crate::parse_yomikae_sentences_with_config(&law_text, "test", &config)[0],
+    Err(crate::YomikaeError::NotFoundYomikae(_))
+  ));
+}
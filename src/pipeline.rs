@@ -0,0 +1,272 @@
+//! 一つの法令の条文のリストから読み替え規定文と表を選んで解析し、解析結果とエラーを組み立てる
+//!
+//! CLIと`batch`モジュールの解析は、どれもこのモジュールの[`analyze_law_text_lst`]を使うので、
+//! 設定や解析結果に付ける情報は入口によらず同じになる
+//! 条文の解析中にpanicが起きた場合は、その条文の[`YomikaeError::InternalError`]にして解析を続ける
+
+use crate::{
+  form_conflict_messages, law_ref, parse_yomikae_sentences_with_config, qualifier, repair,
+  select_yomikae_law_text_with_config, validate, LawInfo, LawRevision, Message, ParserConfig,
+  YomikaeData, YomikaeError, YomikaeInfo,
+};
+use jplaw_text::LawText;
+use std::any::Any;
+
+/// 解析のしかたの設定
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct LawAnalysisOptions {
+  /// 読み替え規定文の候補を見つけるための設定
+  pub parser_config: ParserConfig,
+  /// 読み替え後の語から修飾句を取り出して含めるかどうか（[`qualifier::attach_qualifier_refs`]）
+  pub qualifiers: bool,
+  /// 読み替え後の語から他の法令の引用を取り出して含めるかどうか（[`law_ref::attach_law_refs`]）
+  pub law_refs: bool,
+  /// 鉤括弧の対応が取れていない文のエラーに直し方の候補を含めるかどうか（[`repair::attach_bracket_repairs`]）
+  pub bracket_repairs: bool,
+}
+
+/// 解析結果とエラーに付ける、解析する法令の情報
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LawSource {
+  /// 法律番号
+  pub num: String,
+  /// 元の法令XMLファイル（`None`の場合は付けない）
+  pub file: Option<String>,
+  /// e-Gov法令検索のURLを作るための法令ID（`None`の場合はURLを付けない）
+  pub law_id: Option<String>,
+  /// 法令の版の情報
+  pub revision: Option<LawRevision>,
+  /// 法令名
+  pub title: Option<String>,
+}
+
+impl LawSource {
+  /// 法律番号だけから作る
+  pub fn new(num: impl Into<String>) -> Self {
+    LawSource {
+      num: num.into(),
+      ..Default::default()
+    }
+  }
+
+  /// 条項の解析結果に、元の条文のハッシュ値とこの法令の情報を付ける
+  fn yomikae_data(&self, law_text: &LawText, yomikae_info_lst: Vec<YomikaeInfo>) -> YomikaeData {
+    let mut yomikae_data = YomikaeData::new(
+      self.num.clone(),
+      law_text.article_info.clone(),
+      yomikae_info_lst,
+    )
+    .with_content_hash(law_text);
+    if let Some(file) = &self.file {
+      yomikae_data = yomikae_data.with_source(file);
+    }
+    if let Some(law_id) = &self.law_id {
+      yomikae_data = yomikae_data.with_egov_url(law_id);
+    }
+    if let Some(revision) = &self.revision {
+      yomikae_data = yomikae_data.with_revision(revision.clone());
+    }
+    if let Some(title) = &self.title {
+      yomikae_data = yomikae_data.with_title(title);
+    }
+    yomikae_data
+  }
+
+  /// 条文についてのエラーに、この法令の情報を付ける
+  fn error(&self, err: YomikaeError) -> YomikaeError {
+    let err = match &self.file {
+      Some(file) => err.with_source(file),
+      None => err,
+    };
+    match &self.title {
+      Some(title) => err.with_title(title),
+      None => err,
+    }
+  }
+}
+
+/// 一つの読み替え規定文または表を解析した結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticleAnalysis {
+  /// 解析した条文
+  pub law_text: LawText,
+  /// 解析できたかどうか（読み替えの組が取り出せなかっただけの文は、解析はできたものとする）
+  pub is_parsed: bool,
+  /// 読み替えの組が一つ以上取り出せた場合の解析結果
+  pub data: Option<YomikaeData>,
+  /// 解析に失敗した文や、読み替えの組が取り出せなかった文のエラー
+  pub errors: Vec<YomikaeError>,
+  /// 取り出した語の括弧の対応が取れていないなどの警告
+  pub messages: Vec<Message>,
+}
+
+impl ArticleAnalysis {
+  /// 取り出した読み替えの組の数
+  pub fn pair_count(&self) -> usize {
+    self
+      .data
+      .as_ref()
+      .map_or(0, |yomikae_data| yomikae_data.data.len())
+  }
+}
+
+/// 一つの法令を解析した結果
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LawAnalysis {
+  /// 読み替え規定文と表を選ぶときの警告（表で読み替えると書かれているのに表が見つからないなど）
+  pub select_messages: Vec<Message>,
+  /// 読み替え規定文と表ごとの結果（条文の順）
+  pub articles: Vec<ArticleAnalysis>,
+  /// 文と表とで食い違う読み替えの警告
+  pub conflict_messages: Vec<Message>,
+}
+
+/// panicしたときに渡された値から、エラーに含めるメッセージを取り出す
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+  if let Some(s) = payload.downcast_ref::<&str>() {
+    s.to_string()
+  } else if let Some(s) = payload.downcast_ref::<String>() {
+    s.clone()
+  } else {
+    "unknown panic".to_string()
+  }
+}
+
+/// 条文の解析中にpanicが起きても解析全体を止めずに、エラーとして扱う
+fn parse_catch_panic(
+  law_text: &LawText,
+  num: &str,
+  config: &ParserConfig,
+) -> Vec<Result<Vec<YomikaeInfo>, YomikaeError>> {
+  let res = std::panic::catch_unwind(|| parse_yomikae_sentences_with_config(law_text, num, config));
+  match res {
+    Ok(res_lst) => res_lst,
+    Err(payload) => {
+      let law_info = LawInfo::new(num, law_text.article_info.clone(), law_text.clone());
+      vec![Err(YomikaeError::InternalError(
+        law_info,
+        panic_message(payload.as_ref()),
+      ))]
+    }
+  }
+}
+
+/// 読み替え規定文または表を一つ解析し、`options`に従って解析結果を補い、`source`の情報を付ける
+pub fn analyze_article(
+  law_text: LawText,
+  source: &LawSource,
+  options: &LawAnalysisOptions,
+) -> ArticleAnalysis {
+  let res_lst = parse_catch_panic(&law_text, &source.num, &options.parser_config);
+  let is_parsed = res_lst
+    .iter()
+    .any(|res| matches!(res, Ok(_) | Err(YomikaeError::NotFoundYomikae(_))));
+  let mut yomikae_info_lst = Vec::new();
+  let mut errors = Vec::new();
+  for res in res_lst {
+    match res {
+      Ok(lst) => yomikae_info_lst.extend(lst),
+      Err(err) => {
+        let err = source.error(err);
+        if options.bracket_repairs {
+          errors.push(repair::attach_bracket_repairs(err));
+        } else {
+          errors.push(err);
+        }
+      }
+    }
+  }
+  if options.qualifiers {
+    yomikae_info_lst = yomikae_info_lst
+      .into_iter()
+      .map(qualifier::attach_qualifier_refs)
+      .collect();
+  }
+  if options.law_refs {
+    yomikae_info_lst = yomikae_info_lst
+      .into_iter()
+      .map(law_ref::attach_law_refs)
+      .collect();
+  }
+  let messages =
+    validate::unbalanced_messages(&source.num, &law_text.article_info, &yomikae_info_lst);
+  let data = if yomikae_info_lst.is_empty() {
+    None
+  } else {
+    Some(source.yomikae_data(&law_text, yomikae_info_lst))
+  };
+  ArticleAnalysis {
+    law_text,
+    is_parsed,
+    data,
+    errors,
+    messages,
+  }
+}
+
+/// 法令の条文のリストから読み替え規定文と読み替えの表を取り出して、全て解析する
+pub fn analyze_law_text_lst(
+  law_text_lst: Vec<LawText>,
+  source: &LawSource,
+  options: &LawAnalysisOptions,
+) -> LawAnalysis {
+  let (yomikae_law_text_lst, select_messages) =
+    select_yomikae_law_text_with_config(law_text_lst, &options.parser_config);
+  let articles = yomikae_law_text_lst
+    .into_iter()
+    .map(|law_text| analyze_article(law_text, source, options))
+    .collect::<Vec<_>>();
+  let yomikae_data_lst = articles
+    .iter()
+    .filter_map(|article| article.data.clone())
+    .collect::<Vec<_>>();
+  LawAnalysis {
+    select_messages,
+    articles,
+    conflict_messages: form_conflict_messages(&yomikae_data_lst),
+  }
+}
+
+#[test]
+fn check_analyze_law_text_lst() {
+  use crate::test_utils;
+  let law_text_lst = vec![
+    test_utils::law_text("前条の規定を準用する。"),
+    test_utils::law_text(test_utils::SENTENCE_SINGLE),
+    test_utils::law_text("前条中「前項」とあるのは第二項」と読み替える。"),
+  ];
+  let source = LawSource {
+    file: Some("test.xml".to_string()),
+    title: Some("テスト法".to_string()),
+    ..LawSource::new("test")
+  };
+  let law_analysis = analyze_law_text_lst(law_text_lst, &source, &LawAnalysisOptions::default());
+  assert_eq!(2, law_analysis.articles.len());
+  let yomikae_data = law_analysis.articles[0].data.as_ref().unwrap();
+  assert!(yomikae_data.content_hash.is_some());
+  assert_eq!(Some("test.xml"), yomikae_data.source_file.as_deref());
+  assert_eq!(Some("テスト法"), yomikae_data.title.as_deref());
+  assert!(law_analysis.articles[1].data.is_none());
+  match &law_analysis.articles[1].errors[..] {
+    [YomikaeError::UnmatchedParen(law_info)] => {
+      assert_eq!(Some("test.xml"), law_info.source_file.as_deref());
+      assert!(law_info.bracket_repairs.is_empty());
+    }
+    errors => panic!("unexpected errors: {errors:?}"),
+  }
+  let options = LawAnalysisOptions {
+    bracket_repairs: true,
+    ..Default::default()
+  };
+  let law_analysis = analyze_law_text_lst(
+    vec![test_utils::law_text(
+      "前条中「前項」とあるのは第二項」と読み替える。",
+    )],
+    &LawSource::new("test"),
+    &options,
+  );
+  assert!(matches!(
+    &law_analysis.articles[0].errors[..],
+    [YomikaeError::UnmatchedParen(law_info)] if !law_info.bracket_repairs.is_empty()
+  ));
+}
@@ -0,0 +1,15 @@
+//! よく使う型や関数をまとめて`use`するためのモジュール
+//!
+//! ```
+//! use analysis_yomikae::prelude::*;
+//!
+//! let info = YomikaeInfo::new(["前項"], "第二項");
+//! let data = YomikaeData::new("test", Article::article("第三条"), vec![info]);
+//! ```
+
+pub use crate::kaiseibun::{parse_kaiseibun, KaiseiData, KaiseiInfo};
+pub use crate::{
+  parse_yomikae, parse_yomikae_source, ArticleExt, Lang, LawInfo, LawNum, SourceError, YomikaeData,
+  YomikaeError, YomikaeInfo, YomikaeOperation, YomikaeSource,
+};
+pub use jplaw_text::{Article, LawContents, LawText};
@@ -0,0 +1,162 @@
+//! 法令のインデックス情報に書かれた公布日で、解析する法令を絞り込む
+//!
+//! インデックス情報の各法令の`date`は、`"1947-04-16"`のような文字列か、
+//! `{"era": "Showa", "year": 22, "month": 4, "day": 16}`のような元号と年月日のオブジェクトとして読む
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// 公布日（西暦）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PromulgationDate {
+  pub year: u32,
+  pub month: u32,
+  pub day: u32,
+}
+
+impl FromStr for PromulgationDate {
+  type Err = String;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let err = || format!("invalid date: {s} (expected \"YYYY-MM-DD\")");
+    let parts = s
+      .split('-')
+      .map(|part| part.parse::<u32>().map_err(|_| err()))
+      .collect::<Result<Vec<_>, _>>()?;
+    match parts[..] {
+      [year, month, day] if (1..=12).contains(&month) && (1..=31).contains(&day) => {
+        Ok(PromulgationDate { year, month, day })
+      }
+      _ => Err(err()),
+    }
+  }
+}
+
+impl fmt::Display for PromulgationDate {
+  /// 「1947-04-16」のような形で表示する
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+  }
+}
+
+/// 元号の元年の前の年（西暦）
+pub(crate) fn era_offset(era: &str) -> Option<u32> {
+  match era {
+    "Meiji" => Some(1867),
+    "Taisho" => Some(1911),
+    "Showa" => Some(1925),
+    "Heisei" => Some(1988),
+    "Reiwa" => Some(2018),
+    _ => None,
+  }
+}
+
+/// インデックス情報の一つの法令の`date`から公布日を読む
+/// 月や日が書かれていない場合は1月・1日とする
+pub fn promulgation_date(law: &Value) -> Option<PromulgationDate> {
+  match law.get("date")? {
+    Value::String(s) => s.parse().ok(),
+    Value::Object(date) => {
+      let get_u32 = |key: &str| date.get(key).and_then(|v| v.as_u64()).map(|n| n as u32);
+      let year = get_u32("year")?;
+      let year = match date.get("era").and_then(|era| era.as_str()) {
+        Some(era) => era_offset(era)? + year,
+        None => year,
+      };
+      Some(PromulgationDate {
+        year,
+        month: get_u32("month").unwrap_or(1),
+        day: get_u32("day").unwrap_or(1),
+      })
+    }
+    _ => None,
+  }
+}
+
+/// インデックス情報のJSONの中で`num`を持つオブジェクトを探し、法律番号と公布日の対応を`dates`に加える
+/// すでに同じ法律番号があれば、先に加えたものを残す
+pub fn collect_promulgation_dates(index: &Value, dates: &mut HashMap<String, PromulgationDate>) {
+  match index {
+    Value::Array(lst) => {
+      for v in lst.iter() {
+        collect_promulgation_dates(v, dates);
+      }
+    }
+    Value::Object(map) => match map.get("num").and_then(|num| num.as_str()) {
+      Some(num) => {
+        if let Some(date) = promulgation_date(index) {
+          dates.entry(num.to_string()).or_insert(date);
+        }
+      }
+      None => {
+        for v in map.values() {
+          collect_promulgation_dates(v, dates);
+        }
+      }
+    },
+    _ => (),
+  }
+}
+
+/// 公布日による絞り込みの条件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PromulgationFilter {
+  /// この日以降に公布されたものに絞り込む
+  pub after: Option<PromulgationDate>,
+  /// この日より前に公布されたものに絞り込む
+  pub before: Option<PromulgationDate>,
+}
+
+impl PromulgationFilter {
+  /// 条件が一つも指定されていないかどうか
+  pub fn is_empty(&self) -> bool {
+    self.after.is_none() && self.before.is_none()
+  }
+
+  /// 公布日が条件に合うかどうか
+  /// 条件が指定されている場合、公布日が分からない法令は条件に合わないものとする
+  pub fn contains(&self, date: Option<&PromulgationDate>) -> bool {
+    if self.is_empty() {
+      return true;
+    }
+    match date {
+      Some(date) => {
+        self.after.map_or(true, |after| after <= *date)
+          && self.before.map_or(true, |before| *date < before)
+      }
+      None => false,
+    }
+  }
+}
+
+#[test]
+fn check_promulgation_filter() {
+  use serde_json::json;
+  let index = json!([
+    {"num": "a", "file": "a.xml", "date": {"era": "Showa", "year": 22, "month": 4, "day": 16}},
+    {"num": "b", "file": "b.xml", "date": "1989-01-08"},
+    {"num": "c", "file": "c.xml"},
+  ]);
+  let mut dates = HashMap::new();
+  collect_promulgation_dates(&index, &mut dates);
+  let showa: PromulgationDate = "1947-04-16".parse().unwrap();
+  assert_eq!(Some(&showa), dates.get("a"));
+  assert_eq!(2, dates.len());
+  let filter = PromulgationFilter {
+    after: Some("1989-01-08".parse().unwrap()),
+    before: None,
+  };
+  assert!(!filter.contains(dates.get("a")));
+  assert!(filter.contains(dates.get("b")));
+  assert!(!filter.contains(dates.get("c")));
+  let filter = PromulgationFilter {
+    after: None,
+    before: Some("1989-01-08".parse().unwrap()),
+  };
+  assert!(filter.contains(dates.get("a")));
+  assert!(!filter.contains(dates.get("b")));
+  assert!(PromulgationFilter::default().contains(None));
+  assert!("1989-13-01".parse::<PromulgationDate>().is_err());
+  assert!("平成元年".parse::<PromulgationDate>().is_err());
+}
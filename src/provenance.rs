@@ -0,0 +1,81 @@
+//! 解析結果やエラーが、どの法令XMLファイルのどの要素から来たのかを記録する
+
+use crate::{LawInfo, YomikaeData, YomikaeError};
+use jplaw_text::Article;
+
+/// 条項の、法令XMLの中での要素のパス
+/// 「MainProvision/Article[12]/Paragraph[2]」のように、要素名と`Num`属性の値を並べる
+/// 附則の場合は「SupplProvision[附則の題名]」から始まる
+pub fn article_xml_path(article: &Article) -> String {
+  let mut path = match &article.suppl_provision_title {
+    Some(title) => vec![format!("SupplProvision[{title}]")],
+    None => vec!["MainProvision".to_string()],
+  };
+  if !article.article.is_empty() {
+    path.push(format!("Article[{}]", article.article));
+  }
+  if let Some(paragraph) = &article.paragraph {
+    path.push(format!("Paragraph[{paragraph}]"));
+  }
+  if let Some(item) = &article.item {
+    path.push(format!("Item[{item}]"));
+  }
+  for (i, sub_item) in article.sub_item.iter().flatten().enumerate() {
+    path.push(format!("Subitem{}[{sub_item}]", i + 1));
+  }
+  path.join("/")
+}
+
+impl YomikaeData {
+  /// 元の法令XMLファイルと、その中での条項の要素のパスを記録する
+  pub fn with_source(self, source_file: impl Into<String>) -> Self {
+    YomikaeData {
+      source_file: Some(source_file.into()),
+      xml_path: Some(article_xml_path(&self.article)),
+      ..self
+    }
+  }
+}
+
+impl LawInfo {
+  /// 元の法令XMLファイルと、その中での条項の要素のパスを記録する
+  pub fn with_source(self, source_file: impl Into<String>) -> Self {
+    LawInfo {
+      source_file: Some(source_file.into()),
+      xml_path: Some(article_xml_path(&self.article)),
+      ..self
+    }
+  }
+}
+
+impl YomikaeError {
+  /// 条文についてのエラーであれば、元の法令XMLファイルと要素のパスを記録する
+  /// 法令ファイルについてのエラーは既にファイルを持っているので、そのまま返す
+  pub fn with_source(self, source_file: impl Into<String>) -> Self {
+    self.map_law_info(|law_info| law_info.with_source(source_file))
+  }
+}
+
+#[test]
+fn check_article_xml_path() {
+  use crate::ArticleExt;
+  assert_eq!(
+    "MainProvision/Article[12]/Paragraph[2]",
+    article_xml_path(&Article::article("12").with_paragraph("2"))
+  );
+  assert_eq!(
+    "SupplProvision[附則]/Article[3]/Paragraph[1]/Item[2]",
+    article_xml_path(
+      &Article::article("3")
+        .with_paragraph("1")
+        .with_item("2")
+        .in_suppl_provision("附則")
+    )
+  );
+  let yomikae_data = YomikaeData::new("test", Article::article("12"), vec![]).with_source("a.xml");
+  assert_eq!(Some("a.xml".to_string()), yomikae_data.source_file);
+  assert_eq!(
+    Some("MainProvision/Article[12]".to_string()),
+    yomikae_data.xml_path
+  );
+}
@@ -0,0 +1,132 @@
+//! Pythonから読み替え規定文を解析したり、解析結果を扱ったりするためのPyO3のAPI
+//!
+//! `python` featureを有効にすると使えるようになる
+//! wheelは[maturin](https://www.maturin.rs/)で`maturin build --release`のようにして作る
+//!
+//! 構造体はJSONと同じ形の`dict`や`list`として受け渡しする
+
+use crate::{
+  dedupe_yomikae, kaiseibun::parse_kaiseibun, parse_yomikae, ArticleExt, LawStats, YomikaeData,
+  YomikaeError,
+};
+use jplaw_text::{Article, LawContents, LawText};
+use pyo3::{create_exception, exceptions::PyException, exceptions::PyValueError, prelude::*};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+
+create_exception!(
+  analysis_yomikae,
+  ParseError,
+  PyException,
+  "読み替え規定文や改め文の解析に失敗した"
+);
+
+/// Rustの値をJSONを経由してPythonの値にする
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+  let s = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+  Ok(py.import("json")?.call_method1("loads", (s,))?.into())
+}
+
+/// Pythonの値をJSONを経由してRustの値にする
+fn from_py<T: DeserializeOwned>(py: Python<'_>, value: &PyAny) -> PyResult<T> {
+  let s: String = py
+    .import("json")?
+    .call_method1("dumps", (value,))?
+    .extract()?;
+  serde_json::from_str(&s).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+fn parse_error(err: YomikaeError) -> PyErr {
+  ParseError::new_err(err.to_string())
+}
+
+fn law_text(py: Python<'_>, text: String, article: Option<&PyAny>) -> PyResult<LawText> {
+  let article = match article {
+    Some(article) => from_py(py, article)?,
+    None => Article::article(""),
+  };
+  Ok(LawText {
+    article_info: article,
+    contents: LawContents::Text(text),
+  })
+}
+
+/// 読み替え規定文を解析して、読み替え前後の語の組の`list`を返す
+#[pyfunction(name = "parse_yomikae")]
+#[pyo3(signature = (text, num = String::new(), article = None))]
+fn py_parse_yomikae(
+  py: Python<'_>,
+  text: String,
+  num: String,
+  article: Option<&PyAny>,
+) -> PyResult<PyObject> {
+  let law_text = law_text(py, text, article)?;
+  let yomikae_info_lst =
+    parse_yomikae(&law_text, &num, &law_text.article_info).map_err(parse_error)?;
+  to_py(py, &yomikae_info_lst)
+}
+
+/// 改め文を解析して、改正の内容の`list`を返す
+#[pyfunction(name = "parse_kaiseibun")]
+#[pyo3(signature = (text, num = String::new(), article = None))]
+fn py_parse_kaiseibun(
+  py: Python<'_>,
+  text: String,
+  num: String,
+  article: Option<&PyAny>,
+) -> PyResult<PyObject> {
+  let law_text = law_text(py, text, article)?;
+  let kaisei_info_lst =
+    parse_kaiseibun(&law_text, &num, &law_text.article_info).map_err(parse_error)?;
+  to_py(py, &kaisei_info_lst)
+}
+
+/// CLIが出力した解析結果のJSONファイルを読み込む
+#[pyfunction]
+fn load_dataset(py: Python<'_>, path: &str) -> PyResult<PyObject> {
+  let buf = std::fs::read(path)?;
+  let yomikae_data_lst: Vec<YomikaeData> =
+    serde_json::from_slice(&buf).map_err(|e| PyValueError::new_err(e.to_string()))?;
+  to_py(py, &yomikae_data_lst)
+}
+
+/// 同じ法令の中で全く同じ読み替えをまとめ、まとめた後の解析結果と重複の報告の組を返す
+#[pyfunction]
+fn dedupe(py: Python<'_>, dataset: &PyAny) -> PyResult<(PyObject, PyObject)> {
+  let yomikae_data_lst: Vec<YomikaeData> = from_py(py, dataset)?;
+  let (deduped_lst, report) = dedupe_yomikae(yomikae_data_lst);
+  Ok((to_py(py, &deduped_lst)?, to_py(py, &report)?))
+}
+
+/// 解析結果（とエラー）から、法律番号ごとの読み替えの組の数とエラーの数の`dict`を返す
+#[pyfunction]
+#[pyo3(signature = (dataset, errors = None))]
+fn law_stats(py: Python<'_>, dataset: &PyAny, errors: Option<&PyAny>) -> PyResult<PyObject> {
+  let yomikae_data_lst: Vec<YomikaeData> = from_py(py, dataset)?;
+  let error_lst: Vec<YomikaeError> = match errors {
+    Some(errors) => from_py(py, errors)?,
+    None => Vec::new(),
+  };
+  let mut stats: HashMap<String, LawStats> = HashMap::new();
+  for yomikae_data in yomikae_data_lst.iter() {
+    stats
+      .entry(yomikae_data.num.to_string())
+      .or_default()
+      .add_pairs(yomikae_data.data.len());
+  }
+  for err in error_lst.iter() {
+    stats.entry(err.key().law).or_default().add_error();
+  }
+  to_py(py, &stats)
+}
+
+#[pymodule]
+fn analysis_yomikae(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+  m.add("ParseError", py.get_type::<ParseError>())?;
+  m.add_function(wrap_pyfunction!(py_parse_yomikae, m)?)?;
+  m.add_function(wrap_pyfunction!(py_parse_kaiseibun, m)?)?;
+  m.add_function(wrap_pyfunction!(load_dataset, m)?)?;
+  m.add_function(wrap_pyfunction!(dedupe, m)?)?;
+  m.add_function(wrap_pyfunction!(law_stats, m)?)?;
+  Ok(())
+}
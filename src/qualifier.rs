@@ -0,0 +1,188 @@
+//! 読み替え後の語に含まれる、経過措置などの規定を参照する修飾句を取り出す
+//!
+//! 「附則第十一条の規定によりなおその効力を有するものとされた旧法第二条」のように、
+//! 読み替え後の語の中で別の規定によって効力が残されたものを指していることがある
+
+use crate::YomikaeInfo;
+use serde::{Deserialize, Serialize};
+
+/// 修飾句の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum QualifierKind {
+  /// 「なおその効力を有する」
+  StillEffective,
+  /// 「なお従前の例による」
+  PriorExample,
+}
+
+/// 修飾句が参照している規定
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct QualifierRef {
+  /// 参照している法令の名前（「旧法」「同法」など。法令の名前が書かれていない場合は`None`）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub law: Option<String>,
+  /// 参照している条項（「附則第十一条」など）
+  pub article: String,
+  /// 修飾句の種類
+  pub kind: QualifierKind,
+}
+
+/// 「〜の規定により」の後に続く文言と、その修飾句の種類
+const QUALIFIER_PHRASES: &[(&str, QualifierKind)] = &[
+  ("なおその効力を有する", QualifierKind::StillEffective),
+  ("なお効力を有する", QualifierKind::StillEffective),
+  ("なお従前の例による", QualifierKind::PriorExample),
+];
+
+/// 条項を表す文字列に使われる文字
+pub(crate) const ARTICLE_CHARS: &str = "附則第条項号の〇一二三四五六七八九十百千";
+
+/// 法令の名前の末尾
+pub(crate) const LAW_NAME_SUFFIXES: &[&str] = &["法", "法律", "令", "規則", "条例"];
+
+/// 法令の名前の手前にある区切りの語
+const LAW_NAME_BOUNDARIES: &[&str] = &[
+  "、",
+  "。",
+  "「",
+  "」",
+  "（",
+  "）",
+  "とされた",
+  "された",
+  "による",
+  "に係る",
+  "における",
+  "及び",
+  "並びに",
+  "又は",
+  "若しくは",
+];
+
+/// 語の中の「〜の規定によりなおその効力を有する」「〜の規定によりなお従前の例による」を取り出す
+pub fn extract_qualifier_refs(word: &str) -> Vec<QualifierRef> {
+  let mut refs = Vec::new();
+  for (i, pat) in word.match_indices("の規定により") {
+    let rest = &word[i + pat.len()..];
+    let kind = match QUALIFIER_PHRASES
+      .iter()
+      .find(|(phrase, _)| rest.starts_with(phrase))
+    {
+      Some((_, kind)) => *kind,
+      None => continue,
+    };
+    let head = &word[..i];
+    let article_start = head
+      .char_indices()
+      .rev()
+      .take_while(|(_, c)| ARTICLE_CHARS.contains(*c))
+      .last()
+      .map(|(j, _)| j)
+      .unwrap_or(head.len());
+    // 「の」などから始まっている場合は、「附則」か「第」から始まるようにする
+    let article_start = match head[article_start..].find(|c| c == '附' || c == '第') {
+      Some(j) => article_start + j,
+      None => continue,
+    };
+    let article = &head[article_start..];
+    if !article.contains('条') && article != "附則" {
+      continue;
+    }
+    refs.push(QualifierRef {
+      law: law_name(&head[..article_start]),
+      article: article.to_string(),
+      kind,
+    });
+  }
+  refs
+}
+
+/// 条項の手前の文字列の末尾から法令の名前を取り出す
+/// 「旧法（昭和三十四年法律第百四十一号）」のように後ろに丸括弧が付いている場合は、丸括弧を除いた部分を見る
+pub(crate) fn law_name(head: &str) -> Option<String> {
+  let head = strip_trailing_paren(head);
+  let start = LAW_NAME_BOUNDARIES
+    .iter()
+    .filter_map(|boundary| head.rfind(boundary).map(|j| j + boundary.len()))
+    .max()
+    .unwrap_or(0);
+  let name = &head[start..];
+  if !name.is_empty()
+    && LAW_NAME_SUFFIXES
+      .iter()
+      .any(|suffix| name.ends_with(suffix))
+  {
+    Some(name.to_string())
+  } else {
+    None
+  }
+}
+
+/// 末尾にある対応の取れた丸括弧の部分を取り除く
+pub(crate) fn strip_trailing_paren(s: &str) -> &str {
+  if !s.ends_with('）') {
+    return s;
+  }
+  let mut depth: usize = 0;
+  for (i, c) in s.char_indices().rev() {
+    match c {
+      '）' => depth += 1,
+      '（' => {
+        depth -= 1;
+        if depth == 0 {
+          return &s[..i];
+        }
+      }
+      _ => (),
+    }
+  }
+  s
+}
+
+/// 読み替え後の語から修飾句を取り出して[`YomikaeInfo::qualifiers`]に入れる
+pub fn attach_qualifier_refs(yomikae_info: YomikaeInfo) -> YomikaeInfo {
+  let qualifiers = extract_qualifier_refs(&yomikae_info.after_word);
+  YomikaeInfo {
+    qualifiers,
+    ..yomikae_info
+  }
+}
+
+#[test]
+fn check_extract_qualifier_refs() {
+  assert_eq!(
+    vec![QualifierRef {
+      law: None,
+      article: "附則第十一条".to_string(),
+      kind: QualifierKind::StillEffective,
+    }],
+    extract_qualifier_refs(
+      "附則第十一条の規定によりなおその効力を有するものとされた旧法第二条第一項"
+    )
+  );
+  assert_eq!(
+    vec![QualifierRef {
+      law: Some("旧国民年金法".to_string()),
+      article: "附則第三条第二項".to_string(),
+      kind: QualifierKind::PriorExample,
+    }],
+    extract_qualifier_refs(
+      "旧国民年金法（昭和三十四年法律第百四十一号）附則第三条第二項の規定によりなお従前の例によることとされた年金"
+    )
+  );
+  assert_eq!(
+    vec![QualifierRef {
+      law: Some("同法".to_string()),
+      article: "第五条の二".to_string(),
+      kind: QualifierKind::StillEffective,
+    }],
+    extract_qualifier_refs("同法第五条の二の規定によりなお効力を有する処分")
+  );
+  assert!(extract_qualifier_refs("第五条の規定により指定された者").is_empty());
+  assert!(extract_qualifier_refs("市長").is_empty());
+  let yomikae_info = attach_qualifier_refs(YomikaeInfo::new(
+    ["前条"],
+    "附則第十一条の規定によりなおその効力を有する前条",
+  ));
+  assert_eq!(1, yomikae_info.qualifiers.len());
+}
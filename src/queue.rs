@@ -0,0 +1,168 @@
+//! 解析を待つ法令XMLファイルを置くディレクトリを見張り、置かれたファイルを一つずつ解析する
+//!
+//! `async` featureを有効にすると使えるようになる
+//!
+//! キューのディレクトリに置かれた`<ジョブ名>.xml`を解析し、結果を出力先のディレクトリの`<ジョブ名>.json`に書き出す
+//! 解析し終えたファイルはキューのディレクトリの中の`done`ディレクトリに移す
+//! 書き込み途中のファイルを読まないように、ファイルは「.」で始まる名前で書き込んでから名前を変えて置くこと
+
+use crate::batch::{analyze_law, AnalyzeConfig};
+use crate::schema::law_num;
+use crate::{LawFileInfo, YomikaeData, YomikaeError};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 解析し終えたファイルを移すディレクトリの名前
+pub const DONE_DIR_NAME: &str = "done";
+
+/// 一つのジョブの解析結果
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct JobResult {
+  /// ジョブ名（法令XMLファイルの拡張子を除いた名前）
+  pub job: String,
+  /// 法律番号
+  pub num: String,
+  /// 読み替えの組が一つ以上取り出せた条項の解析結果
+  pub results: Vec<YomikaeData>,
+  /// 解析に失敗した条文のエラー
+  pub errors: Vec<YomikaeError>,
+}
+
+/// 見張るキューのディレクトリと、結果の出力先
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JobQueue {
+  /// 法令XMLファイルが置かれるディレクトリ
+  pub queue_dir: PathBuf,
+  /// 解析結果を書き出すディレクトリ
+  pub output_dir: PathBuf,
+  /// 法令XMLファイル名と法律番号の対応
+  /// 対応が無いファイルは法令XMLに書かれた法令番号を、それも無い場合はジョブ名を法律番号とする
+  pub nums: HashMap<String, String>,
+}
+
+impl JobQueue {
+  pub fn new(queue_dir: impl Into<PathBuf>, output_dir: impl Into<PathBuf>) -> Self {
+    JobQueue {
+      queue_dir: queue_dir.into(),
+      output_dir: output_dir.into(),
+      nums: HashMap::new(),
+    }
+  }
+
+  /// 法令XMLファイル名と法律番号の対応を設定する
+  pub fn with_nums(self, nums: HashMap<String, String>) -> Self {
+    JobQueue { nums, ..self }
+  }
+
+  /// 解析を待っている法令XMLファイルの名前をファイル名の順に返す
+  pub async fn pending_jobs(&self) -> io::Result<Vec<String>> {
+    let mut jobs = Vec::new();
+    let mut entries = tokio::fs::read_dir(&self.queue_dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+      let file_name = entry.file_name().to_string_lossy().to_string();
+      if !file_name.starts_with('.')
+        && Path::new(&file_name)
+          .extension()
+          .map_or(false, |ext| ext == "xml")
+        && entry.file_type().await?.is_file()
+      {
+        jobs.push(file_name);
+      }
+    }
+    jobs.sort();
+    Ok(jobs)
+  }
+
+  /// キューのディレクトリにある一つの法令XMLファイルを解析する
+  pub async fn run_job(&self, file_name: &str, config: &AnalyzeConfig) -> JobResult {
+    let job = Path::new(file_name)
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let num = match self.nums.get(file_name) {
+      Some(num) => num.clone(),
+      None => tokio::fs::read(self.queue_dir.join(file_name))
+        .await
+        .ok()
+        .and_then(|xml| law_num(&xml))
+        .unwrap_or_else(|| job.clone()),
+    };
+    let law_file_info = LawFileInfo {
+      num: num.clone(),
+      file: file_name.to_string(),
+    };
+    let config = AnalyzeConfig {
+      work_dir: self.queue_dir.clone(),
+      ..config.clone()
+    };
+    let mut job_result = JobResult {
+      job,
+      num,
+      results: Vec::new(),
+      errors: Vec::new(),
+    };
+    for res in analyze_law(&law_file_info, &config).await {
+      match res {
+        Ok(yomikae_data) => job_result.results.push(yomikae_data),
+        Err(err) => job_result.errors.push(err),
+      }
+    }
+    job_result
+  }
+
+  /// 解析を待っている法令XMLファイルを全て解析し、結果を書き出してから`done`ディレクトリに移す
+  /// 解析したジョブの結果を返す
+  pub async fn process_pending_jobs(&self, config: &AnalyzeConfig) -> io::Result<Vec<JobResult>> {
+    let done_dir = self.queue_dir.join(DONE_DIR_NAME);
+    tokio::fs::create_dir_all(&done_dir).await?;
+    tokio::fs::create_dir_all(&self.output_dir).await?;
+    let mut job_result_lst = Vec::new();
+    for file_name in self.pending_jobs().await? {
+      let job_result = self.run_job(&file_name, config).await;
+      let output_path = self.output_dir.join(format!("{}.json", job_result.job));
+      // 書き込み途中の結果を読まれないように、一時ファイルに書いてから名前を変える
+      let tmp_path = self
+        .output_dir
+        .join(format!(".{}.json.tmp", job_result.job));
+      let buf = serde_json::to_vec(&job_result)?;
+      tokio::fs::write(&tmp_path, buf).await?;
+      tokio::fs::rename(&tmp_path, &output_path).await?;
+      tokio::fs::rename(self.queue_dir.join(&file_name), done_dir.join(&file_name)).await?;
+      job_result_lst.push(job_result);
+    }
+    Ok(job_result_lst)
+  }
+}
+
+#[tokio::test]
+async fn check_process_pending_jobs() {
+  let dir = std::env::temp_dir().join(format!("analysis_yomikae_queue_{}", std::process::id()));
+  let queue_dir = dir.join("queue");
+  let output_dir = dir.join("output");
+  tokio::fs::create_dir_all(&queue_dir).await.unwrap();
+  tokio::fs::write(queue_dir.join("a.xml"), "<Law>")
+    .await
+    .unwrap();
+  tokio::fs::write(queue_dir.join(".b.xml"), "")
+    .await
+    .unwrap();
+  let nums = HashMap::from([("a.xml".to_string(), "test".to_string())]);
+  let queue = JobQueue::new(&queue_dir, &output_dir).with_nums(nums);
+  assert_eq!(
+    vec!["a.xml".to_string()],
+    queue.pending_jobs().await.unwrap()
+  );
+  let job_result_lst = queue
+    .process_pending_jobs(&AnalyzeConfig::default())
+    .await
+    .unwrap();
+  assert_eq!(1, job_result_lst.len());
+  assert_eq!("a", job_result_lst[0].job);
+  assert_eq!("test", job_result_lst[0].num);
+  assert!(output_dir.join("a.json").exists());
+  assert!(queue_dir.join(DONE_DIR_NAME).join("a.xml").exists());
+  assert!(queue.pending_jobs().await.unwrap().is_empty());
+  tokio::fs::remove_dir_all(&dir).await.unwrap();
+}
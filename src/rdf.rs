@@ -0,0 +1,343 @@
+//! 解析結果をRDFのトリプルとして出力する
+//!
+//! 法令は法令ID（法令XMLファイル名の先頭の部分）が分かる場合はe-Gov法令検索のURIで、
+//! 分からない場合は法律番号から作ったURNで表す
+//! 条項はその法令のURIにe-Gov法令検索と同じ形式のフラグメントを付けたもので表す
+//! 読み替えの組は条項のURIに、附則の場合はどの附則かと、その条項の何番目の組かを付けたもので表す
+
+use crate::egov::{article_anchor, egov_law_url, percent_encode};
+use crate::{article_to_string, YomikaeData};
+use jplaw_text::Article;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// 読み替えの関係を表す語彙の名前空間
+pub const YOMIKAE_NS: &str = "https://github.com/japanese-law-analysis/analysis_yomikae/ns#";
+const RDF_NS: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const RDFS_NS: &str = "http://www.w3.org/2000/01/rdf-schema#";
+const DCTERMS_NS: &str = "http://purl.org/dc/terms/";
+
+/// 出力する形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RdfFormat {
+  #[default]
+  Turtle,
+  NQuads,
+}
+
+impl FromStr for RdfFormat {
+  type Err = String;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "turtle" => Ok(RdfFormat::Turtle),
+      "nquads" => Ok(RdfFormat::NQuads),
+      _ => Err(format!(
+        "unknown RDF format: {s} (expected \"turtle\" or \"nquads\")"
+      )),
+    }
+  }
+}
+
+/// トリプルの目的語
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Term {
+  Iri(String),
+  /// 日本語の文字列
+  Literal(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Triple {
+  pub subject: String,
+  pub predicate: String,
+  pub object: Term,
+}
+
+impl Triple {
+  fn new(subject: &str, predicate: String, object: Term) -> Self {
+    Triple {
+      subject: subject.to_string(),
+      predicate,
+      object,
+    }
+  }
+}
+
+/// 法令のURI
+pub fn law_uri(num: &str, law_id: Option<&str>) -> String {
+  match law_id {
+    Some(law_id) => egov_law_url(law_id),
+    None => format!("urn:analysis_yomikae:law:{}", percent_encode(num)),
+  }
+}
+
+/// 条項のURI
+/// 法令のURIにe-Gov法令検索と同じ形式のアンカーを付ける
+pub fn article_uri(law_uri: &str, article: &Article) -> String {
+  format!("{law_uri}#{}", article_anchor(article))
+}
+
+/// 読み替えの組のURI
+/// e-Gov法令検索のアンカーでは附則を区別できないので、附則の場合は附則の見出しを付ける
+/// `index`は同じ条項（同じ附則）の中での組の番号で、同じ条項の解析結果が複数ある場合も通して数える
+pub fn yomikae_uri(article_uri: &str, article: &Article, index: usize) -> String {
+  match &article.suppl_provision_title {
+    Some(title) => format!("{article_uri}-Sp_{}-Yomikae_{index}", percent_encode(title)),
+    None => format!("{article_uri}-Yomikae_{index}"),
+  }
+}
+
+/// 解析結果をトリプルにする
+/// `law_ids`は法律番号から法令IDへの対応
+pub fn yomikae_triples(
+  yomikae_data_lst: &[YomikaeData],
+  law_ids: &HashMap<String, String>,
+) -> Vec<Triple> {
+  let yk = |name: &str| format!("{YOMIKAE_NS}{name}");
+  let rdf_type = format!("{RDF_NS}type");
+  let mut triples = Vec::new();
+  let mut described = BTreeSet::new();
+  // 条項（附則の場合はどの附則か）ごとの、これまでに出力した読み替えの組の数
+  let mut yomikae_counts: HashMap<(String, Option<String>), usize> = HashMap::new();
+  for yomikae_data in yomikae_data_lst {
+    let law = law_uri(
+      &yomikae_data.num,
+      law_ids.get(yomikae_data.num.as_str()).map(|s| s.as_str()),
+    );
+    let article = article_uri(&law, &yomikae_data.article);
+    if described.insert(law.clone()) {
+      triples.push(Triple::new(&law, rdf_type.clone(), Term::Iri(yk("Law"))));
+      triples.push(Triple::new(
+        &law,
+        yk("lawNum"),
+        Term::Literal(yomikae_data.num.to_string()),
+      ));
+    }
+    if described.insert(article.clone()) {
+      triples.push(Triple::new(
+        &article,
+        rdf_type.clone(),
+        Term::Iri(yk("Article")),
+      ));
+      triples.push(Triple::new(
+        &article,
+        format!("{DCTERMS_NS}isPartOf"),
+        Term::Iri(law.clone()),
+      ));
+      triples.push(Triple::new(
+        &article,
+        format!("{RDFS_NS}label"),
+        Term::Literal(article_to_string(&yomikae_data.article)),
+      ));
+    }
+    let yomikae_count = yomikae_counts
+      .entry((
+        article.clone(),
+        yomikae_data.article.suppl_provision_title.clone(),
+      ))
+      .or_default();
+    for yomikae_info in yomikae_data.data.iter() {
+      *yomikae_count += 1;
+      let yomikae = yomikae_uri(&article, &yomikae_data.article, *yomikae_count);
+      triples.push(Triple::new(
+        &yomikae,
+        rdf_type.clone(),
+        Term::Iri(yk("Yomikae")),
+      ));
+      triples.push(Triple::new(
+        &yomikae,
+        yk("article"),
+        Term::Iri(article.clone()),
+      ));
+      for before_word in yomikae_info.before_words.iter() {
+        triples.push(Triple::new(
+          &yomikae,
+          yk("beforeWord"),
+          Term::Literal(before_word.clone()),
+        ));
+      }
+      triples.push(Triple::new(
+        &yomikae,
+        yk("afterWord"),
+        Term::Literal(yomikae_info.after_word.clone()),
+      ));
+      triples.push(Triple::new(
+        &yomikae,
+        yk("operation"),
+        Term::Iri(yk(&format!("{:?}", yomikae_info.operation))),
+      ));
+    }
+  }
+  triples
+}
+
+fn escape_literal(s: &str) -> String {
+  let mut escaped = String::new();
+  for c in s.chars() {
+    match c {
+      '"' => escaped.push_str("\\\""),
+      '\\' => escaped.push_str("\\\\"),
+      '\n' => escaped.push_str("\\n"),
+      '\r' => escaped.push_str("\\r"),
+      _ => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+fn term_to_string(term: &Term) -> String {
+  match term {
+    Term::Iri(iri) => format!("<{iri}>"),
+    Term::Literal(s) => format!("\"{}\"@ja", escape_literal(s)),
+  }
+}
+
+/// N-Quads形式にする
+/// `graph`を指定した場合はそのグラフに、指定しない場合はデフォルトグラフに入れる
+pub fn to_nquads(triples: &[Triple], graph: Option<&str>) -> String {
+  let graph = graph.map(|g| format!(" <{g}>")).unwrap_or_default();
+  triples
+    .iter()
+    .map(|triple| {
+      format!(
+        "<{}> <{}> {}{graph} .\n",
+        triple.subject,
+        triple.predicate,
+        term_to_string(&triple.object)
+      )
+    })
+    .collect()
+}
+
+/// 名前空間が分かっているIRIは接頭辞を使って短くする
+fn turtle_iri(iri: &str) -> String {
+  let prefixes = [
+    ("yk", YOMIKAE_NS),
+    ("rdf", RDF_NS),
+    ("rdfs", RDFS_NS),
+    ("dcterms", DCTERMS_NS),
+  ];
+  prefixes
+    .iter()
+    .find_map(|(prefix, ns)| {
+      iri
+        .strip_prefix(ns)
+        .filter(|name| name.chars().all(|c| c.is_ascii_alphanumeric()))
+        .map(|name| format!("{prefix}:{name}"))
+    })
+    .unwrap_or_else(|| format!("<{iri}>"))
+}
+
+/// Turtle形式にする
+/// 同じ主語が続くトリプルは一つにまとめる
+pub fn to_turtle(triples: &[Triple]) -> String {
+  let mut turtle = format!(
+    "@prefix yk: <{YOMIKAE_NS}> .\n@prefix rdf: <{RDF_NS}> .\n@prefix rdfs: <{RDFS_NS}> .\n@prefix dcterms: <{DCTERMS_NS}> .\n"
+  );
+  let mut subject = None;
+  for triple in triples {
+    let object = match &triple.object {
+      Term::Iri(iri) => turtle_iri(iri),
+      Term::Literal(_) => term_to_string(&triple.object),
+    };
+    let predicate = if triple.predicate == format!("{RDF_NS}type") {
+      "a".to_string()
+    } else {
+      turtle_iri(&triple.predicate)
+    };
+    if subject == Some(&triple.subject) {
+      let _ = write!(turtle, " ;\n  {predicate} {object}");
+    } else {
+      if subject.is_some() {
+        turtle.push_str(" .\n");
+      }
+      let _ = write!(turtle, "\n<{}> {predicate} {object}", triple.subject);
+      subject = Some(&triple.subject);
+    }
+  }
+  if subject.is_some() {
+    turtle.push_str(" .\n");
+  }
+  turtle
+}
+
+/// 解析結果を指定した形式のRDFにする
+pub fn to_rdf(
+  yomikae_data_lst: &[YomikaeData],
+  law_ids: &HashMap<String, String>,
+  format: RdfFormat,
+  graph: Option<&str>,
+) -> String {
+  let triples = yomikae_triples(yomikae_data_lst, law_ids);
+  match format {
+    RdfFormat::Turtle => to_turtle(&triples),
+    RdfFormat::NQuads => to_nquads(&triples, graph),
+  }
+}
+
+#[test]
+fn check_to_rdf() {
+  use crate::{ArticleExt, YomikaeInfo};
+  let yomikae_data_lst = vec![YomikaeData::new(
+    "平成五年法律第八十八号",
+    Article::article("3").with_paragraph("1"),
+    vec![YomikaeInfo::new(["前条"], "第一条")],
+  )];
+  let mut law_ids = HashMap::new();
+  law_ids.insert(
+    "平成五年法律第八十八号".to_string(),
+    "405AC0000000088".to_string(),
+  );
+  let nquads = to_rdf(&yomikae_data_lst, &law_ids, RdfFormat::NQuads, None);
+  assert!(nquads.contains(&format!(
+    "<https://laws.e-gov.go.jp/law/405AC0000000088#Mp-At_3-Pr_1-Yomikae_1> <{YOMIKAE_NS}beforeWord> \"前条\"@ja .\n"
+  )));
+  let turtle = to_rdf(&yomikae_data_lst, &HashMap::new(), RdfFormat::Turtle, None);
+  let suppl_article = |title: &str| Article::article("2").in_suppl_provision(title);
+  let yomikae_data_lst = vec![
+    YomikaeData::new(
+      "平成五年法律第八十八号",
+      Article::article("3").with_paragraph("1"),
+      vec![YomikaeInfo::new(["前条"], "第一条")],
+    ),
+    YomikaeData::new(
+      "平成五年法律第八十八号",
+      Article::article("3").with_paragraph("1"),
+      vec![YomikaeInfo::new(["前項"], "第一項")],
+    ),
+    YomikaeData::new(
+      "平成五年法律第八十八号",
+      suppl_article("附則"),
+      vec![YomikaeInfo::new(["前条"], "第一条")],
+    ),
+    YomikaeData::new(
+      "平成五年法律第八十八号",
+      suppl_article("附則（平成六年法律第一号）"),
+      vec![YomikaeInfo::new(["前条"], "第一条")],
+    ),
+  ];
+  let subjects = yomikae_triples(&yomikae_data_lst, &law_ids)
+    .into_iter()
+    .filter(|triple| triple.object == Term::Iri(format!("{YOMIKAE_NS}Yomikae")))
+    .map(|triple| triple.subject)
+    .collect::<Vec<_>>();
+  assert_eq!(
+    vec![
+      "https://laws.e-gov.go.jp/law/405AC0000000088#Mp-At_3-Pr_1-Yomikae_1".to_string(),
+      "https://laws.e-gov.go.jp/law/405AC0000000088#Mp-At_3-Pr_1-Yomikae_2".to_string(),
+      format!(
+        "https://laws.e-gov.go.jp/law/405AC0000000088#Sp-At_2-Sp_{}-Yomikae_1",
+        percent_encode("附則")
+      ),
+      format!(
+        "https://laws.e-gov.go.jp/law/405AC0000000088#Sp-At_2-Sp_{}-Yomikae_1",
+        percent_encode("附則（平成六年法律第一号）")
+      ),
+    ],
+    subjects
+  );
+  assert!(turtle.contains(
+    "\n<urn:analysis_yomikae:law:%E5%B9%B3%E6%88%90%E4%BA%94%E5%B9%B4%E6%B3%95%E5%BE%8B%E7%AC%AC%E5%85%AB%E5%8D%81%E5%85%AB%E5%8F%B7> a yk:Law ;\n  yk:lawNum \"平成五年法律第八十八号\"@ja .\n"
+  ));
+}
@@ -0,0 +1,292 @@
+//! 鉤括弧の対応が取れていない文について、鉤括弧を足したり消したりする直し方を探す
+//!
+//! 位置は文の先頭からの文字数（0始まり）で表す
+
+use crate::segment::{split_segments, split_sentences};
+use crate::{parse_yomikae, ArticleExt, LawInfo, YomikaeError};
+use jplaw_text::{Article, LawContents, LawText};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// 一つの直し方で、足したり消したりする鉤括弧の数の上限
+pub const MAX_REPAIR_EDITS: usize = 2;
+
+/// 返す直し方の数の上限
+pub const MAX_REPAIR_SUGGESTIONS: usize = 5;
+
+/// 直し方を探す文の長さ（文字数）の上限
+/// 探す手間は文の長さの3乗程度で増えるので、これより長い文は探さない
+pub const MAX_REPAIR_SENTENCE_CHARS: usize = 300;
+
+/// 鉤括弧を足すのか消すのか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BracketEditKind {
+  /// `position`の文字の前に鉤括弧を足す
+  Insert,
+  /// `position`の鉤括弧を消す
+  Delete,
+}
+
+/// 鉤括弧一つについての編集
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BracketEdit {
+  /// 足すのか消すのか
+  pub kind: BracketEditKind,
+  /// 元の文の中での位置（文字数）
+  pub position: usize,
+  /// 足す、または消す鉤括弧
+  pub bracket: char,
+}
+
+impl fmt::Display for BracketEdit {
+  /// 「insert 「 at 12」のような形で表示する
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let kind = match self.kind {
+      BracketEditKind::Insert => "insert",
+      BracketEditKind::Delete => "delete",
+    };
+    write!(f, "{kind} {} at {}", self.bracket, self.position)
+  }
+}
+
+/// 鉤括弧の対応を取るための一つの直し方
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct BracketRepair {
+  /// 編集の並び（位置の順）
+  pub edits: Vec<BracketEdit>,
+  /// 直した文から読み替えの組が取り出せるかどうか
+  pub parses: bool,
+}
+
+/// 対応の取れていない鉤括弧の数
+fn unmatched_count(chars: &[char]) -> usize {
+  let mut depth: usize = 0;
+  let mut unmatched_close = 0;
+  for c in chars.iter() {
+    match c {
+      '「' => depth += 1,
+      '」' => match depth.checked_sub(1) {
+        Some(d) => depth = d,
+        None => unmatched_close += 1,
+      },
+      _ => (),
+    }
+  }
+  depth + unmatched_close
+}
+
+/// 元の文の位置で表した編集を、文に当てはめる
+fn apply_edits(chars: &[char], edits: &[BracketEdit]) -> String {
+  let mut s = String::new();
+  for (i, c) in chars.iter().chain(std::iter::once(&'\0')).enumerate() {
+    for edit in edits.iter().filter(|edit| edit.position == i) {
+      if edit.kind == BracketEditKind::Insert {
+        s.push(edit.bracket);
+      }
+    }
+    let is_deleted = edits
+      .iter()
+      .any(|edit| edit.position == i && edit.kind == BracketEditKind::Delete);
+    if i < chars.len() && !is_deleted {
+      s.push(*c);
+    }
+  }
+  s
+}
+
+/// 文に一つ編集を加えて、対応の取れていない鉤括弧の数を減らす編集を全て挙げる
+fn reducing_edits(chars: &[char], edits: &[BracketEdit]) -> Vec<BracketEdit> {
+  let current: Vec<char> = apply_edits(chars, edits).chars().collect();
+  let count = unmatched_count(&current);
+  let mut candidates = Vec::new();
+  for position in 0..=chars.len() {
+    for bracket in ['「', '」'] {
+      candidates.push(BracketEdit {
+        kind: BracketEditKind::Insert,
+        position,
+        bracket,
+      });
+    }
+    if let Some(c @ ('「' | '」')) = chars.get(position) {
+      candidates.push(BracketEdit {
+        kind: BracketEditKind::Delete,
+        position,
+        bracket: *c,
+      });
+    }
+  }
+  candidates
+    .into_iter()
+    .filter(|edit| {
+      // 同じ位置の鉤括弧を二度消したり、同じ位置に何度も足したりはしない
+      !edits.iter().any(|e| e.position == edit.position)
+    })
+    .filter(|edit| {
+      let mut new_edits = edits.to_vec();
+      new_edits.push(*edit);
+      let s: Vec<char> = apply_edits(chars, &new_edits).chars().collect();
+      unmatched_count(&s) < count
+    })
+    .collect()
+}
+
+fn is_hiragana(c: char) -> bool {
+  ('\u{3041}'..='\u{309F}').contains(&c)
+}
+
+/// 足す鉤括弧が、語の切れ目にあるかどうか
+/// 鉤括弧で括られる語は漢字などで始まって終わり、その外側は平仮名や読点であることが多いので、
+/// 開き鉤括弧は平仮名や読点の後で平仮名でない文字の前に、閉じ鉤括弧はその逆の位置にあるものを語の切れ目とする
+fn is_word_boundary(chars: &[char], edit: &BracketEdit) -> bool {
+  let is_outside = |c: Option<&char>| c.map_or(true, |c| is_hiragana(*c) || *c == '、');
+  let prev = edit.position.checked_sub(1).and_then(|i| chars.get(i));
+  let next = chars.get(edit.position);
+  match (edit.kind, edit.bracket) {
+    (BracketEditKind::Insert, '「') => is_outside(prev) && !is_outside(next),
+    (BracketEditKind::Insert, _) => !is_outside(prev) && is_outside(next),
+    (BracketEditKind::Delete, _) => false,
+  }
+}
+
+/// 鉤括弧の対応が取れた文から、読み替えの組が取り出せるかどうか
+fn parses(sentence: &str) -> bool {
+  // 鉤括弧の対応が取れていない文は、解析してもエラーになるので解析しない
+  if split_segments(sentence).is_none() {
+    return false;
+  }
+  let law_text = LawText {
+    article_info: Article::article("1"),
+    contents: LawContents::Text(sentence.to_string()),
+  };
+  matches!(parse_yomikae(&law_text, "", &law_text.article_info), Ok(lst) if !lst.is_empty())
+}
+
+/// 鉤括弧の対応が取れていない文について、鉤括弧の対応が取れるようになる直し方を探す
+///
+/// 編集の数が少ないものから順に、同じ数の中では直した文から読み替えの組が取り出せるもの、
+/// 語の切れ目に鉤括弧を足す編集が多いものを先にして、最大[`MAX_REPAIR_SUGGESTIONS`]個返す
+/// [`MAX_REPAIR_EDITS`]個までの編集で直せない場合や、対応が取れている文の場合、
+/// 文が[`MAX_REPAIR_SENTENCE_CHARS`]文字より長い場合は空のリストを返す
+pub fn suggest_bracket_repairs(sentence: &str) -> Vec<BracketRepair> {
+  let chars: Vec<char> = sentence.chars().collect();
+  if chars.len() > MAX_REPAIR_SENTENCE_CHARS {
+    return Vec::new();
+  }
+  let needed = unmatched_count(&chars);
+  if needed == 0 || needed > MAX_REPAIR_EDITS {
+    return Vec::new();
+  }
+  let mut edits_lst: Vec<Vec<BracketEdit>> = vec![Vec::new()];
+  for _ in 0..needed {
+    let mut seen = HashSet::new();
+    edits_lst = edits_lst
+      .iter()
+      .flat_map(|edits| {
+        reducing_edits(&chars, edits).into_iter().map(move |edit| {
+          let mut new_edits = edits.clone();
+          new_edits.push(edit);
+          new_edits.sort();
+          new_edits
+        })
+      })
+      .filter(|edits| seen.insert(edits.clone()))
+      .collect();
+  }
+  let mut repairs = edits_lst
+    .into_iter()
+    .map(|mut edits| {
+      edits.sort_by_key(|edit| (edit.position, edit.kind));
+      let parses = parses(&apply_edits(&chars, &edits));
+      BracketRepair { edits, parses }
+    })
+    .collect::<Vec<_>>();
+  // 編集の数は全て同じなので、読み替えの組が取り出せるもの、語の切れ目に足すものを先にする
+  repairs.sort_by_key(|repair| {
+    let boundary_count = repair
+      .edits
+      .iter()
+      .filter(|edit| is_word_boundary(&chars, edit))
+      .count();
+    (!repair.parses, std::cmp::Reverse(boundary_count))
+  });
+  repairs.truncate(MAX_REPAIR_SUGGESTIONS);
+  repairs
+}
+
+/// 鉤括弧の対応が取れていないエラーに、エラーが起きた文の直し方の候補を[`LawInfo::bracket_repairs`]として付ける
+/// 他のエラーはそのまま返す
+pub fn attach_bracket_repairs(err: YomikaeError) -> YomikaeError {
+  match err {
+    YomikaeError::UnmatchedParen(law_info) => {
+      let bracket_repairs = match (&law_info.contents.contents, law_info.sentence_index) {
+        (LawContents::Text(text), Some(i)) => split_sentences(text)
+          .get(i)
+          .map(|sentence| suggest_bracket_repairs(sentence))
+          .unwrap_or_default(),
+        (LawContents::Text(text), None) => suggest_bracket_repairs(text),
+        (LawContents::Table(_), _) => Vec::new(),
+      };
+      YomikaeError::UnmatchedParen(LawInfo {
+        bracket_repairs,
+        ..law_info
+      })
+    }
+    err => err,
+  }
+}
+
+#[test]
+fn check_suggest_bracket_repairs() {
+  let repairs = suggest_bracket_repairs("前条中「前項」とあるのは第二項」と読み替える。");
+  assert!(!repairs.is_empty());
+  assert_eq!(
+    vec![BracketEdit {
+      kind: BracketEditKind::Insert,
+      position: "前条中「前項」とあるのは".chars().count(),
+      bracket: '「',
+    }],
+    repairs[0].edits
+  );
+  assert!(repairs[0].parses);
+  assert!(repairs.iter().all(|repair| repair.edits.len() == 1));
+  assert!(suggest_bracket_repairs("前条中「前項」とあるのは「第二項」と読み替える。").is_empty());
+  let repairs = suggest_bracket_repairs("前項」とあるのは第二項」と読み替える。");
+  assert!(repairs.iter().all(|repair| repair.edits.len() == 2));
+  assert!(repairs[0].parses);
+  assert!(suggest_bracket_repairs("」」」").is_empty());
+  let long_sentence = format!(
+    "{}」と読み替える。",
+    "前項".repeat(MAX_REPAIR_SENTENCE_CHARS)
+  );
+  assert!(suggest_bracket_repairs(&long_sentence).is_empty());
+}
+
+#[test]
+fn check_attach_bracket_repairs() {
+  let law_text = crate::test_utils::law_text("前条中「前項」とあるのは第二項」と読み替える。");
+  match parse_yomikae(&law_text, "test", &law_text.article_info) {
+    Err(err @ YomikaeError::UnmatchedParen(_)) => {
+      // parse_yomikaeは直し方を探さない
+      assert!(
+        matches!(&err, YomikaeError::UnmatchedParen(law_info) if law_info.bracket_repairs.is_empty())
+      );
+      match attach_bracket_repairs(err) {
+        YomikaeError::UnmatchedParen(law_info) => assert_eq!(
+          suggest_bracket_repairs("前条中「前項」とあるのは第二項」と読み替える。"),
+          law_info.bracket_repairs
+        ),
+        err => panic!("unexpected error: {err:?}"),
+      }
+    }
+    res => panic!("unexpected result: {res:?}"),
+  }
+  let law_text = crate::test_utils::law_text("前条の規定を準用する。");
+  let err = YomikaeError::NotFoundYomikae(LawInfo::new(
+    "test",
+    law_text.article_info.clone(),
+    law_text,
+  ));
+  assert_eq!(err.clone(), attach_bracket_repairs(err));
+}
@@ -0,0 +1,157 @@
+//! 条項ごとの読み替えの組を、読み替える対象の規定ごとに、そのまま当てはめられる置き換えの一覧にまとめる
+//!
+//! 置き換えの一覧は、長い語が先に置き換えられるように読み替え前の語の長い順に並べる
+//! 「都道府県知事」と「都道府県」の両方が読み替えられている場合に、「都道府県知事」の中の「都道府県」を先に置き換えないようにするため
+
+use crate::{flatten_yomikae_data, LawNum, TargetRef, YomikaeData};
+use jplaw_text::Article;
+use serde::{Deserialize, Serialize};
+
+/// 同じ語が違う語に読み替えられていた場合にどうするか
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum ConflictPolicy {
+  /// 最初に出てきた読み替えを使う
+  #[default]
+  First,
+  /// 最後に出てきた読み替えを使う
+  Last,
+  /// [`ReplacementConflict`]を返す
+  Error,
+}
+
+/// ある規定を読み替える、置き換えの一覧
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ReplacementMap {
+  /// 読み替えている法令の法律番号
+  pub num: LawNum,
+  /// 読み替えている条項
+  pub article: Article,
+  /// 読み替えの対象となる規定（書かれていない場合は`None`）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub target: Option<TargetRef>,
+  /// 読み替え前の語と読み替え後の語の組を、読み替え前の語の長い順に並べたもの
+  /// 長さが同じものは出てきた順にする
+  pub pairs: Vec<(String, String)>,
+}
+
+/// 同じ規定の同じ語が、違う語に読み替えられていたもの
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ReplacementConflict {
+  /// 読み替えている法令の法律番号
+  pub num: LawNum,
+  /// 読み替えている条項
+  pub article: Article,
+  /// 読み替えの対象となる規定
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub target: Option<TargetRef>,
+  /// 読み替え前の語
+  pub before_word: String,
+  /// 読み替え後の語（出てきた順）
+  pub after_words: Vec<String>,
+}
+
+/// 条項の読み替えの組を、読み替えの対象となる規定ごとの置き換えの一覧にまとめる
+///
+/// 規定は最初に出てきた順に並べ、読み替え前の語ごとに対象が書かれている場合はその語の対象で分ける
+/// 読み替え前の語が無い組（[`crate::YomikaeOperation::Insert`]）は置き換えで表せないので含めない
+/// 全く同じ組が何度も出てきた場合は一つにまとめ、同じ語が違う語に読み替えられていた場合は`policy`に従う
+pub fn replacement_maps(
+  yomikae_data: &YomikaeData,
+  policy: ConflictPolicy,
+) -> Result<Vec<ReplacementMap>, ReplacementConflict> {
+  // (対象の規定, 読み替え前の語と、出てきた順の読み替え後の語の一覧)
+  let mut groups: Vec<(Option<TargetRef>, Vec<(String, Vec<String>)>)> = Vec::new();
+  for record in flatten_yomikae_data(yomikae_data) {
+    if record.before_word.is_empty() {
+      continue;
+    }
+    let words_lst = match groups
+      .iter()
+      .position(|(target, _)| *target == record.target)
+    {
+      Some(i) => &mut groups[i].1,
+      None => {
+        groups.push((record.target.clone(), Vec::new()));
+        &mut groups.last_mut().unwrap().1
+      }
+    };
+    match words_lst
+      .iter_mut()
+      .find(|(before_word, _)| *before_word == record.before_word)
+    {
+      Some((_, after_words)) => {
+        if !after_words.contains(&record.after_word) {
+          after_words.push(record.after_word)
+        }
+      }
+      None => words_lst.push((record.before_word, vec![record.after_word])),
+    }
+  }
+  let mut map_lst = Vec::new();
+  for (target, words_lst) in groups {
+    let mut pairs = Vec::new();
+    for (before_word, mut after_words) in words_lst {
+      let after_word = if after_words.len() == 1 {
+        after_words.remove(0)
+      } else {
+        match policy {
+          ConflictPolicy::First => after_words.remove(0),
+          ConflictPolicy::Last => after_words.pop().unwrap(),
+          ConflictPolicy::Error => {
+            return Err(ReplacementConflict {
+              num: yomikae_data.num.clone(),
+              article: yomikae_data.article.clone(),
+              target,
+              before_word,
+              after_words,
+            })
+          }
+        }
+      };
+      pairs.push((before_word, after_word));
+    }
+    pairs.sort_by_key(|(before_word, _)| std::cmp::Reverse(before_word.chars().count()));
+    map_lst.push(ReplacementMap {
+      num: yomikae_data.num.clone(),
+      article: yomikae_data.article.clone(),
+      target,
+      pairs,
+    });
+  }
+  Ok(map_lst)
+}
+
+#[test]
+fn check_replacement_maps() {
+  use crate::{ArticleExt, YomikaeInfo};
+  let yomikae_data = YomikaeData::new(
+    "test",
+    Article::article("2"),
+    vec![
+      YomikaeInfo::new(["都道府県"], "市"),
+      YomikaeInfo::new(["都道府県知事"], "市長"),
+      YomikaeInfo::new(["都道府県知事"], "市長"),
+      YomikaeInfo::new(Vec::<String>::new(), "又は町村"),
+      YomikaeInfo::new(["前項"], "次項").with_target(TargetRef::provision("第三条")),
+      YomikaeInfo::new(["都道府県"], "町村"),
+    ],
+  );
+  let map_lst = replacement_maps(&yomikae_data, ConflictPolicy::First).unwrap();
+  assert_eq!(2, map_lst.len());
+  assert_eq!(None, map_lst[0].target);
+  assert_eq!(
+    vec![
+      ("都道府県知事".to_string(), "市長".to_string()),
+      ("都道府県".to_string(), "市".to_string()),
+    ],
+    map_lst[0].pairs
+  );
+  assert_eq!(Some(TargetRef::provision("第三条")), map_lst[1].target);
+  let map_lst = replacement_maps(&yomikae_data, ConflictPolicy::Last).unwrap();
+  assert_eq!("町村", map_lst[0].pairs[1].1);
+  let conflict = replacement_maps(&yomikae_data, ConflictPolicy::Error).unwrap_err();
+  assert_eq!("都道府県", conflict.before_word);
+  assert_eq!(vec!["市", "町村"], conflict.after_words);
+}
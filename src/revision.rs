@@ -0,0 +1,149 @@
+//! 法令の版（どの時点の法令XMLか）の情報を読み取る
+//!
+//! 同じ法令が複数の時点の法令XMLのスナップショットに含まれる場合に、解析結果が混ざらないように、
+//! 解析結果に版の情報を付けて、法令ID・版・条文の内容のハッシュ値の組で見分けられるようにする
+
+use crate::egov::law_id_from_file;
+use crate::promulgation::{era_offset, PromulgationDate};
+use crate::schema::law_element_attrs;
+use crate::YomikaeData;
+use serde::{Deserialize, Serialize};
+
+/// 法令の版の情報
+/// 施行日・公布日の順に並べられるように、この順でフィールドを並べている
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct LawRevision {
+  /// 法令ID（法令XMLファイル名の一つ目の部分）
+  #[serde(default, alias = "lawId", skip_serializing_if = "Option::is_none")]
+  pub law_id: Option<String>,
+  /// その版の施行日（法令XMLファイル名の二つ目の部分、`YYYYMMDD`）
+  #[serde(
+    default,
+    alias = "enforcementDate",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub enforcement_date: Option<String>,
+  /// その版に改正した法令の法令ID（法令XMLファイル名の三つ目の部分）
+  #[serde(
+    default,
+    alias = "amendmentLawId",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub amendment_law_id: Option<String>,
+  /// 公布日（`Law`要素の`Era`・`Year`・`PromulgateMonth`・`PromulgateDay`属性から読む、`YYYY-MM-DD`）
+  #[serde(
+    default,
+    alias = "promulgationDate",
+    skip_serializing_if = "Option::is_none"
+  )]
+  pub promulgation_date: Option<String>,
+}
+
+/// 版ごとに解析結果を見分けるための識別子
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RevisionKey {
+  /// 法令ID（分からない場合は法律番号）
+  pub law_id: String,
+  /// 版（施行日、分からない場合は公布日）
+  pub revision: Option<String>,
+  /// 元の条文の内容のハッシュ値
+  pub content_hash: Option<String>,
+}
+
+/// `Law`要素の属性から公布日を読む
+fn promulgation_date(xml: &[u8]) -> Option<PromulgationDate> {
+  let attrs = law_element_attrs(xml);
+  let get_u32 = |key: &str| attrs.get(key).and_then(|v| v.parse::<u32>().ok());
+  let year = era_offset(attrs.get("Era")?)? + get_u32("Year")?;
+  Some(PromulgationDate {
+    year,
+    month: get_u32("PromulgateMonth")?,
+    day: get_u32("PromulgateDay")?,
+  })
+}
+
+/// 法令XMLファイル名と法令XMLから版の情報を読む
+/// ファイル名が「405AC0000000088_20230401_504AC0000000068.xml」の形でない場合は、ファイル名から読む情報を`None`にする
+pub fn read_law_revision(file: &str, xml: &[u8]) -> LawRevision {
+  let name = file.rsplit(['/', '\\']).next().unwrap_or(file);
+  let stem = name.split('.').next().unwrap_or_default();
+  let mut parts = stem.split('_').skip(1);
+  let enforcement_date = parts
+    .next()
+    .filter(|part| part.len() == 8 && part.chars().all(|c| c.is_ascii_digit()))
+    .map(|part| part.to_string());
+  let amendment_law_id = parts
+    .next()
+    .filter(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+    .map(|part| part.to_string());
+  LawRevision {
+    law_id: law_id_from_file(file),
+    enforcement_date,
+    amendment_law_id,
+    promulgation_date: promulgation_date(xml).map(|date| date.to_string()),
+  }
+}
+
+impl YomikaeData {
+  /// 版の情報を記録する
+  pub fn with_revision(self, revision: LawRevision) -> Self {
+    YomikaeData {
+      revision: Some(revision),
+      ..self
+    }
+  }
+
+  /// 版ごとに解析結果を見分けるための識別子を作る
+  pub fn revision_key(&self) -> RevisionKey {
+    let revision = self.revision.as_ref();
+    RevisionKey {
+      law_id: revision
+        .and_then(|revision| revision.law_id.clone())
+        .unwrap_or_else(|| self.num.to_string()),
+      revision: revision.and_then(|revision| {
+        revision
+          .enforcement_date
+          .clone()
+          .or_else(|| revision.promulgation_date.clone())
+      }),
+      content_hash: self.content_hash.clone(),
+    }
+  }
+}
+
+#[test]
+fn check_read_law_revision() {
+  use crate::ArticleExt;
+  use jplaw_text::Article;
+  let xml = r#"<Law Era="Heisei" Year="05" Num="088" PromulgateMonth="11" PromulgateDay="12" LawType="Act"><LawNum>平成五年法律第八十八号</LawNum></Law>"#;
+  let revision = read_law_revision(
+    "law_xml/405AC0000000088_20230401_504AC0000000068.xml",
+    xml.as_bytes(),
+  );
+  assert_eq!(
+    LawRevision {
+      law_id: Some("405AC0000000088".to_string()),
+      enforcement_date: Some("20230401".to_string()),
+      amendment_law_id: Some("504AC0000000068".to_string()),
+      promulgation_date: Some("1993-11-12".to_string()),
+    },
+    revision
+  );
+  let revision = read_law_revision("405AC0000000088.xml", b"<Law></Law>");
+  assert_eq!(Some("405AC0000000088".to_string()), revision.law_id);
+  assert_eq!(None, revision.enforcement_date);
+  assert_eq!(None, revision.promulgation_date);
+  let yomikae_data = YomikaeData::new("test", Article::article("1"), Vec::new());
+  let old = yomikae_data.clone().with_revision(LawRevision {
+    law_id: Some("405AC0000000088".to_string()),
+    enforcement_date: Some("20200401".to_string()),
+    ..Default::default()
+  });
+  let new = yomikae_data.clone().with_revision(LawRevision {
+    law_id: Some("405AC0000000088".to_string()),
+    enforcement_date: Some("20230401".to_string()),
+    ..Default::default()
+  });
+  assert_ne!(old.revision_key(), new.revision_key());
+  assert_eq!("test", yomikae_data.revision_key().law_id);
+}
@@ -0,0 +1,65 @@
+//! 法令XMLのルビ（振り仮名）の読みを取り除く
+//!
+//! `<Ruby>漢字<Rt>かんじ</Rt></Ruby>`のような要素は、そのままでは本文と読みがつながった文になり、
+//! 語句の照合を妨げるので、jplaw_textに渡す前に`Rt`要素を取り除いて本文だけを残す
+
+use std::borrow::Cow;
+
+/// `needle`が`from`以降で最初に現れる位置
+fn find_from(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+  haystack
+    .get(from..)?
+    .windows(needle.len())
+    .position(|w| w == needle)
+    .map(|i| i + from)
+}
+
+/// `Rt`要素を全て取り除き、取り除いた数と一緒に返す
+pub fn strip_ruby_readings(xml: &[u8]) -> (Cow<'_, [u8]>, usize) {
+  let mut stripped = Vec::new();
+  let mut count = 0;
+  let mut pos = 0;
+  while let Some(start) = find_from(xml, b"<Rt", pos) {
+    // `<Rtxxx>`のような別の要素は取り除かない
+    let is_rt = matches!(xml.get(start + 3), Some(b'>' | b'/' | b' '));
+    let end = if !is_rt {
+      None
+    } else if let Some(b'/') = xml.get(start + 3) {
+      find_from(xml, b">", start).map(|i| i + 1)
+    } else {
+      find_from(xml, b"</Rt>", start).map(|i| i + b"</Rt>".len())
+    };
+    match end {
+      Some(end) => {
+        stripped.extend_from_slice(&xml[pos..start]);
+        pos = end;
+        count += 1;
+      }
+      None => {
+        stripped.extend_from_slice(&xml[pos..start + 3]);
+        pos = start + 3;
+      }
+    }
+  }
+  if count == 0 {
+    (Cow::Borrowed(xml), 0)
+  } else {
+    stripped.extend_from_slice(&xml[pos..]);
+    (Cow::Owned(stripped), count)
+  }
+}
+
+#[test]
+fn check_strip_ruby_readings() {
+  let xml =
+    "<Sentence>この<Ruby>賦<Rt>ふ</Rt></Ruby>課の<Ruby>拘<Rt>こう</Rt></Ruby>束<Rtx/></Sentence>";
+  let (stripped, count) = strip_ruby_readings(xml.as_bytes());
+  assert_eq!(2, count);
+  assert_eq!(
+    "<Sentence>この<Ruby>賦</Ruby>課の<Ruby>拘</Ruby>束<Rtx/></Sentence>",
+    String::from_utf8(stripped.into_owned()).unwrap()
+  );
+  let (stripped, count) = strip_ruby_readings(b"<Sentence>text</Sentence>");
+  assert_eq!(0, count);
+  assert!(matches!(stripped, Cow::Borrowed(_)));
+}
@@ -0,0 +1,168 @@
+//! 解析の進み具合を数えた値
+//!
+//! 値は[`RunMetrics`]にためると同時に、`metrics`クレートのマクロでも記録する
+
+use crate::error_key::error_kind;
+use crate::{Lang, YomikaeError};
+use metrics::{counter, histogram};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// 解析全体で処理した件数とかかった時間
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunMetrics {
+  /// 解析が完了した法令の数
+  pub processed_laws: u64,
+  /// 読み替え規定として解析した条文の数
+  pub processed_articles: u64,
+  /// 取り出した読み替えの組の数
+  pub pairs: u64,
+  /// エラーの種類ごとの件数
+  pub errors: BTreeMap<String, u64>,
+  /// 法令ごとの解析にかかった時間（秒）の合計
+  pub law_duration_secs: f64,
+  /// 解析全体にかかった時間（秒）
+  pub total_duration_secs: f64,
+  /// 読み替え規定文の候補とする文言ごとの件数
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub trigger_phrases: BTreeMap<String, PhraseStats>,
+}
+
+/// 読み替え規定文の候補とする文言一つについての件数
+/// 一つの条文が複数の文言を含む場合は、それぞれの文言で数える
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhraseStats {
+  /// その文言を含む条文の数
+  pub articles: u64,
+  /// そのうち読み替えの組が一つ以上取り出せた条文の数
+  pub articles_with_pairs: u64,
+  /// その文言を含む条文から取り出した読み替えの組の数
+  pub pairs: u64,
+}
+
+impl RunMetrics {
+  /// 一つの法令の解析が終わった
+  pub fn record_law(&mut self, duration: Duration) {
+    self.processed_laws += 1;
+    self.law_duration_secs += duration.as_secs_f64();
+    counter!("analysis_yomikae_laws_total").increment(1);
+    histogram!("analysis_yomikae_law_duration_seconds").record(duration.as_secs_f64());
+  }
+
+  /// 一つの条文から読み替えの組を取り出した
+  pub fn record_article(&mut self, pairs: usize) {
+    self.processed_articles += 1;
+    self.pairs += pairs as u64;
+    counter!("analysis_yomikae_articles_total").increment(1);
+    counter!("analysis_yomikae_pairs_total").increment(pairs as u64);
+  }
+
+  /// 読み替え規定文の候補とする文言を含む条文から、読み替えの組を取り出した
+  pub fn record_phrases(&mut self, phrases: &[&str], pairs: usize) {
+    for phrase in phrases {
+      let phrase_stats = self.trigger_phrases.entry(phrase.to_string()).or_default();
+      phrase_stats.articles += 1;
+      if pairs > 0 {
+        phrase_stats.articles_with_pairs += 1;
+      }
+      phrase_stats.pairs += pairs as u64;
+    }
+  }
+
+  /// エラーが起きた
+  pub fn record_error(&mut self, err: &YomikaeError) {
+    let kind = error_kind(err);
+    *self.errors.entry(kind.to_string()).or_default() += 1;
+    counter!("analysis_yomikae_errors_total", "kind" => kind).increment(1);
+  }
+
+  /// 解析全体が終わった
+  pub fn finish(&mut self, duration: Duration) {
+    self.total_duration_secs = duration.as_secs_f64();
+    histogram!("analysis_yomikae_run_duration_seconds").record(duration.as_secs_f64());
+  }
+
+  /// 解析全体の件数・エラーの種類ごとの件数・かかった時間をまとめた、実行の終わりに表示する文
+  pub fn summary(&self, lang: Lang) -> String {
+    let error_count = self.errors.values().sum::<u64>();
+    let mut lines = match lang {
+      Lang::Ja => vec![
+        format!("法令: {}", self.processed_laws),
+        format!("読み替えの組: {}", self.pairs),
+        format!("エラー: {error_count}"),
+      ],
+      Lang::En => vec![
+        format!("laws: {}", self.processed_laws),
+        format!("pairs: {}", self.pairs),
+        format!("errors: {error_count}"),
+      ],
+    };
+    for (kind, count) in self.errors.iter() {
+      lines.push(format!("  {kind}: {count}"));
+    }
+    for (phrase, phrase_stats) in self.trigger_phrases.iter() {
+      lines.push(match lang {
+        Lang::Ja => format!(
+          "「{phrase}」: 条文 {}（読み替えの組あり {}）、読み替えの組 {}",
+          phrase_stats.articles, phrase_stats.articles_with_pairs, phrase_stats.pairs
+        ),
+        Lang::En => format!(
+          "\"{phrase}\": {} articles ({} with pairs), {} pairs",
+          phrase_stats.articles, phrase_stats.articles_with_pairs, phrase_stats.pairs
+        ),
+      });
+    }
+    lines.push(match lang {
+      Lang::Ja => format!("時間: {:.2}秒", self.total_duration_secs),
+      Lang::En => format!("elapsed: {:.2}s", self.total_duration_secs),
+    });
+    lines.join("\n")
+  }
+}
+
+#[test]
+fn check_run_metrics() {
+  use crate::{LawFileInfo, LawInfo};
+  let mut run_metrics = RunMetrics::default();
+  run_metrics.record_article(2);
+  run_metrics.record_article(1);
+  run_metrics.record_law(Duration::from_millis(500));
+  let law_info = LawInfo::new(
+    "test",
+    crate::test_utils::test_article(),
+    crate::test_utils::law_text("「A」と読み替える"),
+  );
+  run_metrics.record_error(&YomikaeError::NotFoundYomikae(law_info.clone()));
+  run_metrics.record_error(&YomikaeError::NotFoundYomikae(law_info));
+  run_metrics.record_error(&YomikaeError::ReadLawFile(
+    LawFileInfo {
+      num: "test".to_string(),
+      file: "test.xml".to_string(),
+    },
+    "not found".to_string(),
+  ));
+  run_metrics.finish(Duration::from_secs(1));
+  assert_eq!(1, run_metrics.processed_laws);
+  assert_eq!(2, run_metrics.processed_articles);
+  assert_eq!(3, run_metrics.pairs);
+  assert_eq!(Some(&2), run_metrics.errors.get("not_found_yomikae"));
+  assert_eq!(Some(&1), run_metrics.errors.get("read_law_file"));
+  assert_eq!(0.5, run_metrics.law_duration_secs);
+  assert_eq!(1.0, run_metrics.total_duration_secs);
+  assert_eq!(
+    "laws: 1\npairs: 3\nerrors: 3\n  not_found_yomikae: 2\n  read_law_file: 1\nelapsed: 1.00s",
+    run_metrics.summary(Lang::En)
+  );
+  run_metrics.record_phrases(&["と読み替える", "読み替えて準用する"], 2);
+  run_metrics.record_phrases(&["読み替えて準用する"], 0);
+  assert_eq!(
+    Some(&PhraseStats {
+      articles: 2,
+      articles_with_pairs: 1,
+      pairs: 2,
+    }),
+    run_metrics.trigger_phrases.get("読み替えて準用する")
+  );
+  assert_eq!(1, run_metrics.trigger_phrases["と読み替える"].articles);
+}
@@ -0,0 +1,274 @@
+//! 法令案の問題や解析のエラーを、SARIF 2.1.0の形式で出力する
+//!
+//! SARIFを読めるコードレビューや文書レビューのツールに結果を表示させるために使う
+//! 位置はUnicodeのコードポイントの数で表す（`columnKind`は`unicodeCodePoints`）
+
+use crate::error_key::error_kind;
+use crate::lint::LintIssue;
+use crate::repair::{BracketEditKind, BracketRepair};
+use crate::segment::split_sentences;
+use crate::{article_to_string, YomikaeError};
+use jplaw_text::LawContents;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// SARIFのJSONスキーマ
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIFのバージョン
+pub const SARIF_VERSION: &str = "2.1.0";
+
+/// ルールの説明
+fn rule_description(rule_id: &str) -> &'static str {
+  match rule_id {
+    "unbalanced_brackets" => "brackets are not balanced",
+    "before_word_not_found" => "before word is not found in the target provision",
+    "duplicated_mapping" => "the same before word is read more than once",
+    "missing_terminal_phrase" => "「とあるのは」 is not followed by 「と読み替える」",
+    "parse_error" => "the sentence cannot be parsed as a yomikae sentence",
+    "contents_of_table" => "table has an unexpected number of columns",
+    "unmatched_paren" => "unmatched parentheses",
+    "unexpected_parallel_words" => "unexpected parallel words",
+    "unmatched_sorezore_words" => "unmatched number of words for 「それぞれ」",
+    "not_found_yomikae" => "no yomikae pair was extracted",
+    "read_law_file" => "failed to read law file",
+    "parse_law_xml" => "failed to parse law XML",
+    "analyze_law" => "failed to analyze law file",
+    _ => "internal error",
+  }
+}
+
+/// 結果の一覧から、使われたルールの一覧を作ったSARIFのログにする
+fn sarif_log(results: Vec<Value>) -> Value {
+  let rule_ids = results
+    .iter()
+    .filter_map(|result| result["ruleId"].as_str())
+    .map(|rule_id| (rule_id.to_string(), rule_description(rule_id)))
+    .collect::<BTreeMap<_, _>>();
+  let rules = rule_ids
+    .into_iter()
+    .map(|(id, description)| json!({ "id": id, "shortDescription": { "text": description } }))
+    .collect::<Vec<_>>();
+  json!({
+    "$schema": SARIF_SCHEMA,
+    "version": SARIF_VERSION,
+    "runs": [{
+      "tool": {
+        "driver": {
+          "name": env!("CARGO_PKG_NAME"),
+          "version": env!("CARGO_PKG_VERSION"),
+          "informationUri": env!("CARGO_PKG_REPOSITORY"),
+          "rules": rules,
+        }
+      },
+      "columnKind": "unicodeCodePoints",
+      "results": results,
+    }]
+  })
+}
+
+/// 編集の説明を「insert 「 at 12, delete 」 at 20」のような形にする
+fn repair_description(repair: &BracketRepair) -> String {
+  repair
+    .edits
+    .iter()
+    .map(|edit| edit.to_string())
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// 直し方を、文が始まる位置`sentence_offset`を足した位置での置き換えにする
+fn repair_fix(uri: &str, sentence_offset: usize, repair: &BracketRepair) -> Value {
+  let replacements = repair
+    .edits
+    .iter()
+    .map(|edit| match edit.kind {
+      BracketEditKind::Insert => json!({
+        "deletedRegion": { "charOffset": sentence_offset + edit.position, "charLength": 0 },
+        "insertedContent": { "text": edit.bracket.to_string() },
+      }),
+      BracketEditKind::Delete => json!({
+        "deletedRegion": { "charOffset": sentence_offset + edit.position, "charLength": 1 },
+      }),
+    })
+    .collect::<Vec<_>>();
+  json!({
+    "description": { "text": repair_description(repair) },
+    "artifactChanges": [{
+      "artifactLocation": { "uri": uri },
+      "replacements": replacements,
+    }],
+  })
+}
+
+/// 法令案の問題を、SARIFのログにする
+/// `uri`は法令案のファイルを、`text`はその内容を表す
+pub fn lint_to_sarif(uri: &str, text: &str, issues: &[LintIssue]) -> Value {
+  // 文ごとの、法令案の先頭からの位置
+  let mut sentence_offsets = Vec::new();
+  let mut offset = 0;
+  for sentence in split_sentences(text) {
+    sentence_offsets.push(offset);
+    offset += sentence.chars().count();
+  }
+  let results = issues
+    .iter()
+    .map(|issue| {
+      let sentence_offset = sentence_offsets
+        .get(issue.sentence_index)
+        .copied()
+        .unwrap_or_default();
+      let (char_offset, snippet) = match issue
+        .word
+        .as_ref()
+        .and_then(|word| issue.sentence.find(word.as_str()).map(|i| (i, word)))
+      {
+        Some((i, word)) => (sentence_offset + issue.sentence[..i].chars().count(), word),
+        None => (sentence_offset, &issue.sentence),
+      };
+      let fixes = issue
+        .repairs
+        .iter()
+        .map(|repair| repair_fix(uri, sentence_offset, repair))
+        .collect::<Vec<_>>();
+      json!({
+        "ruleId": issue.kind.as_str(),
+        "level": issue.severity.as_str(),
+        "message": { "text": issue.message },
+        "locations": [{
+          "physicalLocation": {
+            "artifactLocation": { "uri": uri },
+            "region": {
+              "charOffset": char_offset,
+              "charLength": snippet.chars().count(),
+              "snippet": { "text": snippet },
+            },
+          },
+        }],
+        "fixes": fixes,
+      })
+    })
+    .collect();
+  sarif_log(results)
+}
+
+/// 解析のエラーを、SARIFのログにする
+/// 条文についてのエラーは、元の法令XMLファイル（分からない場合は法律番号）と条項で位置を表し、エラーが起きた文を抜き出す
+/// 法令XMLの中での文字の位置は分からないため、鉤括弧の直し方の候補は`properties`の`bracketRepairs`に入れる
+pub fn errors_to_sarif(errors: &[YomikaeError]) -> Value {
+  let results = errors
+    .iter()
+    .map(|err| {
+      let rule_id = error_kind(err);
+      let mut result = json!({
+        "ruleId": rule_id,
+        "level": "error",
+        "message": { "text": rule_description(rule_id) },
+      });
+      match err {
+        YomikaeError::ContentsOfTable(law_info)
+        | YomikaeError::UnmatchedParen(law_info)
+        | YomikaeError::UnexpectedParallelWords(law_info)
+        | YomikaeError::UnmatchedSorezoreWords(law_info)
+        | YomikaeError::NotFoundYomikae(law_info)
+        | YomikaeError::InternalError(law_info, _) => {
+          let uri = law_info
+            .source_file
+            .as_deref()
+            .unwrap_or(law_info.num.as_str());
+          let mut physical_location = json!({ "artifactLocation": { "uri": uri } });
+          if let LawContents::Text(text) = &law_info.contents.contents {
+            let snippet = match law_info.sentence_index {
+              Some(i) => split_sentences(text).get(i).cloned(),
+              None => Some(text.clone()),
+            };
+            if let Some(snippet) = snippet {
+              physical_location["region"] = json!({ "snippet": { "text": snippet } });
+            }
+          }
+          let article = article_to_string(&law_info.article);
+          result["locations"] = json!([{
+            "physicalLocation": physical_location,
+            "logicalLocations": [{
+              "fullyQualifiedName": format!("{} {article}", law_info.num),
+              "kind": "module",
+            }],
+          }]);
+          if let YomikaeError::InternalError(_, msg) = err {
+            result["message"]["text"] = json!(format!("{}: {msg}", rule_description(rule_id)));
+          }
+          if !law_info.bracket_repairs.is_empty() {
+            result["properties"] = json!({ "bracketRepairs": law_info.bracket_repairs });
+          }
+        }
+        YomikaeError::ReadLawFile(info, msg)
+        | YomikaeError::ParseLawXml(info, msg)
+        | YomikaeError::AnalyzeLaw(info, msg) => {
+          result["message"]["text"] = json!(format!("{}: {msg}", rule_description(rule_id)));
+          result["locations"] = json!([{
+            "physicalLocation": { "artifactLocation": { "uri": info.file } },
+          }]);
+        }
+      }
+      result
+    })
+    .collect();
+  sarif_log(results)
+}
+
+#[test]
+fn check_lint_to_sarif() {
+  use crate::lint::lint_draft;
+  let text = "前条中「前項」とあるのは「第二項」と読み替える。次条中「前項」とあるのは第三項」と読み替える。";
+  let issues = lint_draft(text, None, &crate::ParserConfig::default());
+  let sarif = lint_to_sarif("draft.txt", text, &issues);
+  assert_eq!(SARIF_VERSION, sarif["version"]);
+  let results = sarif["runs"][0]["results"].as_array().unwrap();
+  assert_eq!(1, results.len());
+  assert_eq!("unbalanced_brackets", results[0]["ruleId"]);
+  assert_eq!("error", results[0]["level"]);
+  let region = &results[0]["locations"][0]["physicalLocation"]["region"];
+  assert_eq!(
+    json!("前条中「前項」とあるのは「第二項」と読み替える。"
+      .chars()
+      .count()),
+    region["charOffset"]
+  );
+  let replacement = &results[0]["fixes"][0]["artifactChanges"][0]["replacements"][0];
+  assert_eq!(
+    json!(
+      "前条中「前項」とあるのは「第二項」と読み替える。次条中「前項」とあるのは"
+        .chars()
+        .count()
+    ),
+    replacement["deletedRegion"]["charOffset"]
+  );
+  assert_eq!("「", replacement["insertedContent"]["text"]);
+  let rules = sarif["runs"][0]["tool"]["driver"]["rules"]
+    .as_array()
+    .unwrap();
+  assert_eq!(1, rules.len());
+}
+
+#[test]
+fn check_errors_to_sarif() {
+  use crate::{test_utils, LawInfo};
+  let law_text = test_utils::law_text("前条の規定を準用する。「前項」とあるのは「次項」とする。");
+  let law_info = LawInfo {
+    sentence_index: Some(1),
+    ..LawInfo::new("test", test_utils::test_article(), law_text)
+  }
+  .with_source("test.xml");
+  let sarif = errors_to_sarif(&[YomikaeError::NotFoundYomikae(law_info)]);
+  let result = &sarif["runs"][0]["results"][0];
+  assert_eq!("not_found_yomikae", result["ruleId"]);
+  let location = &result["locations"][0];
+  assert_eq!(
+    "test.xml",
+    location["physicalLocation"]["artifactLocation"]["uri"]
+  );
+  assert_eq!(
+    "「前項」とあるのは「次項」とする。",
+    location["physicalLocation"]["region"]["snippet"]["text"]
+  );
+}
@@ -0,0 +1,339 @@
+//! 法令XMLのスキーマの違いを判別する
+//!
+//! 法令XMLのスナップショットによって、ルート要素やスキーマのバージョンが少しずつ異なる
+//! e-Gov法令API（Version 1）の応答のように`Law`要素が別の要素に包まれている場合は、
+//! `Law`要素だけを取り出してからjplaw_textに渡す
+//!
+//! 告示や規則などには、`Law`要素を持たずに`LawBody`要素を別の要素で包んでいるものや、
+//! 条に分かれておらず条番号の無いものがある
+//! `LawBody`要素は`Law`要素で包み直し、条番号の無い条文には[`FALLBACK_ARTICLE_LABEL`]を付けて、同じように解析できるようにする
+
+use jplaw_text::LawText;
+use quick_xml::{events::Event, Reader};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// 判別した法令XMLのスキーマ
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LawXmlSchema {
+  /// ルート要素が`Law`である法令標準XMLスキーマ
+  /// `version`は`xsi:noNamespaceSchemaLocation`などで参照しているスキーマファイルの名前
+  Standard { version: Option<String> },
+  /// `DataRoot`の中に`Law`要素があるe-Gov法令API（Version 1）の応答
+  EgovApiV1,
+  /// `Law`要素を持たず、`LawBody`要素を別の要素で包んでいる告示などの文書（ルート要素の名前）
+  Wrapped(String),
+  /// 判別できなかったXML（ルート要素の名前）
+  Unknown(String),
+}
+
+impl LawXmlSchema {
+  /// jplaw_textで解析できる形式かどうか
+  pub fn is_supported(&self) -> bool {
+    !matches!(self, LawXmlSchema::Unknown(_))
+  }
+}
+
+/// 法令XMLが表す文書の種類
+/// `Law`要素の`LawType`属性から判別し、属性が無い場合や`Misc`の場合は法令番号の書き方から判別する
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum DocumentType {
+  /// 憲法
+  Constitution,
+  /// 法律
+  Act,
+  /// 政令
+  CabinetOrder,
+  /// 勅令
+  ImperialOrder,
+  /// 府令・省令
+  MinisterialOrdinance,
+  /// 規則
+  Rule,
+  /// 告示
+  Notice,
+  /// その他
+  #[default]
+  Misc,
+}
+
+/// 条番号の無い条文に付ける条の名前
+pub const FALLBACK_ARTICLE_LABEL: &str = "本則";
+
+/// スキーマの場所の属性の値から、スキーマファイルの名前を取り出す
+fn schema_file_name(value: &str) -> Option<String> {
+  value
+    .split_whitespace()
+    .last()
+    .and_then(|location| location.rsplit('/').next())
+    .filter(|name| !name.is_empty())
+    .map(|name| name.to_string())
+}
+
+/// ルート要素から法令XMLのスキーマを判別する
+pub fn detect_schema(xml: &[u8]) -> LawXmlSchema {
+  let mut reader = Reader::from_reader(xml);
+  let mut buf = Vec::new();
+  loop {
+    match reader.read_event_into(&mut buf) {
+      Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
+        let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+        return match name.as_str() {
+          "Law" => {
+            let version = e
+              .attributes()
+              .filter_map(|attr| attr.ok())
+              .find(|attr| {
+                matches!(
+                  attr.key.as_ref(),
+                  b"xsi:noNamespaceSchemaLocation" | b"xsi:schemaLocation"
+                )
+              })
+              .and_then(|attr| schema_file_name(&String::from_utf8_lossy(&attr.value)));
+            LawXmlSchema::Standard { version }
+          }
+          "DataRoot" => LawXmlSchema::EgovApiV1,
+          _ => LawXmlSchema::Unknown(name),
+        };
+      }
+      Ok(Event::Eof) | Err(_) => return LawXmlSchema::Unknown(String::new()),
+      _ => (),
+    }
+    buf.clear();
+  }
+}
+
+/// `<name`から最後の`</name>`までを取り出す
+fn extract_element<'a>(xml: &'a [u8], name: &str) -> Option<&'a [u8]> {
+  let start_tag = format!("<{name}");
+  let start = xml.windows(start_tag.len() + 1).position(|w| {
+    w.starts_with(start_tag.as_bytes())
+      && matches!(
+        w[start_tag.len()],
+        b' ' | b'>' | b'/' | b'\t' | b'\r' | b'\n'
+      )
+  })?;
+  let end_tag = format!("</{name}>");
+  let end = xml
+    .windows(end_tag.len())
+    .rposition(|w| w == end_tag.as_bytes())?
+    + end_tag.len();
+  (start < end).then(|| &xml[start..end])
+}
+
+/// 最初の`name`要素の中の文字列を取り出す
+fn element_text(xml: &[u8], name: &str) -> Option<String> {
+  let mut reader = Reader::from_reader(xml);
+  let mut buf = Vec::new();
+  let mut is_inside = false;
+  let mut text = String::new();
+  loop {
+    match reader.read_event_into(&mut buf) {
+      Ok(Event::Start(e)) if e.name().as_ref() == name.as_bytes() => is_inside = true,
+      Ok(Event::Text(e)) if is_inside => text.push_str(&e.unescape().ok()?),
+      Ok(Event::End(e)) if e.name().as_ref() == name.as_bytes() => {
+        return Some(text.trim().to_string()).filter(|text| !text.is_empty())
+      }
+      Ok(Event::Eof) | Err(_) => return None,
+      _ => (),
+    }
+    buf.clear();
+  }
+}
+
+/// 最初の`Law`要素の属性を取り出す
+pub(crate) fn law_element_attrs(xml: &[u8]) -> HashMap<String, String> {
+  let mut reader = Reader::from_reader(xml);
+  let mut buf = Vec::new();
+  loop {
+    match reader.read_event_into(&mut buf) {
+      Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name().as_ref() == b"Law" => {
+        return e
+          .attributes()
+          .filter_map(|attr| attr.ok())
+          .map(|attr| {
+            (
+              String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+              String::from_utf8_lossy(&attr.value).to_string(),
+            )
+          })
+          .collect()
+      }
+      Ok(Event::Eof) | Err(_) => return HashMap::new(),
+      _ => (),
+    }
+    buf.clear();
+  }
+}
+
+/// 法令XMLの`LawNum`要素に書かれた法令番号（「平成十年厚生省告示第百号」など）を取り出す
+/// インデックス情報に無い文書の法律番号の代わりに使う
+pub fn law_num(xml: &[u8]) -> Option<String> {
+  element_text(xml, "LawNum")
+}
+
+/// 法令XMLの`LawTitle`要素に書かれた法令名を取り出す
+/// ルビの読みも文字列に含まれるので、[`crate::ruby::strip_ruby_readings`]で取り除いた法令XMLを渡す
+pub fn law_title(xml: &[u8]) -> Option<String> {
+  element_text(xml, "LawTitle")
+}
+
+/// 法令番号の書き方から文書の種類を判別する
+fn document_type_from_law_num(law_num: &str) -> DocumentType {
+  if law_num.contains("告示") {
+    DocumentType::Notice
+  } else if law_num.contains("規則") {
+    DocumentType::Rule
+  } else if law_num.contains("省令") || law_num.contains("府令") {
+    DocumentType::MinisterialOrdinance
+  } else if law_num.contains("政令") {
+    DocumentType::CabinetOrder
+  } else if law_num.contains("勅令") {
+    DocumentType::ImperialOrder
+  } else if law_num.contains("法律") {
+    DocumentType::Act
+  } else {
+    DocumentType::Misc
+  }
+}
+
+/// 法令XMLが表す文書の種類を判別する
+pub fn detect_document_type(xml: &[u8]) -> DocumentType {
+  let document_type = match law_element_attrs(xml).get("LawType").map(|s| s.as_str()) {
+    Some("Constitution") => DocumentType::Constitution,
+    Some("Act") => DocumentType::Act,
+    Some("CabinetOrder") => DocumentType::CabinetOrder,
+    Some("ImperialOrder") => DocumentType::ImperialOrder,
+    Some("MinisterialOrdinance") => DocumentType::MinisterialOrdinance,
+    Some("Rule") => DocumentType::Rule,
+    _ => DocumentType::Misc,
+  };
+  if document_type == DocumentType::Misc {
+    law_num(xml)
+      .map(|law_num| document_type_from_law_num(&law_num))
+      .unwrap_or_default()
+  } else {
+    document_type
+  }
+}
+
+/// 条番号の無い条文に、条の名前として[`FALLBACK_ARTICLE_LABEL`]を付ける
+/// 名前を付けた条文の数を返す
+pub fn label_unnumbered_articles(law_text_lst: Vec<LawText>) -> (Vec<LawText>, usize) {
+  let mut count = 0;
+  let law_text_lst = law_text_lst
+    .into_iter()
+    .map(|mut law_text| {
+      if law_text.article_info.article.is_empty() {
+        law_text.article_info.article = FALLBACK_ARTICLE_LABEL.to_string();
+        count += 1;
+      }
+      law_text
+    })
+    .collect();
+  (law_text_lst, count)
+}
+
+/// スキーマを判別し、jplaw_textに渡せる形の法令XMLにする
+pub fn normalize_law_xml(xml: &[u8]) -> (LawXmlSchema, Cow<'_, [u8]>) {
+  let schema = detect_schema(xml);
+  match &schema {
+    LawXmlSchema::EgovApiV1 => match extract_element(xml, "Law") {
+      Some(law) => {
+        let mut normalized = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n".to_vec();
+        normalized.extend_from_slice(law);
+        (schema, Cow::Owned(normalized))
+      }
+      None => (
+        LawXmlSchema::Unknown("DataRoot".to_string()),
+        Cow::Borrowed(xml),
+      ),
+    },
+    LawXmlSchema::Unknown(root) => match extract_element(xml, "LawBody") {
+      Some(law_body) => {
+        let mut normalized = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Law>".to_vec();
+        if let Some(law_num) = extract_element(xml, "LawNum") {
+          normalized.extend_from_slice(law_num);
+        }
+        normalized.extend_from_slice(law_body);
+        normalized.extend_from_slice(b"</Law>");
+        (LawXmlSchema::Wrapped(root.clone()), Cow::Owned(normalized))
+      }
+      None => (schema, Cow::Borrowed(xml)),
+    },
+    _ => (schema, Cow::Borrowed(xml)),
+  }
+}
+
+#[test]
+fn check_detect_schema() {
+  let standard = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Law Era="Heisei" Lang="ja" LawType="Act" Num="088" Year="05" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:noNamespaceSchemaLocation="https://example.com/XMLSchemaForJapaneseLaw_v3.xsd"><LawNum>平成五年法律第八十八号</LawNum></Law>"#;
+  assert_eq!(
+    LawXmlSchema::Standard {
+      version: Some("XMLSchemaForJapaneseLaw_v3.xsd".to_string())
+    },
+    detect_schema(standard.as_bytes())
+  );
+  let api_v1 = r#"<?xml version="1.0" encoding="UTF-8"?>
+<DataRoot><Result><Code>0</Code></Result><ApplData><LawFullText><Law Era="Heisei" Lang="ja"><LawNum>平成五年法律第八十八号</LawNum></Law></LawFullText></ApplData></DataRoot>"#;
+  let (schema, normalized) = normalize_law_xml(api_v1.as_bytes());
+  assert_eq!(LawXmlSchema::EgovApiV1, schema);
+  assert!(normalized.ends_with(
+    r#"<Law Era="Heisei" Lang="ja"><LawNum>平成五年法律第八十八号</LawNum></Law>"#.as_bytes()
+  ));
+  assert_eq!(
+    LawXmlSchema::Unknown("Html".to_string()),
+    detect_schema(b"<Html></Html>")
+  );
+}
+
+#[test]
+fn check_wrapped_notice() {
+  let notice = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Notice><LawNum>平成十年厚生省告示第百号</LawNum><LawBody><LawTitle>テスト告示</LawTitle><MainProvision><Paragraph Num="1"><ParagraphNum/><ParagraphSentence><Sentence>本文</Sentence></ParagraphSentence></Paragraph></MainProvision></LawBody></Notice>"#;
+  let (schema, normalized) = normalize_law_xml(notice.as_bytes());
+  assert_eq!(LawXmlSchema::Wrapped("Notice".to_string()), schema);
+  assert!(schema.is_supported());
+  assert!(normalized.ends_with(b"</MainProvision></LawBody></Law>"));
+  assert_eq!(
+    LawXmlSchema::Standard { version: None },
+    detect_schema(&normalized)
+  );
+  assert_eq!(
+    Some("平成十年厚生省告示第百号".to_string()),
+    law_num(notice.as_bytes())
+  );
+  assert_eq!(Some("テスト告示".to_string()), law_title(notice.as_bytes()));
+  assert_eq!(
+    DocumentType::Notice,
+    detect_document_type(notice.as_bytes())
+  );
+  let act = r#"<Law LawType="Act"><LawNum>平成五年法律第八十八号</LawNum></Law>"#;
+  assert_eq!(DocumentType::Act, detect_document_type(act.as_bytes()));
+  let misc = r#"<Law LawType="Misc"><LawNum>昭和二十四年人事院規則一―〇</LawNum></Law>"#;
+  assert_eq!(DocumentType::Rule, detect_document_type(misc.as_bytes()));
+}
+
+#[test]
+fn check_label_unnumbered_articles() {
+  use crate::ArticleExt;
+  use jplaw_text::{Article, LawContents};
+  let law_text_lst = vec![
+    LawText {
+      article_info: Article::article(""),
+      contents: LawContents::Text("本文".to_string()),
+    },
+    LawText {
+      article_info: Article::article("1"),
+      contents: LawContents::Text("本文".to_string()),
+    },
+  ];
+  let (law_text_lst, count) = label_unnumbered_articles(law_text_lst);
+  assert_eq!(1, count);
+  assert_eq!(FALLBACK_ARTICLE_LABEL, law_text_lst[0].article_info.article);
+  assert_eq!("1", law_text_lst[1].article_info.article);
+}
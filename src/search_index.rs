@@ -0,0 +1,227 @@
+//! 解析結果の読み替え前後の語を全文検索するためのインデックス
+//!
+//! `tantivy` featureを有効にすると使えるようになる
+//!
+//! 日本語の語は空白で区切られていないため、1文字と2文字のn-gramでインデックスを作り、
+//! 検索で見つかったものから実際に語を含むものだけを返す
+
+use crate::{article_to_string, YomikaeData, YomikaeInfo};
+use std::path::Path;
+use tantivy::{
+  collector::TopDocs,
+  doc,
+  query::{BooleanQuery, Occur, Query, TermQuery},
+  schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, STORED, STRING,
+  },
+  tokenizer::{NgramTokenizer, TextAnalyzer},
+  Index, IndexWriter, TantivyDocument, TantivyError, Term,
+};
+use thiserror::Error;
+
+/// 読み替え前後の語に使うトークナイザの名前
+const TOKENIZER_NAME: &str = "ja_ngram";
+
+/// インデックスの作成や検索で起きるエラー
+#[derive(Debug, Error)]
+pub enum SearchIndexError {
+  #[error(transparent)]
+  Tantivy(#[from] TantivyError),
+  #[error(transparent)]
+  Json(#[from] serde_json::Error),
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+}
+
+/// 検索する対象の語
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SearchTarget {
+  /// 読み替え前の語
+  Before,
+  /// 読み替え後の語
+  After,
+  /// 読み替え前後のどちらか
+  #[default]
+  Any,
+}
+
+impl std::str::FromStr for SearchTarget {
+  type Err = String;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "before" => Ok(SearchTarget::Before),
+      "after" => Ok(SearchTarget::After),
+      "any" => Ok(SearchTarget::Any),
+      _ => Err(format!(
+        "unknown search target: {s} (expected \"before\", \"after\" or \"any\")"
+      )),
+    }
+  }
+}
+
+impl SearchTarget {
+  fn is_match(&self, yomikae_info: &YomikaeInfo, word: &str) -> bool {
+    let is_before_match = || yomikae_info.before_words.iter().any(|s| s.contains(word));
+    let is_after_match = || yomikae_info.after_word.contains(word);
+    match self {
+      SearchTarget::Before => is_before_match(),
+      SearchTarget::After => is_after_match(),
+      SearchTarget::Any => is_before_match() || is_after_match(),
+    }
+  }
+}
+
+struct Fields {
+  num: Field,
+  article: Field,
+  before: Field,
+  after: Field,
+  data: Field,
+}
+
+fn schema() -> (Schema, Fields) {
+  let word_options = TextOptions::default().set_indexing_options(
+    TextFieldIndexing::default()
+      .set_tokenizer(TOKENIZER_NAME)
+      .set_index_option(IndexRecordOption::Basic),
+  );
+  let mut builder = Schema::builder();
+  let fields = Fields {
+    num: builder.add_text_field("num", STRING | STORED),
+    article: builder.add_text_field("article", STRING | STORED),
+    before: builder.add_text_field("before", word_options.clone()),
+    after: builder.add_text_field("after", word_options),
+    data: builder.add_text_field("data", STORED),
+  };
+  (builder.build(), fields)
+}
+
+fn register_tokenizer(index: &Index) -> Result<(), SearchIndexError> {
+  let analyzer = TextAnalyzer::from(NgramTokenizer::new(1, 2, false)?);
+  index.tokenizers().register(TOKENIZER_NAME, analyzer);
+  Ok(())
+}
+
+/// 解析結果からインデックスを作ってディレクトリに保存する
+pub fn build_index(dir: &Path, yomikae_data_lst: &[YomikaeData]) -> Result<(), SearchIndexError> {
+  std::fs::create_dir_all(dir)?;
+  let (schema, fields) = schema();
+  let index = Index::create_in_dir(dir, schema)?;
+  register_tokenizer(&index)?;
+  let mut writer: IndexWriter = index.writer(50_000_000)?;
+  for yomikae_data in yomikae_data_lst {
+    let mut document = doc!(
+      fields.num => yomikae_data.num.to_string(),
+      fields.article => article_to_string(&yomikae_data.article),
+      fields.data => serde_json::to_string(yomikae_data)?,
+    );
+    for yomikae_info in yomikae_data.data.iter() {
+      for before_word in yomikae_info.before_words.iter() {
+        document.add_text(fields.before, before_word);
+      }
+      document.add_text(fields.after, &yomikae_info.after_word);
+    }
+    writer.add_document(document)?;
+  }
+  writer.commit()?;
+  Ok(())
+}
+
+/// 語を1文字または2文字ずつに分けて、全て含むものを探すクエリを作る
+fn word_query(field: Field, word: &str) -> Box<dyn Query> {
+  let chars = word.chars().collect::<Vec<_>>();
+  let grams = if chars.len() < 2 {
+    vec![word.to_string()]
+  } else {
+    chars
+      .windows(2)
+      .map(|w| w.iter().collect::<String>())
+      .collect()
+  };
+  let queries = grams
+    .into_iter()
+    .map(|gram| {
+      let query: Box<dyn Query> = Box::new(TermQuery::new(
+        Term::from_field_text(field, &gram),
+        IndexRecordOption::Basic,
+      ));
+      (Occur::Must, query)
+    })
+    .collect();
+  Box::new(BooleanQuery::new(queries))
+}
+
+/// インデックスから語を含む読み替えを探す
+/// 見つかった解析結果には、その語を含む読み替え前後の語の組だけを残す
+pub fn search(
+  dir: &Path,
+  word: &str,
+  target: SearchTarget,
+  limit: usize,
+) -> Result<Vec<YomikaeData>, SearchIndexError> {
+  let index = Index::open_in_dir(dir)?;
+  register_tokenizer(&index)?;
+  let (_, fields) = schema();
+  let query: Box<dyn Query> = match target {
+    SearchTarget::Before => word_query(fields.before, word),
+    SearchTarget::After => word_query(fields.after, word),
+    SearchTarget::Any => Box::new(BooleanQuery::new(vec![
+      (Occur::Should, word_query(fields.before, word)),
+      (Occur::Should, word_query(fields.after, word)),
+    ])),
+  };
+  let searcher = index.reader()?.searcher();
+  let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+  let mut yomikae_data_lst = Vec::new();
+  for (_, doc_address) in top_docs {
+    let document = searcher.doc::<TantivyDocument>(doc_address)?;
+    let data = document
+      .get_first(fields.data)
+      .and_then(|v| v.as_str())
+      .unwrap_or_default();
+    let mut yomikae_data: YomikaeData = serde_json::from_str(data)?;
+    yomikae_data
+      .data
+      .retain(|yomikae_info| target.is_match(yomikae_info, word));
+    if !yomikae_data.data.is_empty() {
+      yomikae_data_lst.push(yomikae_data);
+    }
+  }
+  Ok(yomikae_data_lst)
+}
+
+#[test]
+fn check_search() {
+  use crate::ArticleExt;
+  use jplaw_text::Article;
+  let dir = std::env::temp_dir().join(format!("analysis_yomikae_index_{}", std::process::id()));
+  let _ = std::fs::remove_dir_all(&dir);
+  let yomikae_data_lst = vec![
+    YomikaeData::new(
+      "a",
+      Article::article("1"),
+      vec![
+        YomikaeInfo::new(["都道府県知事"], "市町村長"),
+        YomikaeInfo::new(["前条"], "第一条"),
+      ],
+    ),
+    YomikaeData::new(
+      "b",
+      Article::article("2"),
+      vec![YomikaeInfo::new(["市町村長"], "都道府県知事")],
+    ),
+  ];
+  build_index(&dir, &yomikae_data_lst).unwrap();
+  let found = search(&dir, "都道府県知事", SearchTarget::Before, 10).unwrap();
+  assert_eq!(
+    vec![YomikaeData::new(
+      "a",
+      Article::article("1"),
+      vec![YomikaeInfo::new(["都道府県知事"], "市町村長")],
+    )],
+    found
+  );
+  let found = search(&dir, "都道府県知事", SearchTarget::Any, 10).unwrap();
+  assert_eq!(2, found.len());
+  std::fs::remove_dir_all(&dir).unwrap();
+}
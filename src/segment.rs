@@ -0,0 +1,218 @@
+//! 文を一番外側の鉤括弧で括られた部分とそれ以外の部分に分割する
+//! また、条文を一文ずつに区切る
+//!
+//! 分割した結果は[`tokenize_yomikae_sentence`]で公開し、独自の取り出し方を組み立てられるようにする
+
+use crate::repair::{suggest_bracket_repairs, BracketRepair};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// 文を鉤括弧を基準に分割したときの一つの部分
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "text", rename_all = "snake_case")]
+pub enum Segment {
+  /// 一番外側の鉤括弧で括られた部分（外側の鉤括弧自体は含まない）
+  Quoted(String),
+  /// 鉤括弧の外側の部分（「とあるのは」「と、」のような語をつなぐ文字列）
+  Text(String),
+}
+
+impl Segment {
+  /// 鉤括弧で括られた部分かどうか
+  pub fn is_quoted(&self) -> bool {
+    matches!(self, Segment::Quoted(_))
+  }
+
+  /// 部分の文字列
+  pub fn as_str(&self) -> &str {
+    match self {
+      Segment::Quoted(s) | Segment::Text(s) => s,
+    }
+  }
+}
+
+/// 鉤括弧の対応が取れていないため、文を分割できなかった
+#[derive(Debug, Error, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[error("Unmatched brackets in {sentence:?}")]
+pub struct UnmatchedBrackets {
+  /// 分割しようとした文
+  pub sentence: String,
+  /// 鉤括弧の対応を取るための直し方の候補（[`suggest_bracket_repairs`]）
+  pub repairs: Vec<BracketRepair>,
+}
+
+/// 読み替え規定文を、一番外側の鉤括弧で括られた部分と、語をつなぐ鉤括弧の外側の部分に分割する
+/// [`parse_yomikae`](crate::parse_yomikae)が順に読む単位と同じもので、並びの順は文の中の順になる
+/// 鉤括弧の対応が取れていない場合は、鉤括弧の直し方の候補を付けたエラーを返す
+pub fn tokenize_yomikae_sentence(sentence: &str) -> Result<Vec<Segment>, UnmatchedBrackets> {
+  split_segments(sentence).ok_or_else(|| UnmatchedBrackets {
+    sentence: sentence.to_string(),
+    repairs: suggest_bracket_repairs(sentence),
+  })
+}
+
+/// 文を鉤括弧で括られた部分とそれ以外の部分に分割する
+/// 鉤括弧の対応が取れていない場合は`None`を返す
+pub(crate) fn split_segments(input: &str) -> Option<Vec<Segment>> {
+  let mut segments = Vec::new();
+  let mut depth: usize = 0;
+  let mut buf = String::new();
+  for c in input.chars() {
+    match c {
+      '「' => {
+        if depth == 0 {
+          if !buf.is_empty() {
+            segments.push(Segment::Text(std::mem::take(&mut buf)));
+          }
+        } else {
+          // 鉤括弧内の鉤括弧は単語の一部として扱う
+          buf.push(c);
+        }
+        depth += 1;
+      }
+      '」' => {
+        match depth {
+          0 => return None,
+          1 => segments.push(Segment::Quoted(std::mem::take(&mut buf))),
+          _ => buf.push(c),
+        }
+        depth -= 1;
+      }
+      _ => buf.push(c),
+    }
+  }
+  if depth != 0 {
+    return None;
+  }
+  if !buf.is_empty() {
+    segments.push(Segment::Text(buf));
+  }
+  Some(segments)
+}
+
+/// 鉤括弧で括られた単語と、その直後にある鉤括弧の外側の文字列の組のリストにする
+/// 最初の鉤括弧より前にある文字列は捨てる
+pub(crate) fn quoted_with_following_text(segments: &[Segment]) -> Vec<(String, String)> {
+  let mut lst = Vec::new();
+  for (i, segment) in segments.iter().enumerate() {
+    if let Segment::Quoted(word) = segment {
+      let text = match segments.get(i + 1) {
+        Some(Segment::Text(text)) => text.clone(),
+        _ => String::new(),
+      };
+      lst.push((word.clone(), text));
+    }
+  }
+  lst
+}
+
+/// 鉤括弧で括られた部分の中身を取り除き、「「」とあるのは「」と読み替える」のように鉤括弧だけを残す
+/// 鉤括弧の中に引用された文言で、文全体の種類を判定しないようにするために使う
+/// 鉤括弧の対応が取れていなくても、開いている間の文字を取り除くだけでそのまま続ける
+pub(crate) fn mask_quoted(input: &str) -> String {
+  let mut masked = String::new();
+  let mut depth: usize = 0;
+  for c in input.chars() {
+    match c {
+      '「' => {
+        if depth == 0 {
+          masked.push(c);
+        }
+        depth += 1;
+      }
+      '」' => {
+        depth = depth.saturating_sub(1);
+        if depth == 0 {
+          masked.push(c);
+        }
+      }
+      _ if depth == 0 => masked.push(c),
+      _ => (),
+    }
+  }
+  masked
+}
+
+/// 条文を、鉤括弧や括弧の外側にある「。」で一文ずつに区切る
+/// 区切りの「。」は前の文に含める
+pub(crate) fn split_sentences(input: &str) -> Vec<String> {
+  let mut sentences = Vec::new();
+  let mut depth: usize = 0;
+  let mut buf = String::new();
+  for c in input.chars() {
+    buf.push(c);
+    match c {
+      '「' | '（' => depth += 1,
+      '」' | '）' => depth = depth.saturating_sub(1),
+      '。' if depth == 0 => sentences.push(std::mem::take(&mut buf)),
+      _ => (),
+    }
+  }
+  if !buf.is_empty() {
+    sentences.push(buf);
+  }
+  sentences
+}
+
+#[test]
+fn check_mask_quoted() {
+  assert_eq!(
+    "第一条中「」とあるのは「」と読み替える。",
+    mask_quoted("第一条中「前条」とあるのは「「次項」と読み替える」と読み替える。")
+  );
+  assert_eq!(
+    "前条中「」を改める。",
+    mask_quoted("前条中「と読み替える」を改める。")
+  );
+}
+
+#[test]
+fn check_split_sentences() {
+  assert_eq!(
+    vec![
+      "第一条中「前条（以下「旧条」という。）」とあるのは「次条」と読み替える。",
+      "前条の規定を準用する。",
+      "ただし書",
+    ],
+    split_sentences(
+      "第一条中「前条（以下「旧条」という。）」とあるのは「次条」と読み替える。前条の規定を準用する。ただし書"
+    )
+  );
+}
+
+#[test]
+fn check_split_segments() {
+  assert_eq!(
+    Some(vec![
+      Segment::Text("第一条中".to_string()),
+      Segment::Quoted("前条（以下「旧条」という。）".to_string()),
+      Segment::Text("を".to_string()),
+      Segment::Quoted("次条".to_string()),
+      Segment::Text("に改める。".to_string()),
+    ]),
+    split_segments("第一条中「前条（以下「旧条」という。）」を「次条」に改める。")
+  );
+  assert_eq!(None, split_segments("第一条中「前条」」を削る。"));
+  assert_eq!(None, split_segments("第一条中「前条を削る。"));
+}
+
+#[test]
+fn check_tokenize_yomikae_sentence() {
+  let segments =
+    tokenize_yomikae_sentence("同条中「前項」とあるのは「次項」と読み替える。").unwrap();
+  assert_eq!(
+    vec![
+      Segment::Text("同条中".to_string()),
+      Segment::Quoted("前項".to_string()),
+      Segment::Text("とあるのは".to_string()),
+      Segment::Quoted("次項".to_string()),
+      Segment::Text("と読み替える。".to_string()),
+    ],
+    segments
+  );
+  assert!(segments[1].is_quoted());
+  assert_eq!("とあるのは", segments[2].as_str());
+  let err = tokenize_yomikae_sentence("「前項」とあるのは「次項と読み替える。").unwrap_err();
+  assert_eq!("「前項」とあるのは「次項と読み替える。", err.sentence);
+  assert!(!err.repairs.is_empty());
+}
@@ -0,0 +1,185 @@
+//! 読み替え規定の解析と解析結果の検索をHTTPで提供するサーバー
+//!
+//! `server` featureを有効にすると使えるようになる
+//!
+//! - `POST /sentence`：JSONで送られた文を解析して[`YomikaeInfo`]のリストを返す
+//! - `POST /law?num=法律番号`：送られた法令XMLを解析して[`LawResponse`]を返す
+//! - `GET /data?num=法律番号&word=語`：読み込んだ解析結果から条件に合うものを返す
+//! - `GET /data/:num`：読み込んだ解析結果からその法律番号のものを返す
+
+use crate::{
+  batch::parse_law_xml, parse_yomikae, ArticleExt, YomikaeData, YomikaeError, YomikaeInfo,
+};
+use axum::{
+  body::Bytes,
+  extract::{Path, Query, State},
+  http::StatusCode,
+  routing::{get, post},
+  Json, Router,
+};
+use jplaw_text::{Article, LawContents, LawText};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// `POST /sentence`で送る文
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentenceRequest {
+  /// 解析する文
+  pub text: String,
+  /// 法律番号
+  #[serde(default)]
+  pub num: String,
+  /// 文がある条項（省略した場合は空の条）
+  #[serde(default)]
+  pub article: Option<Article>,
+}
+
+/// `POST /law`で送る法令XMLの法律番号
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LawQuery {
+  /// 法律番号
+  #[serde(default)]
+  pub num: String,
+}
+
+/// `POST /law`の結果
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LawResponse {
+  /// 解析結果
+  pub data: Vec<YomikaeData>,
+  /// 解析に失敗した条文のエラー
+  pub errors: Vec<YomikaeError>,
+}
+
+/// `GET /data`の検索条件
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DataQuery {
+  /// 法律番号が一致するもの
+  pub num: Option<String>,
+  /// 読み替え前後の語にこの語を含むもの
+  pub word: Option<String>,
+}
+
+/// 検索の対象にする解析結果
+type Dataset = Arc<Vec<YomikaeData>>;
+
+/// サーバーのルーティングを作る
+pub fn router(dataset: Vec<YomikaeData>) -> Router {
+  Router::new()
+    .route("/sentence", post(post_sentence))
+    .route("/law", post(post_law))
+    .route("/data", get(get_data))
+    .route("/data/:num", get(get_data_by_num))
+    .with_state(Arc::new(dataset))
+}
+
+async fn post_sentence(
+  Json(req): Json<SentenceRequest>,
+) -> Result<Json<Vec<YomikaeInfo>>, (StatusCode, Json<YomikaeError>)> {
+  let article = req.article.unwrap_or_else(|| Article::article(""));
+  let law_text = LawText {
+    article_info: article.clone(),
+    contents: LawContents::Text(req.text),
+  };
+  parse_yomikae(&law_text, &req.num, &article)
+    .map(Json)
+    .map_err(|err| (StatusCode::UNPROCESSABLE_ENTITY, Json(err)))
+}
+
+async fn post_law(
+  Query(query): Query<LawQuery>,
+  body: Bytes,
+) -> Result<Json<LawResponse>, (StatusCode, Json<YomikaeError>)> {
+  let law_parse_result = parse_law_xml(&query.num, &body)
+    .await
+    .map_err(|err| (StatusCode::BAD_REQUEST, Json(err)))?;
+  Ok(Json(LawResponse {
+    data: law_parse_result.data,
+    errors: law_parse_result.errors,
+  }))
+}
+
+/// 検索条件に合うかどうか
+fn is_match(yomikae_data: &YomikaeData, query: &DataQuery) -> bool {
+  let is_num_match = match &query.num {
+    Some(num) => &yomikae_data.num == num,
+    None => true,
+  };
+  let is_word_match = match &query.word {
+    Some(word) => yomikae_data.data.iter().any(|yomikae_info| {
+      yomikae_info.after_word.contains(word.as_str())
+        || yomikae_info
+          .before_words
+          .iter()
+          .any(|s| s.contains(word.as_str()))
+    }),
+    None => true,
+  };
+  is_num_match && is_word_match
+}
+
+async fn get_data(
+  State(dataset): State<Dataset>,
+  Query(query): Query<DataQuery>,
+) -> Json<Vec<YomikaeData>> {
+  Json(
+    dataset
+      .iter()
+      .filter(|yomikae_data| is_match(yomikae_data, &query))
+      .cloned()
+      .collect(),
+  )
+}
+
+async fn get_data_by_num(
+  State(dataset): State<Dataset>,
+  Path(num): Path<String>,
+) -> Json<Vec<YomikaeData>> {
+  let query = DataQuery {
+    num: Some(num),
+    word: None,
+  };
+  get_data(State(dataset), Query(query)).await
+}
+
+#[tokio::test]
+async fn check_post_sentence() {
+  let req = SentenceRequest {
+    text: crate::test_utils::SENTENCE_SINGLE.to_string(),
+    num: "test".to_string(),
+    article: None,
+  };
+  let Json(yomikae_info_lst) = post_sentence(Json(req)).await.unwrap();
+  assert_eq!(
+    vec![YomikaeInfo::new(
+      ["被後見人を代表する"],
+      "被保佐人を代表し、又は被保佐人がこれをすることに同意する"
+    )
+    .with_target(crate::TargetRef::provision("第八百五十一条第四号"))],
+    yomikae_info_lst
+  );
+}
+
+#[tokio::test]
+async fn check_get_data() {
+  let dataset = Arc::new(vec![
+    YomikaeData::new(
+      "a",
+      Article::article("1"),
+      vec![YomikaeInfo::new(["前条"], "第一条")],
+    ),
+    YomikaeData::new(
+      "b",
+      Article::article("1"),
+      vec![YomikaeInfo::new(["前項"], "第二項")],
+    ),
+  ]);
+  let query = DataQuery {
+    num: None,
+    word: Some("前条".to_string()),
+  };
+  let Json(lst) = get_data(State(dataset.clone()), Query(query)).await;
+  assert_eq!(vec![dataset[0].clone()], lst);
+  let Json(lst) = get_data_by_num(State(dataset.clone()), Path("b".to_string())).await;
+  assert_eq!(vec![dataset[1].clone()], lst);
+}
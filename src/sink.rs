@@ -0,0 +1,965 @@
+//! 解析結果とエラーを書き出す先
+//!
+//! `async` featureを有効にすると使えるようになる
+//!
+//! [`OutputSink`]を実装すれば、CLIと同じ流れで独自の形式や保存先に書き出せる
+//! 組み込みの形式として、JSON・NDJSON・CSV・SQLite（`sqlite` featureが必要）がある
+//!
+//! JSON・NDJSON・CSVでは、[`KeyCase::Camel`]を指定するとキーや列の名前をcamelCaseにできる
+//! 変わるのはこのクレートの構造体のフィールド名だけで、マップのキーとjplaw_textの型のフィールド名はそのままになる
+//! camelCaseで書き出したものも、snake_caseのものと同じように読み込める
+//! JSON・NDJSONでは、`flatten`を指定すると解析結果を[`FlatYomikaeRecord`]の形で読み替え前の語一つごとに書き出す
+//!
+//! JSON・NDJSON・CSVは、[`OutputSink::offsets`]で記録したバイト数まで切り詰めて続きから書き出せる（[`resume_sink`]）
+//!
+//! [`CombinedSink`]を使うと、解析結果・エラー・警告を一つのJSONファイルにまとめて書き出せる
+
+use crate::field_case::{to_camel_case, to_camel_case_value};
+use crate::{
+  article_to_string, error_key::error_kind, flatten_yomikae_data, FlatYomikaeRecord, Message,
+  YomikaeData, YomikaeError,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::io::SeekFrom;
+use std::path::Path;
+use std::str::FromStr;
+use thiserror::Error;
+use tokio::{
+  fs::{File, OpenOptions},
+  io::{AsyncSeekExt, AsyncWriteExt},
+};
+
+/// 書き出しで起きるエラー
+#[derive(Debug, Error)]
+pub enum SinkError {
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+  #[error(transparent)]
+  Json(#[from] serde_json::Error),
+  #[cfg(feature = "sqlite")]
+  #[error(transparent)]
+  Sqlite(#[from] rusqlite::Error),
+  #[error("{0} output requires the `{0}` feature")]
+  FeatureDisabled(&'static str),
+  #[error("{0} output cannot be resumed from a checkpoint")]
+  ResumeUnsupported(&'static str),
+}
+
+/// 解析結果とエラーの出力ファイルに書き出したバイト数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct SinkOffsets {
+  /// 解析結果の出力ファイル
+  pub output: u64,
+  /// エラーの出力ファイル
+  pub error_output: u64,
+}
+
+/// 解析結果とエラーの書き出し先
+#[async_trait]
+pub trait OutputSink: Send {
+  /// 解析結果を一つ書き出す
+  async fn write_record(&mut self, yomikae_data: &YomikaeData) -> Result<(), SinkError>;
+  /// エラーを一つ書き出す
+  async fn write_error(&mut self, err: &YomikaeError) -> Result<(), SinkError>;
+  /// 警告を一つ書き出す
+  /// 警告の書き出し先がない形式では何もしない
+  async fn write_warning(&mut self, _message: &Message) -> Result<(), SinkError> {
+    Ok(())
+  }
+  /// 書き出しを終える
+  /// これを呼び出すまでは出力が完全なものにならない場合がある
+  async fn finish(&mut self) -> Result<(), SinkError>;
+  /// それまでに書き出した内容をファイルに反映して、書き出したバイト数を返す
+  /// 続きから書き出すことに対応していない形式では`None`を返す
+  async fn offsets(&mut self) -> Result<Option<SinkOffsets>, SinkError> {
+    Ok(None)
+  }
+}
+
+/// 組み込みの出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OutputFormat {
+  /// 解析結果とエラーのそれぞれを一つのJSONの配列にする
+  #[default]
+  Json,
+  /// 一行に一つずつJSONを書く
+  Ndjson,
+  /// 解析結果は読み替えの組ごと、エラーは一件ごとに一行のCSVにする
+  Csv,
+  /// 解析結果とエラーを一つのSQLiteのデータベースの`yomikae`表と`errors`表に入れる
+  Sqlite,
+}
+
+impl FromStr for OutputFormat {
+  type Err = String;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "json" => Ok(OutputFormat::Json),
+      "ndjson" => Ok(OutputFormat::Ndjson),
+      "csv" => Ok(OutputFormat::Csv),
+      "sqlite" => Ok(OutputFormat::Sqlite),
+      _ => Err(format!(
+        "unknown output format: {s} (expected \"json\", \"ndjson\", \"csv\" or \"sqlite\")"
+      )),
+    }
+  }
+}
+
+/// 出力するキーの名前の形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum KeyCase {
+  /// `before_words`のようなsnake_case（構造体のフィールド名のまま）
+  #[default]
+  Snake,
+  /// `beforeWords`のようなcamelCase
+  Camel,
+}
+
+impl FromStr for KeyCase {
+  type Err = String;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "snake" => Ok(KeyCase::Snake),
+      "camel" => Ok(KeyCase::Camel),
+      _ => Err(format!(
+        "unknown key case: {s} (expected \"snake\" or \"camel\")"
+      )),
+    }
+  }
+}
+
+impl KeyCase {
+  /// snake_caseの名前をこの形式にする
+  pub fn convert(&self, name: &str) -> String {
+    match self {
+      KeyCase::Snake => name.to_string(),
+      KeyCase::Camel => to_camel_case(name),
+    }
+  }
+
+  /// 構造体のフィールド名をこの形式にしたJSON文字列にする
+  /// マップのキーはデータなので、この形式によらずそのままにする
+  pub fn to_json_string<T: Serialize + ?Sized>(
+    &self,
+    value: &T,
+  ) -> Result<String, serde_json::Error> {
+    match self {
+      KeyCase::Snake => serde_json::to_string(value),
+      KeyCase::Camel => serde_json::to_string(&to_camel_case_value(value)?),
+    }
+  }
+}
+
+/// 指定した形式の書き出し先を作る
+/// SQLiteの場合は`error_output`を使わず、エラーも`output`のデータベースに入れる
+/// SQLiteの列の名前は`key_case`によらずsnake_caseになる
+/// CSVとSQLiteは元から読み替えの組ごとに一行なので、`flatten`によらず同じ形になる
+pub async fn create_sink(
+  format: OutputFormat,
+  key_case: KeyCase,
+  flatten: bool,
+  output: impl AsRef<Path>,
+  error_output: impl AsRef<Path>,
+) -> Result<Box<dyn OutputSink>, SinkError> {
+  match format {
+    OutputFormat::Json => Ok(Box::new(
+      JsonSink::create(output, error_output)
+        .await?
+        .with_key_case(key_case)
+        .with_flatten(flatten),
+    )),
+    OutputFormat::Ndjson => Ok(Box::new(
+      NdjsonSink::create(output, error_output)
+        .await?
+        .with_key_case(key_case)
+        .with_flatten(flatten),
+    )),
+    OutputFormat::Csv => Ok(Box::new(
+      CsvSink::create_with_key_case(output, error_output, key_case).await?,
+    )),
+    #[cfg(feature = "sqlite")]
+    OutputFormat::Sqlite => Ok(Box::new(SqliteSink::create(output)?)),
+    #[cfg(not(feature = "sqlite"))]
+    OutputFormat::Sqlite => Err(SinkError::FeatureDisabled("sqlite")),
+  }
+}
+
+/// 指定した形式で、記録したバイト数まで切り詰めた出力ファイルの続きから書き出す書き出し先を作る
+/// SQLiteと、全て書き終えてから書き出す[`CombinedSink`]は続きから書き出せない
+pub async fn resume_sink(
+  format: OutputFormat,
+  key_case: KeyCase,
+  flatten: bool,
+  output: impl AsRef<Path>,
+  error_output: impl AsRef<Path>,
+  offsets: SinkOffsets,
+) -> Result<Box<dyn OutputSink>, SinkError> {
+  match format {
+    OutputFormat::Json => Ok(Box::new(
+      JsonSink::resume(output, error_output, offsets)
+        .await?
+        .with_key_case(key_case)
+        .with_flatten(flatten),
+    )),
+    OutputFormat::Ndjson => Ok(Box::new(
+      NdjsonSink::resume(output, error_output, offsets)
+        .await?
+        .with_key_case(key_case)
+        .with_flatten(flatten),
+    )),
+    OutputFormat::Csv => Ok(Box::new(
+      CsvSink::resume(output, error_output, offsets).await?,
+    )),
+    OutputFormat::Sqlite => Err(SinkError::ResumeUnsupported("sqlite")),
+  }
+}
+
+/// 既存のファイルを`offset`バイトまで切り詰めて、その続きから書き出せるように開く
+async fn open_truncated(path: impl AsRef<Path>, offset: u64) -> Result<File, SinkError> {
+  let mut file = OpenOptions::new().write(true).open(path).await?;
+  file.set_len(offset).await?;
+  file.seek(SeekFrom::Start(offset)).await?;
+  Ok(file)
+}
+
+/// 書き出した内容をファイルに反映して、書き出したバイト数を返す
+async fn file_offset(file: &mut File) -> Result<u64, SinkError> {
+  file.flush().await?;
+  Ok(file.stream_position().await?)
+}
+
+/// JSONの配列に要素を一つ書き足す
+async fn write_json_element(
+  file: &mut File,
+  is_head: &mut bool,
+  json_str: &str,
+) -> Result<(), SinkError> {
+  if *is_head {
+    file.write_all("\n".as_bytes()).await?;
+    *is_head = false;
+  } else {
+    file.write_all(",\n".as_bytes()).await?;
+  }
+  file.write_all(json_str.as_bytes()).await?;
+  Ok(())
+}
+
+/// 解析結果とエラーのそれぞれを一つのJSONの配列にして書き出す
+#[derive(Debug)]
+pub struct JsonSink {
+  output_file: File,
+  error_output_file: File,
+  is_head: bool,
+  is_error_head: bool,
+  key_case: KeyCase,
+  flatten: bool,
+}
+
+impl JsonSink {
+  pub async fn create(
+    output: impl AsRef<Path>,
+    error_output: impl AsRef<Path>,
+  ) -> Result<Self, SinkError> {
+    let mut output_file = File::create(output).await?;
+    output_file.write_all("[".as_bytes()).await?;
+    let mut error_output_file = File::create(error_output).await?;
+    error_output_file.write_all("[".as_bytes()).await?;
+    Ok(JsonSink {
+      output_file,
+      error_output_file,
+      is_head: true,
+      is_error_head: true,
+      key_case: KeyCase::Snake,
+      flatten: false,
+    })
+  }
+
+  /// 記録したバイト数まで切り詰めて、その続きから配列の要素を書き足す
+  pub async fn resume(
+    output: impl AsRef<Path>,
+    error_output: impl AsRef<Path>,
+    offsets: SinkOffsets,
+  ) -> Result<Self, SinkError> {
+    // 先頭の「[」の後に何も書き出していなければ、次の要素が配列の最初の要素になる
+    Ok(JsonSink {
+      output_file: open_truncated(output, offsets.output).await?,
+      error_output_file: open_truncated(error_output, offsets.error_output).await?,
+      is_head: offsets.output <= 1,
+      is_error_head: offsets.error_output <= 1,
+      key_case: KeyCase::Snake,
+      flatten: false,
+    })
+  }
+
+  /// キーの名前の形式を指定する
+  pub fn with_key_case(self, key_case: KeyCase) -> Self {
+    JsonSink { key_case, ..self }
+  }
+
+  /// 解析結果を読み替え前の語一つごとの平坦な形で書き出すかどうかを指定する
+  pub fn with_flatten(self, flatten: bool) -> Self {
+    JsonSink { flatten, ..self }
+  }
+}
+
+#[async_trait]
+impl OutputSink for JsonSink {
+  async fn write_record(&mut self, yomikae_data: &YomikaeData) -> Result<(), SinkError> {
+    if self.flatten {
+      for record in flatten_yomikae_data(yomikae_data) {
+        let json_str = self.key_case.to_json_string(&record)?;
+        write_json_element(&mut self.output_file, &mut self.is_head, &json_str).await?;
+      }
+      return Ok(());
+    }
+    let json_str = self.key_case.to_json_string(yomikae_data)?;
+    write_json_element(&mut self.output_file, &mut self.is_head, &json_str).await
+  }
+  async fn write_error(&mut self, err: &YomikaeError) -> Result<(), SinkError> {
+    let json_str = self.key_case.to_json_string(err)?;
+    write_json_element(
+      &mut self.error_output_file,
+      &mut self.is_error_head,
+      &json_str,
+    )
+    .await
+  }
+  async fn finish(&mut self) -> Result<(), SinkError> {
+    self.output_file.write_all("\n]".as_bytes()).await?;
+    self.output_file.flush().await?;
+    self.error_output_file.write_all("\n]".as_bytes()).await?;
+    self.error_output_file.flush().await?;
+    Ok(())
+  }
+  async fn offsets(&mut self) -> Result<Option<SinkOffsets>, SinkError> {
+    Ok(Some(SinkOffsets {
+      output: file_offset(&mut self.output_file).await?,
+      error_output: file_offset(&mut self.error_output_file).await?,
+    }))
+  }
+}
+
+/// 解析結果とエラーを一行に一つずつJSONで書き出す
+#[derive(Debug)]
+pub struct NdjsonSink {
+  output_file: File,
+  error_output_file: File,
+  key_case: KeyCase,
+  flatten: bool,
+}
+
+impl NdjsonSink {
+  pub async fn create(
+    output: impl AsRef<Path>,
+    error_output: impl AsRef<Path>,
+  ) -> Result<Self, SinkError> {
+    Ok(NdjsonSink {
+      output_file: File::create(output).await?,
+      error_output_file: File::create(error_output).await?,
+      key_case: KeyCase::Snake,
+      flatten: false,
+    })
+  }
+
+  /// 記録したバイト数まで切り詰めて、その続きの行から書き出す
+  pub async fn resume(
+    output: impl AsRef<Path>,
+    error_output: impl AsRef<Path>,
+    offsets: SinkOffsets,
+  ) -> Result<Self, SinkError> {
+    Ok(NdjsonSink {
+      output_file: open_truncated(output, offsets.output).await?,
+      error_output_file: open_truncated(error_output, offsets.error_output).await?,
+      key_case: KeyCase::Snake,
+      flatten: false,
+    })
+  }
+
+  /// キーの名前の形式を指定する
+  pub fn with_key_case(self, key_case: KeyCase) -> Self {
+    NdjsonSink { key_case, ..self }
+  }
+
+  /// 解析結果を読み替え前の語一つごとの平坦な形で書き出すかどうかを指定する
+  pub fn with_flatten(self, flatten: bool) -> Self {
+    NdjsonSink { flatten, ..self }
+  }
+}
+
+#[async_trait]
+impl OutputSink for NdjsonSink {
+  async fn write_record(&mut self, yomikae_data: &YomikaeData) -> Result<(), SinkError> {
+    let json_str_lst = if self.flatten {
+      flatten_yomikae_data(yomikae_data)
+        .iter()
+        .map(|record| self.key_case.to_json_string(record))
+        .collect::<Result<Vec<_>, _>>()?
+    } else {
+      vec![self.key_case.to_json_string(yomikae_data)?]
+    };
+    for json_str in json_str_lst {
+      self
+        .output_file
+        .write_all(format!("{json_str}\n").as_bytes())
+        .await?;
+    }
+    Ok(())
+  }
+  async fn write_error(&mut self, err: &YomikaeError) -> Result<(), SinkError> {
+    let json_str = self.key_case.to_json_string(err)?;
+    self
+      .error_output_file
+      .write_all(format!("{json_str}\n").as_bytes())
+      .await?;
+    Ok(())
+  }
+  async fn finish(&mut self) -> Result<(), SinkError> {
+    self.output_file.flush().await?;
+    self.error_output_file.flush().await?;
+    Ok(())
+  }
+  async fn offsets(&mut self) -> Result<Option<SinkOffsets>, SinkError> {
+    Ok(Some(SinkOffsets {
+      output: file_offset(&mut self.output_file).await?,
+      error_output: file_offset(&mut self.error_output_file).await?,
+    }))
+  }
+}
+
+/// [`CombinedSink`]で書き出すJSONファイルの`meta`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CombinedMeta {
+  /// 出力したソフトウェアのバージョン
+  pub version: String,
+  /// `results`の件数
+  #[serde(alias = "resultCount")]
+  pub result_count: usize,
+  /// `errors`の件数
+  #[serde(alias = "errorCount")]
+  pub error_count: usize,
+  /// `warnings`の件数
+  #[serde(alias = "warningCount")]
+  pub warning_count: usize,
+}
+
+/// 解析結果・エラー・警告を`{ meta, results, errors, warnings }`の形の一つのJSONファイルにまとめて書き出す
+///
+/// 全て書き終えてから`meta`の件数が決まるので、[`OutputSink::finish`]を呼び出すまでは何も書き出さない
+#[derive(Debug)]
+pub struct CombinedSink {
+  output_file: File,
+  results: Vec<String>,
+  errors: Vec<String>,
+  warnings: Vec<String>,
+  key_case: KeyCase,
+  flatten: bool,
+}
+
+impl CombinedSink {
+  pub async fn create(output: impl AsRef<Path>) -> Result<Self, SinkError> {
+    Ok(CombinedSink {
+      output_file: File::create(output).await?,
+      results: Vec::new(),
+      errors: Vec::new(),
+      warnings: Vec::new(),
+      key_case: KeyCase::Snake,
+      flatten: false,
+    })
+  }
+
+  /// キーの名前の形式を指定する
+  pub fn with_key_case(self, key_case: KeyCase) -> Self {
+    CombinedSink { key_case, ..self }
+  }
+
+  /// 解析結果を読み替え前の語一つごとの平坦な形で書き出すかどうかを指定する
+  pub fn with_flatten(self, flatten: bool) -> Self {
+    CombinedSink { flatten, ..self }
+  }
+}
+
+/// JSON文字列を並べて配列にする
+fn json_array(json_str_lst: &[String]) -> String {
+  if json_str_lst.is_empty() {
+    "[]".to_string()
+  } else {
+    format!("[\n{}\n]", json_str_lst.join(",\n"))
+  }
+}
+
+#[async_trait]
+impl OutputSink for CombinedSink {
+  async fn write_record(&mut self, yomikae_data: &YomikaeData) -> Result<(), SinkError> {
+    if self.flatten {
+      for record in flatten_yomikae_data(yomikae_data) {
+        self.results.push(self.key_case.to_json_string(&record)?);
+      }
+    } else {
+      self
+        .results
+        .push(self.key_case.to_json_string(yomikae_data)?);
+    }
+    Ok(())
+  }
+  async fn write_error(&mut self, err: &YomikaeError) -> Result<(), SinkError> {
+    self.errors.push(self.key_case.to_json_string(err)?);
+    Ok(())
+  }
+  async fn write_warning(&mut self, message: &Message) -> Result<(), SinkError> {
+    self.warnings.push(self.key_case.to_json_string(message)?);
+    Ok(())
+  }
+  async fn finish(&mut self) -> Result<(), SinkError> {
+    let meta = CombinedMeta {
+      version: env!("CARGO_PKG_VERSION").to_string(),
+      result_count: self.results.len(),
+      error_count: self.errors.len(),
+      warning_count: self.warnings.len(),
+    };
+    let json_str = format!(
+      "{{\"meta\":{},\n\"results\":{},\n\"errors\":{},\n\"warnings\":{}}}\n",
+      self.key_case.to_json_string(&meta)?,
+      json_array(&self.results),
+      json_array(&self.errors),
+      json_array(&self.warnings)
+    );
+    self.output_file.write_all(json_str.as_bytes()).await?;
+    self.output_file.flush().await?;
+    Ok(())
+  }
+}
+
+/// CSVの一つの欄にする
+/// カンマ・ダブルクォート・改行を含む場合はダブルクォートで囲む
+fn csv_field(s: &str) -> String {
+  if s.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", s.replace('"', "\"\""))
+  } else {
+    s.to_string()
+  }
+}
+
+fn csv_line(fields: &[&str]) -> String {
+  let mut line = fields
+    .iter()
+    .map(|s| csv_field(s))
+    .collect::<Vec<_>>()
+    .join(",");
+  line.push('\n');
+  line
+}
+
+/// エラーが起きた法令の法律番号と、条項またはファイルの場所
+fn error_location(err: &YomikaeError) -> (String, String) {
+  match err {
+    YomikaeError::ContentsOfTable(law_info)
+    | YomikaeError::UnmatchedParen(law_info)
+    | YomikaeError::UnexpectedParallelWords(law_info)
+    | YomikaeError::UnmatchedSorezoreWords(law_info)
+    | YomikaeError::NotFoundYomikae(law_info)
+    | YomikaeError::InternalError(law_info, _) => (
+      law_info.num.to_string(),
+      article_to_string(&law_info.article),
+    ),
+    YomikaeError::ReadLawFile(law_file_info, _)
+    | YomikaeError::ParseLawXml(law_file_info, _)
+    | YomikaeError::AnalyzeLaw(law_file_info, _) => {
+      (law_file_info.num.clone(), law_file_info.file.clone())
+    }
+  }
+}
+
+/// 解析結果は読み替えの組ごと、エラーは一件ごとに一行のCSVにして書き出す
+///
+/// 読み替え前の語は複数ある場合があるので、JSONの配列にして一つの欄に入れる
+#[derive(Debug)]
+pub struct CsvSink {
+  output_file: File,
+  error_output_file: File,
+}
+
+impl CsvSink {
+  pub async fn create(
+    output: impl AsRef<Path>,
+    error_output: impl AsRef<Path>,
+  ) -> Result<Self, SinkError> {
+    Self::create_with_key_case(output, error_output, KeyCase::Snake).await
+  }
+
+  /// 見出しの行の列の名前の形式を指定して作る
+  pub async fn create_with_key_case(
+    output: impl AsRef<Path>,
+    error_output: impl AsRef<Path>,
+    key_case: KeyCase,
+  ) -> Result<Self, SinkError> {
+    let header = |names: &[&str]| {
+      let names = names
+        .iter()
+        .map(|s| key_case.convert(s))
+        .collect::<Vec<_>>();
+      csv_line(&names.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+    };
+    let mut output_file = File::create(output).await?;
+    output_file
+      .write_all(
+        header(&[
+          "num",
+          "article",
+          "before_words",
+          "after_word",
+          "operation",
+          "confidence",
+          "url",
+        ])
+        .as_bytes(),
+      )
+      .await?;
+    let mut error_output_file = File::create(error_output).await?;
+    error_output_file
+      .write_all(header(&["kind", "num", "location", "message"]).as_bytes())
+      .await?;
+    Ok(CsvSink {
+      output_file,
+      error_output_file,
+    })
+  }
+
+  /// 記録したバイト数まで切り詰めて、その続きの行から書き出す
+  /// 見出しの行はすでに書き出されているものとする
+  pub async fn resume(
+    output: impl AsRef<Path>,
+    error_output: impl AsRef<Path>,
+    offsets: SinkOffsets,
+  ) -> Result<Self, SinkError> {
+    Ok(CsvSink {
+      output_file: open_truncated(output, offsets.output).await?,
+      error_output_file: open_truncated(error_output, offsets.error_output).await?,
+    })
+  }
+}
+
+#[async_trait]
+impl OutputSink for CsvSink {
+  async fn write_record(&mut self, yomikae_data: &YomikaeData) -> Result<(), SinkError> {
+    let article = article_to_string(&yomikae_data.article);
+    let url = yomikae_data.url.clone().unwrap_or_default();
+    let mut lines = String::new();
+    for yomikae_info in yomikae_data.data.iter() {
+      let before_words = serde_json::to_string(&yomikae_info.before_words)?;
+      let operation = format!("{:?}", yomikae_info.operation);
+      let confidence = format!("{:?}", yomikae_info.confidence);
+      lines.push_str(&csv_line(&[
+        &yomikae_data.num,
+        &article,
+        &before_words,
+        &yomikae_info.after_word,
+        &operation,
+        &confidence,
+        &url,
+      ]));
+    }
+    self.output_file.write_all(lines.as_bytes()).await?;
+    Ok(())
+  }
+  async fn write_error(&mut self, err: &YomikaeError) -> Result<(), SinkError> {
+    let (num, location) = error_location(err);
+    let line = csv_line(&[error_kind(err), &num, &location, &err.to_string()]);
+    self.error_output_file.write_all(line.as_bytes()).await?;
+    Ok(())
+  }
+  async fn finish(&mut self) -> Result<(), SinkError> {
+    self.output_file.flush().await?;
+    self.error_output_file.flush().await?;
+    Ok(())
+  }
+  async fn offsets(&mut self) -> Result<Option<SinkOffsets>, SinkError> {
+    Ok(Some(SinkOffsets {
+      output: file_offset(&mut self.output_file).await?,
+      error_output: file_offset(&mut self.error_output_file).await?,
+    }))
+  }
+}
+
+/// 解析結果とエラーを一つのSQLiteのデータベースに入れる
+///
+/// `sqlite` featureを有効にすると使えるようになる
+/// 書き込みは一つのトランザクションで行い、[`OutputSink::finish`]でコミットする
+/// 複数の時点の法令XMLの解析結果を一つのデータベースに入れても版が混ざらないように、
+/// 解析結果ごとに[`crate::RevisionKey`]の法令ID・版・内容のハッシュ値も入れる
+#[cfg(feature = "sqlite")]
+#[derive(Debug)]
+pub struct SqliteSink {
+  conn: rusqlite::Connection,
+}
+
+#[cfg(feature = "sqlite")]
+impl SqliteSink {
+  pub fn create(path: impl AsRef<Path>) -> Result<Self, SinkError> {
+    let conn = rusqlite::Connection::open(path)?;
+    conn.execute_batch(
+      "CREATE TABLE IF NOT EXISTS yomikae (
+        num TEXT NOT NULL,
+        article TEXT NOT NULL,
+        article_json TEXT NOT NULL,
+        before_words TEXT NOT NULL,
+        after_word TEXT NOT NULL,
+        operation TEXT NOT NULL,
+        confidence TEXT NOT NULL,
+        url TEXT,
+        law_id TEXT NOT NULL,
+        revision TEXT,
+        content_hash TEXT
+      );
+      CREATE TABLE IF NOT EXISTS errors (
+        kind TEXT NOT NULL,
+        num TEXT NOT NULL,
+        location TEXT NOT NULL,
+        message TEXT NOT NULL,
+        error_json TEXT NOT NULL
+      );
+      BEGIN;",
+    )?;
+    Ok(SqliteSink { conn })
+  }
+}
+
+#[cfg(feature = "sqlite")]
+#[async_trait]
+impl OutputSink for SqliteSink {
+  async fn write_record(&mut self, yomikae_data: &YomikaeData) -> Result<(), SinkError> {
+    let article = article_to_string(&yomikae_data.article);
+    let article_json = serde_json::to_string(&yomikae_data.article)?;
+    let revision_key = yomikae_data.revision_key();
+    let mut stmt = self.conn.prepare_cached(
+      "INSERT INTO yomikae (num, article, article_json, before_words, after_word, operation, confidence, url, law_id, revision, content_hash)
+       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+    )?;
+    for yomikae_info in yomikae_data.data.iter() {
+      stmt.execute(rusqlite::params![
+        yomikae_data.num.as_str(),
+        article,
+        article_json,
+        serde_json::to_string(&yomikae_info.before_words)?,
+        yomikae_info.after_word,
+        format!("{:?}", yomikae_info.operation),
+        format!("{:?}", yomikae_info.confidence),
+        yomikae_data.url,
+        revision_key.law_id,
+        revision_key.revision,
+        revision_key.content_hash,
+      ])?;
+    }
+    Ok(())
+  }
+  async fn write_error(&mut self, err: &YomikaeError) -> Result<(), SinkError> {
+    let (num, location) = error_location(err);
+    self.conn.execute(
+      "INSERT INTO errors (kind, num, location, message, error_json) VALUES (?1, ?2, ?3, ?4, ?5)",
+      rusqlite::params![
+        error_kind(err),
+        num,
+        location,
+        err.to_string(),
+        serde_json::to_string(err)?,
+      ],
+    )?;
+    Ok(())
+  }
+  async fn finish(&mut self) -> Result<(), SinkError> {
+    self.conn.execute_batch("COMMIT;")?;
+    Ok(())
+  }
+}
+
+#[test]
+fn check_csv_field() {
+  assert_eq!("前条", csv_field("前条"));
+  assert_eq!("\"a,b\"", csv_field("a,b"));
+  assert_eq!("\"「\"\"」\"", csv_field("「\"」"));
+}
+
+#[tokio::test]
+async fn check_json_sink() {
+  use crate::{ArticleExt, YomikaeInfo};
+  use jplaw_text::Article;
+  let dir = std::env::temp_dir().join(format!("analysis_yomikae_sink_{}", std::process::id()));
+  std::fs::create_dir_all(&dir).unwrap();
+  let output = dir.join("output.json");
+  let error_output = dir.join("error.json");
+  let yomikae_data = YomikaeData::new(
+    "test",
+    Article::article("1"),
+    vec![YomikaeInfo::new(["前条"], "第一条")],
+  );
+  let mut sink = create_sink(
+    OutputFormat::Json,
+    KeyCase::Snake,
+    false,
+    &output,
+    &error_output,
+  )
+  .await
+  .unwrap();
+  sink.write_record(&yomikae_data).await.unwrap();
+  sink.write_record(&yomikae_data).await.unwrap();
+  sink.finish().await.unwrap();
+  let yomikae_data_lst: Vec<YomikaeData> =
+    serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+  assert_eq!(vec![yomikae_data.clone(), yomikae_data], yomikae_data_lst);
+  let error_lst: Vec<YomikaeError> =
+    serde_json::from_slice(&std::fs::read(&error_output).unwrap()).unwrap();
+  assert!(error_lst.is_empty());
+  let mut sink = create_sink(
+    OutputFormat::Ndjson,
+    KeyCase::Snake,
+    true,
+    &output,
+    &error_output,
+  )
+  .await
+  .unwrap();
+  sink.write_record(&yomikae_data).await.unwrap();
+  sink.finish().await.unwrap();
+  let record_lst = std::fs::read_to_string(&output)
+    .unwrap()
+    .lines()
+    .map(|line| serde_json::from_str(line).unwrap())
+    .collect::<Vec<FlatYomikaeRecord>>();
+  assert_eq!(flatten_yomikae_data(&yomikae_data), record_lst);
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn check_resume_sink() {
+  use crate::{ArticleExt, YomikaeInfo};
+  use jplaw_text::Article;
+  let dir = std::env::temp_dir().join(format!(
+    "analysis_yomikae_resume_sink_{}",
+    std::process::id()
+  ));
+  std::fs::create_dir_all(&dir).unwrap();
+  let output = dir.join("output.json");
+  let error_output = dir.join("error.json");
+  let yomikae_data = |num: &str| {
+    YomikaeData::new(
+      num,
+      Article::article("1"),
+      vec![YomikaeInfo::new(["前条"], "第一条")],
+    )
+  };
+  for format in [OutputFormat::Json, OutputFormat::Ndjson] {
+    let mut sink = create_sink(format, KeyCase::Snake, false, &output, &error_output)
+      .await
+      .unwrap();
+    sink.write_record(&yomikae_data("a")).await.unwrap();
+    let offsets = sink.offsets().await.unwrap().unwrap();
+    // チェックポイントの後に書き出したものは、再開したときに取り除かれる
+    sink.write_record(&yomikae_data("b")).await.unwrap();
+    sink.finish().await.unwrap();
+    let mut sink = resume_sink(
+      format,
+      KeyCase::Snake,
+      false,
+      &output,
+      &error_output,
+      offsets,
+    )
+    .await
+    .unwrap();
+    sink.write_record(&yomikae_data("c")).await.unwrap();
+    sink.finish().await.unwrap();
+    let yomikae_data_lst: Vec<YomikaeData> = match format {
+      OutputFormat::Json => serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap(),
+      _ => std::fs::read_to_string(&output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect(),
+    };
+    assert_eq!(vec![yomikae_data("a"), yomikae_data("c")], yomikae_data_lst);
+  }
+  assert!(matches!(
+    resume_sink(
+      OutputFormat::Sqlite,
+      KeyCase::Snake,
+      false,
+      &output,
+      &error_output,
+      SinkOffsets::default(),
+    )
+    .await,
+    Err(SinkError::ResumeUnsupported(_))
+  ));
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn check_combined_sink() {
+  use crate::{test_utils, ArticleExt, LawInfo, YomikaeInfo};
+  use jplaw_text::Article;
+  let dir = std::env::temp_dir().join(format!(
+    "analysis_yomikae_combined_sink_{}",
+    std::process::id()
+  ));
+  std::fs::create_dir_all(&dir).unwrap();
+  let output = dir.join("combined.json");
+  let yomikae_data = YomikaeData::new(
+    "test",
+    Article::article("1"),
+    vec![YomikaeInfo::new(["前条"], "第一条")],
+  );
+  let err = YomikaeError::NotFoundYomikae(LawInfo::new(
+    "test",
+    Article::article("2"),
+    test_utils::law_text("前条の規定を準用する。"),
+  ));
+  let message = Message::TableNotFound(Article::article("3"));
+  let mut sink = CombinedSink::create(&output).await.unwrap();
+  sink.write_record(&yomikae_data).await.unwrap();
+  sink.write_error(&err).await.unwrap();
+  sink.write_warning(&message).await.unwrap();
+  sink.finish().await.unwrap();
+  let combined: serde_json::Value =
+    serde_json::from_slice(&std::fs::read(&output).unwrap()).unwrap();
+  let meta: CombinedMeta = serde_json::from_value(combined["meta"].clone()).unwrap();
+  assert_eq!(
+    (1, 1, 1),
+    (meta.result_count, meta.error_count, meta.warning_count)
+  );
+  let yomikae_data_lst: Vec<YomikaeData> =
+    serde_json::from_value(combined["results"].clone()).unwrap();
+  assert_eq!(vec![yomikae_data], yomikae_data_lst);
+  let error_lst: Vec<YomikaeError> = serde_json::from_value(combined["errors"].clone()).unwrap();
+  assert_eq!(vec![err], error_lst);
+  let warning_lst: Vec<Message> = serde_json::from_value(combined["warnings"].clone()).unwrap();
+  assert_eq!(vec![message], warning_lst);
+  std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn check_key_case() {
+  use crate::{ArticleExt, YomikaeInfo};
+  use jplaw_text::Article;
+  assert_eq!("beforeWords", KeyCase::Camel.convert("before_words"));
+  assert_eq!(
+    "supplProvisionTitle",
+    KeyCase::Camel.convert("suppl_provision_title")
+  );
+  assert_eq!("before_words", KeyCase::Snake.convert("before_words"));
+  let yomikae_data = YomikaeData::new(
+    "test",
+    Article::article("1"),
+    vec![YomikaeInfo::new(["前条"], "第一条")],
+  );
+  let json_str = KeyCase::Camel.to_json_string(&yomikae_data).unwrap();
+  assert!(json_str.contains("\"beforeWords\":[\"前条\"]"));
+  assert!(json_str.contains("\"afterWord\":\"第一条\""));
+  // jplaw_textの型のフィールド名はそのまま
+  assert!(json_str.contains("\"suppl_provision_title\""));
+  // camelCaseで書き出したものも読み込める
+  assert_eq!(
+    yomikae_data,
+    serde_json::from_str::<YomikaeData>(&json_str).unwrap()
+  );
+}
@@ -0,0 +1,137 @@
+//! jplaw_textの構造体を作らずに、文字列や文字列の表をそのまま解析するためのトレイト
+
+use crate::{parse_yomikae, YomikaeError, YomikaeInfo};
+use jplaw_text::{Article, LawContents, LawText};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+
+/// [`YomikaeSource`]から条文の内容を作るときや、[`parse_yomikae_source`]で解析するときのエラー
+#[derive(Debug, Error, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SourceError {
+  /// 各行の欄の文字列から表を作れなかった
+  #[error("Failed to build table contents: {0}")]
+  TableContents(String),
+  /// 解析に失敗した
+  #[error(transparent)]
+  Parse(#[from] YomikaeError),
+}
+
+/// 読み替え規定の解析の入力にできるもの
+///
+/// 文（`str`・`String`）、表の各行の欄の文字列（`[Vec<String>]`など）、
+/// jplaw_textの[`LawContents`]と[`LawText`]に実装している
+pub trait YomikaeSource {
+  /// 解析する条文の内容
+  fn to_law_contents(&self) -> Result<LawContents, SourceError>;
+}
+
+impl YomikaeSource for str {
+  fn to_law_contents(&self) -> Result<LawContents, SourceError> {
+    Ok(LawContents::Text(self.to_string()))
+  }
+}
+
+impl YomikaeSource for String {
+  fn to_law_contents(&self) -> Result<LawContents, SourceError> {
+    self.as_str().to_law_contents()
+  }
+}
+
+/// 各行の欄の文字列から表を作る
+/// jplaw_textの表の欄は文字列以外の情報も持つので、シリアライズした形から作る
+fn table_contents<'a, S: AsRef<str> + 'a>(
+  rows: impl Iterator<Item = &'a [S]>,
+) -> Result<LawContents, SourceError> {
+  let rows = rows
+    .map(|row| {
+      let columns = row
+        .iter()
+        .map(|s| json!({ "contents": { "Text": s.as_ref() } }))
+        .collect::<Vec<_>>();
+      json!({ "row": columns })
+    })
+    .collect::<Vec<_>>();
+  serde_json::from_value(json!({ "Table": rows }))
+    .map_err(|e| SourceError::TableContents(e.to_string()))
+}
+
+impl<S: AsRef<str>> YomikaeSource for [&[S]] {
+  fn to_law_contents(&self) -> Result<LawContents, SourceError> {
+    table_contents(self.iter().copied())
+  }
+}
+
+impl<S: AsRef<str>> YomikaeSource for [Vec<S>] {
+  fn to_law_contents(&self) -> Result<LawContents, SourceError> {
+    table_contents(self.iter().map(|row| row.as_slice()))
+  }
+}
+
+impl<S: AsRef<str>> YomikaeSource for Vec<Vec<S>> {
+  fn to_law_contents(&self) -> Result<LawContents, SourceError> {
+    self.as_slice().to_law_contents()
+  }
+}
+
+impl YomikaeSource for LawContents {
+  fn to_law_contents(&self) -> Result<LawContents, SourceError> {
+    Ok(self.clone())
+  }
+}
+
+impl YomikaeSource for LawText {
+  fn to_law_contents(&self) -> Result<LawContents, SourceError> {
+    Ok(self.contents.clone())
+  }
+}
+
+/// [`YomikaeSource`]を実装したものを読み替え規定文または読み替えの表として解析する
+pub fn parse_yomikae_source<S: YomikaeSource + ?Sized>(
+  source: &S,
+  num: &str,
+  article: &Article,
+) -> Result<Vec<YomikaeInfo>, SourceError> {
+  let law_text = LawText {
+    article_info: article.clone(),
+    contents: source.to_law_contents()?,
+  };
+  parse_yomikae(&law_text, num, article).map_err(SourceError::Parse)
+}
+
+#[test]
+fn check_parse_yomikae_source() {
+  use crate::{ArticleExt, TableCellRef, TargetRef};
+  let article = Article::article("1");
+  let yomikae_info_lst = parse_yomikae_source(
+    "この場合において、前条中「前項」とあるのは「第二項」と読み替えるものとする。",
+    "test",
+    &article,
+  )
+  .unwrap();
+  assert_eq!(
+    vec![YomikaeInfo::new(["前項"], "第二項").with_target(TargetRef::provision("前条"))],
+    yomikae_info_lst
+  );
+  let table = vec![vec!["第二条", "前条", "第一条"]];
+  let yomikae_info_lst = parse_yomikae_source(&table, "test", &article).unwrap();
+  assert_eq!(
+    vec![YomikaeInfo {
+      table_cell: Some(TableCellRef {
+        row: 0,
+        before_column: 1,
+        after_column: 2,
+      }),
+      ..YomikaeInfo::new(["前条"], "第一条")
+    }],
+    yomikae_info_lst
+  );
+  assert!(matches!(
+    parse_yomikae_source(
+      "「前項」とあるのは「次項」」と読み替える。",
+      "test",
+      &article
+    ),
+    Err(SourceError::Parse(YomikaeError::UnmatchedParen(_)))
+  ));
+}
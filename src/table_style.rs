@@ -0,0 +1,117 @@
+//! 読み替えの内容を文ではなく表で示す読み替え規定文を見分ける
+//!
+//! 「それぞれ同表の下欄に掲げる字句と読み替える」「同表の下欄の字句に読み替える」
+//! 「同表の第三欄に掲げる字句と読み替える」のように、読み替え後の字句が書かれた欄を示す形になっている
+
+use crate::order::parse_kansuji;
+use crate::segment::mask_quoted;
+use serde::{Deserialize, Serialize};
+
+/// 読み替えの表で、読み替え後の字句が書かれている欄
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TableDirection {
+  /// 下欄
+  Lower,
+  /// 右欄
+  Right,
+  /// 左欄
+  Left,
+  /// 「第三欄」のように番号で示された欄（1始まり）
+  Numbered(u64),
+}
+
+/// 欄の名前と「読み替える」の間に置かれる語
+const COLUMN_OBJECTS: &[&str] = &[
+  "に掲げる字句",
+  "の字句",
+  "に掲げる日又は月",
+  "に掲げる日",
+  "に掲げる規定",
+  "に掲げる字句等",
+  "に掲げる用語",
+];
+
+/// 文が表で読み替えると書かれた読み替え規定文であれば、読み替え後の字句が書かれている欄を返す
+/// 鉤括弧の中に引用された文言は見ない
+pub fn detect_table_style(s: &str) -> Option<TableDirection> {
+  let masked = mask_quoted(s);
+  for (i, _) in masked.match_indices("読み替え") {
+    let before = &masked[..i];
+    let before = match before
+      .strip_suffix('と')
+      .or_else(|| before.strip_suffix('に'))
+    {
+      Some(before) => before,
+      None => continue,
+    };
+    for object in COLUMN_OBJECTS {
+      if let Some(column) = before
+        .strip_suffix(object)
+        .and_then(|s| s.strip_suffix('欄'))
+      {
+        if let Some(direction) = column_direction(column) {
+          return Some(direction);
+        }
+      }
+    }
+  }
+  None
+}
+
+/// 「欄」の直前までの文字列から欄の種類を取り出す
+fn column_direction(column: &str) -> Option<TableDirection> {
+  if column.ends_with('下') {
+    return Some(TableDirection::Lower);
+  }
+  if column.ends_with('右') {
+    return Some(TableDirection::Right);
+  }
+  if column.ends_with('左') {
+    return Some(TableDirection::Left);
+  }
+  let (head, num) = column.split_at(
+    column
+      .char_indices()
+      .rev()
+      .take_while(|(_, c)| "〇一二三四五六七八九十百".contains(*c))
+      .last()
+      .map(|(i, _)| i)?,
+  );
+  if head.ends_with('第') {
+    parse_kansuji(num).map(TableDirection::Numbered)
+  } else {
+    None
+  }
+}
+
+#[test]
+fn check_detect_table_style() {
+  use crate::test_utils;
+  assert_eq!(
+    Some(TableDirection::Lower),
+    detect_table_style(test_utils::SENTENCE_TABLE)
+  );
+  assert_eq!(
+    Some(TableDirection::Lower),
+    detect_table_style("この場合において、次の表の上欄に掲げる同法の規定中同表の中欄に掲げる字句は、それぞれ同表の下欄の字句に読み替えるものとする。")
+  );
+  assert_eq!(
+    Some(TableDirection::Lower),
+    detect_table_style(
+      "次の表の上欄に掲げる期間は、それぞれ同表の下欄に掲げる日又は月と読み替えるものとする。"
+    )
+  );
+  assert_eq!(
+    Some(TableDirection::Numbered(3)),
+    detect_table_style("次の表の第一欄に掲げる規定中同表の第二欄に掲げる字句は、それぞれ同表の第三欄に掲げる字句と読み替えるものとする。")
+  );
+  assert_eq!(
+    Some(TableDirection::Right),
+    detect_table_style("左欄に掲げる字句は、それぞれ右欄に掲げる字句と読み替えるものとする。")
+  );
+  assert_eq!(None, detect_table_style(test_utils::SENTENCE_SINGLE));
+  assert_eq!(
+    None,
+    detect_table_style("第三条中「同表の下欄に掲げる字句と読み替える」を削る。")
+  );
+}
@@ -0,0 +1,224 @@
+//! 読み替え規定文の「同条第一項中」「別表第二の特定疾病の項中」のような、読み替えの対象となる規定を取り出す
+
+use crate::qualifier::{strip_trailing_paren, LAW_NAME_SUFFIXES};
+use crate::CONJUNCTIONS;
+use serde::{Deserialize, Serialize};
+
+/// 読み替えの対象の種類
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum TargetKind {
+  /// 条・項・号などの規定
+  #[default]
+  Provision,
+  /// 表、またはその項
+  Table,
+}
+
+/// 読み替えの対象の範囲の広さ
+#[derive(
+  Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub enum ScopeGranularity {
+  /// 「第三条第二項中」のように、書かれた箇所の中だけを対象にする
+  #[default]
+  Location,
+  /// 「第三条の規定中」のように、規定全体の文言を対象にする
+  WholeProvision,
+}
+
+/// 読み替えの対象となる規定
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TargetRef {
+  /// 対象の法令の名前（「同法」「徴収法施行規則」など。書かれていない場合は`None`）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub law: Option<String>,
+  /// 対象の規定（「第八百五十一条第四号」「同条」「別表第二」など）
+  pub provision: String,
+  /// 対象の種類
+  #[serde(default)]
+  pub kind: TargetKind,
+  /// 表の項が対象の場合は、その項の名前（「別表第二の特定疾病の項中」の「特定疾病」）
+  #[serde(default, alias = "tableRow", skip_serializing_if = "Option::is_none")]
+  pub table_row: Option<String>,
+  /// 対象の範囲の広さ
+  #[serde(default)]
+  pub granularity: ScopeGranularity,
+}
+
+impl TargetRef {
+  /// 条・項・号などの規定を対象にする
+  pub fn provision(provision: impl Into<String>) -> Self {
+    TargetRef {
+      law: None,
+      provision: provision.into(),
+      kind: TargetKind::Provision,
+      table_row: None,
+      granularity: ScopeGranularity::Location,
+    }
+  }
+
+  /// 表、または表の項を対象にする
+  pub fn table(table: impl Into<String>, table_row: Option<String>) -> Self {
+    TargetRef {
+      law: None,
+      provision: table.into(),
+      kind: TargetKind::Table,
+      table_row,
+      granularity: ScopeGranularity::Location,
+    }
+  }
+
+  /// 対象の法令の名前を付ける
+  pub fn with_law(self, law: impl Into<String>) -> Self {
+    TargetRef {
+      law: Some(law.into()),
+      ..self
+    }
+  }
+
+  /// 対象の範囲の広さを変える
+  pub fn with_granularity(self, granularity: ScopeGranularity) -> Self {
+    TargetRef {
+      granularity,
+      ..self
+    }
+  }
+
+  /// 表を対象にしているかどうか
+  pub fn is_table(&self) -> bool {
+    matches!(self.kind, TargetKind::Table)
+  }
+}
+
+/// 読み替え前の語と、その語の対象となる規定
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ScopedWord {
+  /// 対象となる規定（書かれていない場合は`None`）
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub scope: Option<TargetRef>,
+  /// 読み替え前の語
+  pub word: String,
+}
+
+/// 規定を表す文字列の始まり
+const PROVISION_HEADS: &[&str] = &["第", "同", "前", "次", "附則", "別表", "本"];
+
+/// 対象を表す文字列の手前にある区切りの語
+const SCOPE_BOUNDARIES: &[&str] = &["、", "。", "において"];
+
+/// 表の番号に使われる文字
+const TABLE_NUMBER_CHARS: &str = "第〇一二三四五六七八九十百千";
+
+/// 鉤括弧の手前にある「〜中」の文字列から、読み替えの対象となる規定を取り出す
+/// 「この場合において、同条第一項中」のように、前に別の文言があってもよい
+pub fn parse_target_ref(scope: &str) -> Option<TargetRef> {
+  let clause = scope.strip_suffix('中')?;
+  let (clause, granularity) = match clause.strip_suffix("の規定") {
+    Some(clause) => (clause, ScopeGranularity::WholeProvision),
+    None => (clause, ScopeGranularity::Location),
+  };
+  let start = SCOPE_BOUNDARIES
+    .iter()
+    .filter_map(|boundary| clause.rfind(boundary).map(|i| i + boundary.len()))
+    .max()
+    .unwrap_or(0);
+  // 「、及び同項第二号中」のように、前の語との間の接続詞が付いていることがある
+  let clause = &clause[start..];
+  let clause = CONJUNCTIONS
+    .iter()
+    .find_map(|conjunction| clause.strip_prefix(conjunction))
+    .unwrap_or(clause);
+  let (law, provision) = split_law_name(clause);
+  if !PROVISION_HEADS
+    .iter()
+    .any(|head| provision.starts_with(head))
+  {
+    return None;
+  }
+  let target = if let Some(row_scope) = provision.strip_suffix("の項") {
+    let table_end = table_end(row_scope)?;
+    let table_row = row_scope[table_end..].strip_prefix('の')?;
+    if table_row.is_empty() {
+      return None;
+    }
+    TargetRef::table(&row_scope[..table_end], Some(table_row.to_string()))
+  } else if table_end(provision) == Some(provision.len()) {
+    TargetRef::table(provision, None)
+  } else {
+    TargetRef::provision(provision)
+  };
+  let target = target.with_granularity(granularity);
+  Some(match law {
+    Some(law) => target.with_law(law),
+    None => target,
+  })
+}
+
+/// 先頭に法令の名前があれば、法令の名前とそれ以降の規定に分ける
+fn split_law_name(clause: &str) -> (Option<&str>, &str) {
+  for (i, _) in clause.char_indices().skip(1) {
+    let (law, provision) = clause.split_at(i);
+    let law = strip_trailing_paren(law);
+    if LAW_NAME_SUFFIXES.iter().any(|suffix| law.ends_with(suffix))
+      && PROVISION_HEADS
+        .iter()
+        .any(|head| provision.starts_with(head))
+    {
+      return (Some(law), provision);
+    }
+  }
+  (None, clause)
+}
+
+/// 「別表第二」「同表」のような表を表す部分の終わりの位置
+fn table_end(s: &str) -> Option<usize> {
+  let i = s.find('表')? + '表'.len_utf8();
+  let len = s[i..]
+    .chars()
+    .take_while(|c| TABLE_NUMBER_CHARS.contains(*c))
+    .map(|c| c.len_utf8())
+    .sum::<usize>();
+  Some(i + len)
+}
+
+#[test]
+fn check_parse_target_ref() {
+  assert_eq!(
+    Some(TargetRef::provision("第八百五十一条第四号")),
+    parse_target_ref("この場合において、第八百五十一条第四号中")
+  );
+  assert_eq!(
+    Some(TargetRef::provision("第二十七条及び第二十八条").with_law("徴収法施行規則")),
+    parse_target_ref("この場合において、徴収法施行規則第二十七条及び第二十八条中")
+  );
+  assert_eq!(
+    Some(TargetRef::table("別表第二", Some("特定疾病".to_string())).with_law("同法")),
+    parse_target_ref("この場合において、同法別表第二の特定疾病の項中")
+  );
+  assert_eq!(
+    Some(TargetRef::table("同表", None)),
+    parse_target_ref("同表中")
+  );
+  assert!(parse_target_ref("この場合において、同条中")
+    .unwrap()
+    .law
+    .is_none());
+  assert_eq!(
+    Some(TargetRef::provision("第三条").with_granularity(ScopeGranularity::WholeProvision)),
+    parse_target_ref("この場合において、第三条の規定中")
+  );
+  assert_eq!(
+    ScopeGranularity::Location,
+    parse_target_ref("この場合において、第三条第二項中")
+      .unwrap()
+      .granularity
+  );
+  assert_eq!(
+    Some(TargetRef::provision("同項第二号")),
+    parse_target_ref("、及び同項第二号中")
+  );
+  assert_eq!(None, parse_target_ref("この場合において、"));
+  assert_eq!(None, parse_target_ref("、"));
+}
@@ -0,0 +1,74 @@
+//! テストで使う入力を簡単に作るための関数や、実際の法令にある読み替え規定文
+//!
+//! `test-utils` featureを有効にすると使えるようになる
+
+pub use crate::ArticleExt;
+use crate::YomikaeSource;
+use jplaw_text::{Article, LawContents, LawText};
+
+/// 一つの組の読み替え規定文
+pub const SENTENCE_SINGLE: &str = "この場合において、第八百五十一条第四号中「被後見人を代表する」とあるのは、「被保佐人を代表し、又は被保佐人がこれをすることに同意する」と読み替えるものとする。";
+
+/// 複数の組が「と、」でつながっている読み替え規定文
+pub const SENTENCE_MULTIPLE: &str = "この場合において、同条第一項中「法第六十九条の三十三第一項」とあるのは「令第三十七条の七第一項」と、同項第五号中「前条」とあるのは「第百十三条の三十七」と読み替えるものとする。";
+
+/// 読み替え前の語が「とあり、及び」で並んでいる読み替え規定文
+pub const SENTENCE_PARALLEL: &str = "この場合において、同項中「それぞれ同項各号に定める者」とあり、及び同項第二号中「その者」とあるのは、「都道府県の教育委員会」と読み替えるものとする。";
+
+/// 読み替えの表を使う読み替え規定文
+pub const SENTENCE_TABLE: &str = "この場合において、次の表の上欄に掲げる同条の規定中同表の中欄に掲げる字句は、それぞれ同表の下欄に掲げる字句と読み替えるものとする。";
+
+/// テスト用の条項（本則の「test」条）
+pub fn test_article() -> Article {
+  Article::article("test")
+}
+
+/// 表の各行の欄の文字列から表を作る
+///
+/// # Panics
+/// 表を作れなかった場合（[`crate::SourceError::TableContents`]）
+pub fn table_contents(rows: &[&[&str]]) -> LawContents {
+  rows.to_law_contents().expect("table contents")
+}
+
+/// [`LawText`]を作る
+#[derive(Debug, Clone)]
+pub struct LawTextBuilder {
+  article: Article,
+  contents: LawContents,
+}
+
+impl LawTextBuilder {
+  /// 文の[`LawText`]を作り始める
+  pub fn text(text: impl Into<String>) -> Self {
+    LawTextBuilder {
+      article: test_article(),
+      contents: LawContents::Text(text.into()),
+    }
+  }
+
+  /// 表の[`LawText`]を作り始める
+  pub fn table(rows: &[&[&str]]) -> Self {
+    LawTextBuilder {
+      article: test_article(),
+      contents: table_contents(rows),
+    }
+  }
+
+  /// 条項を指定する
+  pub fn article(self, article: Article) -> Self {
+    LawTextBuilder { article, ..self }
+  }
+
+  pub fn build(self) -> LawText {
+    LawText {
+      article_info: self.article,
+      contents: self.contents,
+    }
+  }
+}
+
+/// 文から、条項を[`test_article`]にした[`LawText`]を作る
+pub fn law_text(text: &str) -> LawText {
+  LawTextBuilder::text(text).build()
+}
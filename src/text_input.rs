@@ -0,0 +1,191 @@
+//! 法令XMLではなく、条文をそのままの文字列で並べたTSV・CSVのファイルを解析の入力にする
+//!
+//! 一行に一つの条文を、idと本文の二つの欄で書く
+//! idは法律番号と条項を`:`で区切った形（`平成五年法律第八十八号:3`・`平成五年法律第八十八号:3:2`など）で書く
+//! 条の後に`:`で区切って項を書ける。`:`が無い場合はid全体を法律番号とし、条は空にする
+//! 一行目の一つ目の欄が`id`の場合は見出しの行として読み飛ばす
+
+use crate::ArticleExt;
+use jplaw_text::{Article, LawContents, LawText};
+use std::str::FromStr;
+
+/// 入力のファイルの形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextInputFormat {
+  /// タブ区切り（欄の中にタブや改行を含められない）
+  Tsv,
+  /// カンマ区切り（欄を`"`で囲むと、カンマや改行を含められる）
+  Csv,
+}
+
+impl FromStr for TextInputFormat {
+  type Err = String;
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "tsv" => Ok(TextInputFormat::Tsv),
+      "csv" => Ok(TextInputFormat::Csv),
+      _ => Err(format!(
+        "unknown input format: {s} (expected \"tsv\" or \"csv\")"
+      )),
+    }
+  }
+}
+
+/// 入力のファイルの一行
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextRecord {
+  /// 行の番号（1始まり、見出しの行を含む）
+  pub line: usize,
+  /// id
+  pub id: String,
+  /// 条文
+  pub text: String,
+}
+
+impl TextRecord {
+  /// idから法律番号と条項を取り出す
+  pub fn num_and_article(&self) -> (String, Article) {
+    let mut parts = self.id.splitn(3, ':');
+    let num = parts.next().unwrap_or_default().to_string();
+    let article = Article::article(parts.next().unwrap_or_default());
+    match parts.next() {
+      Some(paragraph) => (num, article.with_paragraph(paragraph)),
+      None => (num, article),
+    }
+  }
+
+  /// jplaw_textの条文にする
+  pub fn to_law_text(&self) -> LawText {
+    let (_, article) = self.num_and_article();
+    LawText {
+      article_info: article,
+      contents: LawContents::Text(self.text.clone()),
+    }
+  }
+}
+
+/// CSVの行を欄に分ける
+/// `"`で囲まれた欄の中のカンマと改行は欄の区切りとせず、`""`は`"`一つにする
+/// 返す行の番号は、その行が始まる行の番号
+fn split_csv(input: &str) -> Result<Vec<(usize, Vec<String>)>, String> {
+  let mut rows = Vec::new();
+  let mut fields = Vec::new();
+  let mut field = String::new();
+  let mut line = 1;
+  let mut row_line = 1;
+  let mut is_quoted = false;
+  let mut chars = input.chars().peekable();
+  while let Some(c) = chars.next() {
+    match (c, is_quoted) {
+      ('"', true) if chars.peek() == Some(&'"') => {
+        chars.next();
+        field.push('"');
+      }
+      ('"', true) => is_quoted = false,
+      ('"', false) if field.is_empty() => is_quoted = true,
+      (',', false) => fields.push(std::mem::take(&mut field)),
+      ('\r', false) if chars.peek() == Some(&'\n') => (),
+      ('\n', false) => {
+        fields.push(std::mem::take(&mut field));
+        rows.push((row_line, std::mem::take(&mut fields)));
+        line += 1;
+        row_line = line;
+      }
+      ('\n', true) => {
+        field.push(c);
+        line += 1;
+      }
+      _ => field.push(c),
+    }
+  }
+  if is_quoted {
+    return Err(format!("unterminated quoted field at line {row_line}"));
+  }
+  if !field.is_empty() || !fields.is_empty() {
+    fields.push(field);
+    rows.push((row_line, fields));
+  }
+  Ok(rows)
+}
+
+/// TSVの行を欄に分ける
+fn split_tsv(input: &str) -> Vec<(usize, Vec<String>)> {
+  input
+    .lines()
+    .enumerate()
+    .map(|(i, line)| (i + 1, line.split('\t').map(|s| s.to_string()).collect()))
+    .collect()
+}
+
+/// TSV・CSVの文字列を読み、空でない行をidと条文の組にする
+/// 欄が二つ無い行がある場合はエラーを返す
+pub fn parse_text_input(input: &str, format: TextInputFormat) -> Result<Vec<TextRecord>, String> {
+  let rows = match format {
+    TextInputFormat::Tsv => split_tsv(input),
+    TextInputFormat::Csv => split_csv(input)?,
+  };
+  let mut records = Vec::new();
+  for (i, (line, fields)) in rows.into_iter().enumerate() {
+    if fields.iter().all(|field| field.trim().is_empty()) {
+      continue;
+    }
+    if i == 0 && fields.first().map(|field| field.trim()) == Some("id") {
+      continue;
+    }
+    match &fields[..] {
+      [id, text, ..] => records.push(TextRecord {
+        line,
+        id: id.trim().to_string(),
+        text: text.trim().to_string(),
+      }),
+      _ => return Err(format!("expected id and text columns at line {line}")),
+    }
+  }
+  Ok(records)
+}
+
+/// 行を法律番号ごとにまとめ、法律番号が最初に出てきた順に、その法令の条文のリストにする
+pub fn group_by_num(records: &[TextRecord]) -> Vec<(String, Vec<LawText>)> {
+  let mut law_lst: Vec<(String, Vec<LawText>)> = Vec::new();
+  for record in records.iter() {
+    let (num, _) = record.num_and_article();
+    let law_text = record.to_law_text();
+    match law_lst.iter_mut().find(|(n, _)| *n == num) {
+      Some((_, law_text_lst)) => law_text_lst.push(law_text),
+      None => law_lst.push((num, vec![law_text])),
+    }
+  }
+  law_lst
+}
+
+#[test]
+fn check_parse_text_input() {
+  let tsv = "id\ttext\n平成五年法律第八十八号:3\t前条中「前項」とあるのは「第二項」と読み替える。\n\n平成五年法律第八十八号:4:2\t本文\nother\t本文\n";
+  let records = parse_text_input(tsv, TextInputFormat::Tsv).unwrap();
+  assert_eq!(3, records.len());
+  assert_eq!(2, records[0].line);
+  assert_eq!(
+    (
+      "平成五年法律第八十八号".to_string(),
+      Article::article("4").with_paragraph("2")
+    ),
+    records[1].num_and_article()
+  );
+  assert_eq!(
+    ("other".to_string(), Article::article("")),
+    records[2].num_and_article()
+  );
+  let law_lst = group_by_num(&records);
+  assert_eq!(2, law_lst.len());
+  assert_eq!(2, law_lst[0].1.len());
+  let csv = "a:1,\"前条中「前項」とあるのは、\"\"第二項\"\"と\n読み替える。\"\r\nb:2,本文\n";
+  let records = parse_text_input(csv, TextInputFormat::Csv).unwrap();
+  assert_eq!(2, records.len());
+  assert_eq!(
+    "前条中「前項」とあるのは、\"第二項\"と\n読み替える。",
+    records[0].text
+  );
+  assert_eq!(3, records[1].line);
+  assert!(parse_text_input("a:1\n", TextInputFormat::Tsv).is_err());
+  assert!(parse_text_input("a:1,\"本文\n", TextInputFormat::Csv).is_err());
+}
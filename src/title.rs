@@ -0,0 +1,95 @@
+//! 解析結果とエラーに法令名を付ける
+//!
+//! 法律番号だけでは何の法令か分からず、法令名を知るためにインデックス情報をもう一度読む必要があるため、
+//! インデックス情報の`name`か法令XMLの`LawTitle`要素から法令名を読み、解析結果とエラーに含められるようにする
+
+use crate::{LawInfo, YomikaeData, YomikaeError};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// インデックス情報のJSONの中で`num`を持つオブジェクトを探し、法律番号と法令名（`name`）の対応を`titles`に加える
+/// すでに同じ法律番号があれば、先に加えたものを残す
+pub fn collect_law_titles(index: &Value, titles: &mut HashMap<String, String>) {
+  match index {
+    Value::Array(lst) => {
+      for v in lst.iter() {
+        collect_law_titles(v, titles);
+      }
+    }
+    Value::Object(map) => match map.get("num").and_then(|num| num.as_str()) {
+      Some(num) => {
+        if let Some(name) = map.get("name").and_then(|name| name.as_str()) {
+          titles
+            .entry(num.to_string())
+            .or_insert_with(|| name.to_string());
+        }
+      }
+      None => {
+        for v in map.values() {
+          collect_law_titles(v, titles);
+        }
+      }
+    },
+    _ => (),
+  }
+}
+
+impl YomikaeData {
+  /// 法令名を記録する
+  pub fn with_title(self, title: impl Into<String>) -> Self {
+    YomikaeData {
+      title: Some(title.into()),
+      ..self
+    }
+  }
+}
+
+impl LawInfo {
+  /// 法令名を記録する
+  pub fn with_title(self, title: impl Into<String>) -> Self {
+    LawInfo {
+      title: Some(title.into()),
+      ..self
+    }
+  }
+}
+
+impl YomikaeError {
+  /// 条文についてのエラーであれば、法令名を記録する
+  /// 法令ファイルについてのエラーはそのまま返す
+  pub fn with_title(self, title: impl Into<String>) -> Self {
+    self.map_law_info(|law_info| law_info.with_title(title))
+  }
+}
+
+#[test]
+fn check_collect_law_titles() {
+  use crate::{test_utils, ArticleExt, LawFileInfo};
+  use jplaw_text::Article;
+  let index = serde_json::json!([
+    {"num": "a", "file": "a.xml", "name": "A法"},
+    {"num": "b", "file": "b.xml"},
+    {"laws": [{"num": "a", "file": "a2.xml", "name": "A法施行令"}]},
+  ]);
+  let mut titles = HashMap::new();
+  collect_law_titles(&index, &mut titles);
+  assert_eq!(1, titles.len());
+  assert_eq!(Some(&"A法".to_string()), titles.get("a"));
+  let yomikae_data = YomikaeData::new("a", Article::article("1"), Vec::new()).with_title("A法");
+  assert_eq!(Some("A法".to_string()), yomikae_data.title);
+  let law_info = LawInfo::new(
+    "a",
+    test_utils::test_article(),
+    test_utils::law_text("本文"),
+  );
+  assert_eq!(
+    YomikaeError::NotFoundYomikae(law_info.clone().with_title("A法")),
+    YomikaeError::NotFoundYomikae(law_info).with_title("A法")
+  );
+  let law_file_info = LawFileInfo {
+    num: "a".to_string(),
+    file: "a.xml".to_string(),
+  };
+  let err = YomikaeError::ReadLawFile(law_file_info, "not found".to_string());
+  assert_eq!(err.clone(), err.with_title("A法"));
+}
@@ -0,0 +1,93 @@
+//! 取り出した読み替え前後の語の括弧の対応を確かめる
+//!
+//! 語の中で丸括弧や鉤括弧の対応が取れていない場合は、多くの場合文の区切り方を誤っている
+
+use crate::{Message, YomikaeConfidence, YomikaeInfo};
+use jplaw_text::Article;
+
+/// 丸括弧と鉤括弧の対応が取れているかどうか
+pub fn is_balanced(word: &str) -> bool {
+  let mut stack = Vec::new();
+  for c in word.chars() {
+    match c {
+      '（' | '「' => stack.push(c),
+      '）' => {
+        if stack.pop() != Some('（') {
+          return false;
+        }
+      }
+      '」' => {
+        if stack.pop() != Some('「') {
+          return false;
+        }
+      }
+      _ => (),
+    }
+  }
+  stack.is_empty()
+}
+
+/// 読み替え前後の語のうち、括弧の対応が取れていないもの
+pub fn unbalanced_words(yomikae_info: &YomikaeInfo) -> Vec<&str> {
+  yomikae_info
+    .before_words
+    .iter()
+    .chain(std::iter::once(&yomikae_info.after_word))
+    .map(|s| s.as_str())
+    .filter(|s| !is_balanced(s))
+    .collect()
+}
+
+/// 括弧の対応が取れていない語があれば、確からしさを[`YomikaeConfidence::Low`]にする
+pub fn validate_yomikae_info(yomikae_info: YomikaeInfo) -> YomikaeInfo {
+  if unbalanced_words(&yomikae_info).is_empty() {
+    yomikae_info
+  } else {
+    YomikaeInfo {
+      confidence: YomikaeConfidence::Low,
+      ..yomikae_info
+    }
+  }
+}
+
+/// 括弧の対応が取れていない語ごとの警告のメッセージ
+pub fn unbalanced_messages(
+  num: &str,
+  article: &Article,
+  yomikae_info_lst: &[YomikaeInfo],
+) -> Vec<Message> {
+  yomikae_info_lst
+    .iter()
+    .flat_map(unbalanced_words)
+    .map(|word| Message::UnbalancedBrackets(num.to_string(), article.clone(), word.to_string()))
+    .collect()
+}
+
+pub(crate) fn validate_yomikae_info_lst(yomikae_info_lst: Vec<YomikaeInfo>) -> Vec<YomikaeInfo> {
+  yomikae_info_lst
+    .into_iter()
+    .map(validate_yomikae_info)
+    .collect()
+}
+
+#[test]
+fn check_validate_yomikae_info() {
+  assert!(is_balanced("前条（第二項を除く。）"));
+  assert!(is_balanced("「前項」という"));
+  assert!(!is_balanced("前条（第二項を除く。"));
+  assert!(!is_balanced("前項」という「"));
+  assert!(!is_balanced("（「前項）」"));
+  let yomikae_info = YomikaeInfo::new(["前条（第二項を除く。"], "次条");
+  assert_eq!(
+    vec!["前条（第二項を除く。"],
+    unbalanced_words(&yomikae_info)
+  );
+  assert_eq!(
+    YomikaeConfidence::Low,
+    validate_yomikae_info(yomikae_info).confidence
+  );
+  assert_eq!(
+    YomikaeConfidence::High,
+    validate_yomikae_info(YomikaeInfo::new(["前条"], "次条")).confidence
+  );
+}
@@ -0,0 +1,106 @@
+//! 解析の途中で、取り出した読み替えの組や警告・エラーを一つずつ受け取るためのAPI
+//!
+//! 解析結果を全てリストにためずに、独自の保存先へ順に書き出したい場合に使う
+
+use crate::validate::unbalanced_messages;
+use crate::{parse_yomikae_sentences, select_yomikae_law_text, Message, YomikaeError, YomikaeInfo};
+use jplaw_text::{Article, LawContents, LawText};
+
+/// 解析の途中で起きたことを受け取る
+///
+/// 全てのメソッドには何もしない既定の実装がある
+/// `on_table_row`は既定では`on_pair`を呼び出す
+pub trait YomikaeVisitor {
+  /// 読み替え規定文から読み替えの組が一つ取り出された
+  fn on_pair(&mut self, _num: &str, _article: &Article, _yomikae_info: YomikaeInfo) {}
+  /// 読み替えの表の`row_index`番目（0始まり）の行から読み替えの組が取り出された
+  fn on_table_row(
+    &mut self,
+    num: &str,
+    article: &Article,
+    _row_index: usize,
+    yomikae_info: YomikaeInfo,
+  ) {
+    self.on_pair(num, article, yomikae_info)
+  }
+  /// 解析は続けられたが、利用者に知らせるべきことがあった
+  fn on_warning(&mut self, _num: &str, _message: Message) {}
+  /// 条文の解析に失敗した
+  fn on_error(&mut self, _err: YomikaeError) {}
+}
+
+/// 一つの条文を一文ずつ解析して、その結果を`visitor`に渡す
+pub fn visit_law_text<V: YomikaeVisitor + ?Sized>(law_text: &LawText, num: &str, visitor: &mut V) {
+  let article = &law_text.article_info;
+  for res in parse_yomikae_sentences(law_text, num) {
+    match res {
+      Ok(yomikae_info_lst) => {
+        for message in unbalanced_messages(num, article, &yomikae_info_lst) {
+          visitor.on_warning(num, message);
+        }
+        match &law_text.contents {
+          LawContents::Text(_) => {
+            for yomikae_info in yomikae_info_lst {
+              visitor.on_pair(num, article, yomikae_info);
+            }
+          }
+          LawContents::Table(_) => {
+            for (row_index, yomikae_info) in yomikae_info_lst.into_iter().enumerate() {
+              visitor.on_table_row(num, article, row_index, yomikae_info);
+            }
+          }
+        }
+      }
+      Err(err) => visitor.on_error(err),
+    }
+  }
+}
+
+/// 法令の条文のリストから読み替え規定文と読み替えの表を取り出して解析し、その結果を`visitor`に渡す
+pub fn visit_law_text_lst<V: YomikaeVisitor + ?Sized>(
+  law_text_lst: Vec<LawText>,
+  num: &str,
+  visitor: &mut V,
+) {
+  let (yomikae_law_text_lst, message_lst) = select_yomikae_law_text(law_text_lst);
+  for message in message_lst {
+    visitor.on_warning(num, message);
+  }
+  for law_text in yomikae_law_text_lst.iter() {
+    visit_law_text(law_text, num, visitor);
+  }
+}
+
+#[test]
+fn check_visit_law_text_lst() {
+  use crate::test_utils;
+
+  #[derive(Default)]
+  struct Events(Vec<String>);
+
+  impl YomikaeVisitor for Events {
+    fn on_pair(&mut self, _num: &str, _article: &Article, yomikae_info: YomikaeInfo) {
+      self.0.push(format!("pair:{}", yomikae_info.after_word));
+    }
+    fn on_warning(&mut self, _num: &str, _message: Message) {
+      self.0.push("warning".to_string());
+    }
+    fn on_error(&mut self, _err: YomikaeError) {
+      self.0.push("error".to_string());
+    }
+  }
+
+  let law_text_lst = vec![
+    test_utils::law_text(
+      "この場合において、前条中「前項」とあるのは「第二項」と読み替えるものとする。",
+    ),
+    test_utils::law_text(test_utils::SENTENCE_TABLE),
+    test_utils::LawTextBuilder::table(&[&["第二条", "前条", "第一条"]]).build(),
+    test_utils::law_text(
+      "この場合において、前条中「前項」」とあるのは「第二項」と読み替えるものとする。",
+    ),
+  ];
+  let mut events = Events::default();
+  visit_law_text_lst(law_text_lst, "test", &mut events);
+  assert_eq!(vec!["pair:第二項", "pair:第一条", "error"], events.0);
+}
@@ -0,0 +1,51 @@
+//! JavaScriptから読み替え規定文や改め文を解析するためのwasm-bindgenのAPI
+//!
+//! `wasm` featureを有効にして`wasm32-unknown-unknown`向けにビルドすると使えるようになる
+//!
+//! ```sh
+//! cargo build --lib --target wasm32-unknown-unknown --no-default-features --features wasm
+//! ```
+
+use crate::{kaiseibun::parse_kaiseibun, parse_yomikae, ArticleExt};
+use jplaw_text::{Article, LawContents, LawText};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+fn to_js_value<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+  serde_wasm_bindgen::to_value(value).map_err(JsValue::from)
+}
+
+/// 条項が省略された場合は空の条にする
+fn law_text(text: String, article: JsValue) -> Result<LawText, JsValue> {
+  let article = if article.is_undefined() || article.is_null() {
+    Article::article("")
+  } else {
+    serde_wasm_bindgen::from_value(article)?
+  };
+  Ok(LawText {
+    article_info: article,
+    contents: LawContents::Text(text),
+  })
+}
+
+/// 読み替え規定文を解析して、読み替え前後の語の組（`YomikaeInfo`）の配列を返す
+/// 解析に失敗した場合は`YomikaeError`を投げる
+#[wasm_bindgen(js_name = parseYomikae)]
+pub fn parse_yomikae_js(text: String, num: String, article: JsValue) -> Result<JsValue, JsValue> {
+  let law_text = law_text(text, article)?;
+  match parse_yomikae(&law_text, &num, &law_text.article_info) {
+    Ok(yomikae_info_lst) => to_js_value(&yomikae_info_lst),
+    Err(err) => Err(to_js_value(&err)?),
+  }
+}
+
+/// 改め文を解析して、改正の内容（`KaiseiInfo`）の配列を返す
+/// 解析に失敗した場合は`YomikaeError`を投げる
+#[wasm_bindgen(js_name = parseKaiseibun)]
+pub fn parse_kaiseibun_js(text: String, num: String, article: JsValue) -> Result<JsValue, JsValue> {
+  let law_text = law_text(text, article)?;
+  match parse_kaiseibun(&law_text, &num, &law_text.article_info) {
+    Ok(kaisei_info_lst) => to_js_value(&kaisei_info_lst),
+    Err(err) => Err(to_js_value(&err)?),
+  }
+}
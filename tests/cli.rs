@@ -0,0 +1,97 @@
+//! 同梱した法令XMLに対してCLIを実行し、出力されたJSONを確かめる
+//!
+//! 文の分類・表の組み合わせ・読み替え文の解析を通して動かしたときの結果を確かめるためのもの
+//! `tests/fixtures/law_xml`の民法（`129AC0000000089.xml`）は、e-Gov法令検索の法令XMLから第八百七十六条の三だけを抜き出したもの
+//! 表による読み替えや鉤括弧の対応が取れていない文を含む法令は、実際の法令XMLから抜き出して`expected.json`を実行結果から作るまで含めない
+
+#![cfg(feature = "cli")]
+
+mod common;
+
+use common::{fixtures_dir, run_cli};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+/// 同梱した法令XMLに対してCLIを実行し、解析結果とエラーのJSONを返す
+fn run_cli_json(name: &str, extra_args: &[&str]) -> (Value, Value) {
+  let (output, error_output) = run_cli(name, extra_args);
+  let read_json =
+    |path: &Path| -> Value { serde_json::from_slice(&fs::read(path).unwrap()).unwrap() };
+  (read_json(&output), read_json(&error_output))
+}
+
+/// 条項やハッシュ値などを除き、法律番号と読み替え前後の語だけを取り出して並べる
+fn project_data(data: &Value) -> Vec<Value> {
+  let mut lst = data
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|yomikae_data| {
+      let pairs = yomikae_data["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|yomikae_info| {
+          json!({
+            "before_words": yomikae_info["before_words"],
+            "after_word": yomikae_info["after_word"],
+            "table": !yomikae_info["table_cell"].is_null(),
+          })
+        })
+        .collect::<Vec<_>>();
+      json!({
+        "num": yomikae_data["num"],
+        "pairs": pairs,
+      })
+    })
+    .collect::<Vec<_>>();
+  lst.sort_by_key(|v| (v["num"].to_string(), v["pairs"].to_string()));
+  lst
+}
+
+/// エラーの種類・法律番号・条だけを取り出して並べる
+fn project_errors(errors: &Value) -> Vec<Value> {
+  let mut lst = errors
+    .as_array()
+    .unwrap()
+    .iter()
+    .filter_map(|err| err.as_object()?.iter().next())
+    .map(|(kind, law_info)| {
+      json!({
+        "kind": kind,
+        "num": law_info["num"],
+        "article": law_info["article"]["article"],
+      })
+    })
+    .collect::<Vec<_>>();
+  lst.sort_by_key(|v| {
+    (
+      v["num"].to_string(),
+      v["article"].to_string(),
+      v["kind"].to_string(),
+    )
+  });
+  lst
+}
+
+#[test]
+fn check_cli_fixtures() {
+  let expected: Value =
+    serde_json::from_slice(&fs::read(fixtures_dir().join("expected.json")).unwrap()).unwrap();
+  let (data, errors) = run_cli_json("fixtures", &[]);
+  assert_eq!(expected["data"].as_array().unwrap(), &project_data(&data));
+  assert_eq!(
+    expected["errors"].as_array().unwrap(),
+    &project_errors(&errors)
+  );
+}
+
+#[test]
+fn check_cli_fixtures_with_title() {
+  let (data, _) = run_cli_json("fixtures_with_title", &["--include-title"]);
+  assert!(!data.as_array().unwrap().is_empty());
+  for yomikae_data in data.as_array().unwrap() {
+    assert_eq!("民法", yomikae_data["title"]);
+  }
+}
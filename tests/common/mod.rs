@@ -0,0 +1,33 @@
+//! 結合テストで共通して使う、同梱した法令XMLに対してCLIを実行する関数
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub fn fixtures_dir() -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("tests")
+    .join("fixtures")
+}
+
+/// `name`という名前の出力先のディレクトリを作ってCLIを実行し、解析結果とエラーを書き出したJSONファイルへのpathを返す
+pub fn run_cli(name: &str, extra_args: &[&str]) -> (PathBuf, PathBuf) {
+  let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+  fs::create_dir_all(&out_dir).unwrap();
+  let output = out_dir.join("output.json");
+  let error_output = out_dir.join("error.json");
+  let status = Command::new(env!("CARGO_BIN_EXE_analysis_yomikae"))
+    .arg("-o")
+    .arg(&output)
+    .arg("-e")
+    .arg(&error_output)
+    .arg("-w")
+    .arg(fixtures_dir().join("law_xml"))
+    .arg("-i")
+    .arg(fixtures_dir().join("index.json"))
+    .args(extra_args)
+    .status()
+    .unwrap();
+  assert!(status.success());
+  (output, error_output)
+}